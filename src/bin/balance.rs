@@ -0,0 +1,132 @@
+// Plays the economy headless with the auto-buyer and writes a CSV timeline, for comparing balance changes.
+//
+//   cargo run --bin balance -- --minutes 90 --sample 10 --policy roi --out timeline.csv
+//
+// Every row holds the resources on hand, event rows also name the unlock reached at that time.
+use std::io::Write;
+use turbo_incremental::*;
+
+const TICKS_PER_SEC: usize = 60;
+
+const RESOURCES: [Resources; 5] = [Resources::Research, Resources::Drones, Resources::Metals, Resources::Power, Resources::Prestige];
+
+struct Options {
+    minutes: usize,
+    sample: usize,
+    seed: u64,
+    // Prestige index of the sector to play, 0 is the home map
+    sector: u32,
+    // Of each resource but prestige
+    start: u64,
+    scanning: bool,
+    policy: BuyPolicy,
+    // Percent kept back by the reserve policy
+    reserve: u32,
+    out: Option<String>,
+}
+
+impl Options {
+    fn parse() -> Result<Self, String> {
+        let mut options = Options { minutes: 120, sample: 10, seed: 0, sector: 0, start: 0, scanning: true, policy: BuyPolicy::Cheapest, reserve: RESERVE_STEPS[1], out: None };
+        let mut args = std::env::args().skip(1);
+        while let Some(arg) = args.next() {
+            let mut value = || args.next().ok_or(format!("{} needs a value", arg));
+            match arg.as_str() {
+                "--minutes" => options.minutes = value()?.parse().map_err(|err| format!("--minutes: {}", err))?,
+                "--sample" => options.sample = value()?.parse().map_err(|err| format!("--sample: {}", err))?,
+                "--seed" => options.seed = value()?.parse().map_err(|err| format!("--seed: {}", err))?,
+                "--sector" => options.sector = value()?.parse().map_err(|err| format!("--sector: {}", err))?,
+                "--start" => options.start = value()?.parse().map_err(|err| format!("--start: {}", err))?,
+                "--policy" => options.policy = match value()?.as_str() {
+                    "cheapest" => BuyPolicy::Cheapest,
+                    "roi" => BuyPolicy::BestRoi,
+                    "reserve" => BuyPolicy::Reserve,
+                    other => return Err(format!("--policy: unknown policy {}, expected cheapest, roi or reserve", other)),
+                },
+                "--reserve" => options.reserve = value()?.parse().map_err(|err| format!("--reserve: {}", err))?,
+                "--out" => options.out = Some(value()?),
+                // Leave RESEARCH to the drones instead of holding the manual scan
+                "--no-scan" => options.scanning = false,
+                _ => return Err(format!("unknown argument {}\nusage: balance [--minutes N] [--sample SECS] [--seed N] [--sector N] [--start AMOUNT] [--policy cheapest|roi|reserve] [--reserve PERCENT] [--out FILE] [--no-scan]", arg)),
+            }
+        }
+        options.sample = options.sample.max(1);
+        Ok(options)
+    }
+}
+
+// Event names are Debug output, quoted so a variant with several fields keeps the columns lined up
+fn quoted(field: &str) -> String {
+    format!("\"{}\"", field.replace('"', "\"\""))
+}
+
+fn row(out: &mut impl Write, sim: &Simulation, event: &str, bought: usize) -> std::io::Result<()> {
+    let secs = sim.tick as f64 / TICKS_PER_SEC as f64;
+    let amounts: Vec<String> = RESOURCES.iter().map(|resource| sim.player.amount(resource).to_f64().to_string()).collect();
+    writeln!(out, "{:.1},{},{},{}", secs, quoted(event), amounts.join(","), bought)
+}
+
+fn run(options: &Options, out: &mut impl Write) -> std::io::Result<()> {
+    let mut sim = Simulation::new(options.seed, Sector::generate(options.sector, options.seed), Player::default());
+    // A new game starts with a bankroll for testing, the bot starts from --start instead
+    sim.player.resources = Wallet::new();
+    for resource in RESOURCES.iter().filter(|resource| **resource != Resources::Prestige) {
+        sim.player.resources.deposit(resource.clone(), BigNum::from(options.start), Source::Offline);
+    }
+    // Every station, the jump included, so the run ends at the first prestige
+    let mut buyer = AutoBuyer { unlocked: true, policy: options.policy, reserve: options.reserve.min(100), enabled: vec![true; StationId::ALL.len()], ..AutoBuyer::new() };
+    let mut bought = 0;
+    let mut reached = vec![];
+
+    writeln!(out, "seconds,event,research,drones,metals,power,prestige,upgrades")?;
+    row(out, &sim, "", bought)?;
+    let ticks = options.minutes * 60 * TICKS_PER_SEC;
+    while sim.tick < ticks {
+        // Nobody is there to read the cutscenes
+        sim.event_manager.skip_dialogue();
+
+        let mut input = SimInput { scanning: options.scanning, commands: vec![] };
+        if buyer.buy(&sim, &mut input) {
+            bought += 1;
+        }
+
+        for event in sim.step(&input) {
+            let name = format!("{:?}", event);
+            row(out, &sim, &name, bought)?;
+            reached.push((name, sim.tick));
+        }
+        for index in sim.take_unlocked() {
+            reached.push((ACHIEVEMENTS[index].name.to_string(), sim.tick));
+        }
+        if reached.iter().any(|(name, _)| name == "Prestige") {
+            break;
+        }
+        if sim.tick.is_multiple_of(options.sample * TICKS_PER_SEC) {
+            row(out, &sim, "", bought)?;
+        }
+    }
+
+    for (name, tick) in reached.iter() {
+        eprintln!("{:<24} {}", name, Numbers::format_duration((tick / TICKS_PER_SEC) as u64));
+    }
+    eprintln!("{:<24} {}", "upgrades bought", bought);
+    Ok(())
+}
+
+fn main() {
+    let options = match Options::parse() {
+        Ok(options) => options,
+        Err(err) => {
+            eprintln!("{}", err);
+            std::process::exit(2);
+        }
+    };
+    let result = match &options.out {
+        Some(path) => std::fs::File::create(path).and_then(|file| run(&options, &mut std::io::BufWriter::new(file))),
+        None => run(&options, &mut std::io::stdout().lock()),
+    };
+    if let Err(err) = result {
+        eprintln!("balance: {}", err);
+        std::process::exit(1);
+    }
+}
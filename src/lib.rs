@@ -2,47 +2,82 @@ mod model;
 use std::fmt::Error;
 
 pub use model::*;
+// Replay aware input, instead of the engine's
+pub use model::{gamepad, pointer, Pointer};
 
 turbo::init!(
     struct GameState {
-        player: Player,
+        sim: Simulation,
         vignette: Vignette,
-        event_manager: EventManager,
-        exoplanet: Exoplanet,
-        drone_depot: DroneDepot,
         asteroid_field: AsteroidField,
-        asteroid_mines: AsteroidMines,
         nebula_storm: NebulaStorm,
-        power_plant: PowerPlant,
-        jumpgate: Jumpgate,
-        research_complex: ResearchComplex,
+        offline: OfflineProgress,
+        saved_at: u64,
+        auto_buyer: AutoBuyer,
     } = GameState::load_local()
 );
 
 impl GameState {
-    pub fn new(prestiged: bool, prestige_earned: u64, prestige_prog: u64, prestige_index: u32, avail_upgrades: Vec<Upgrade>) -> Self {
+    pub fn new(mut sim: Simulation) -> Self {
+        // Every run starts from its own seed, logged so it can be given with bug reports
+        log!("run seed {:016x}", sim.rng.seed);
         let mut state = GameState {  
-            player: Player::load(prestiged, prestige_earned, prestige_prog, prestige_index, avail_upgrades),
-            vignette: Vignette::new(),
-            event_manager: EventManager::new(),
-            exoplanet: Exoplanet::load(),
-            drone_depot: DroneDepot::load(),
-            asteroid_field: AsteroidField::new(),
-            asteroid_mines: AsteroidMines::load(),
-            nebula_storm: NebulaStorm::new(),
-            power_plant: PowerPlant::load(),
-            jumpgate: Jumpgate::load(),
-            research_complex: ResearchComplex::load(),
+            vignette: Vignette::new(&sim.sector, &mut sim.rng.cosmetic),
+            asteroid_field: AsteroidField::new(&sim.sector),
+            nebula_storm: NebulaStorm::new(&mut sim.rng.cosmetic),
+            sim,
+            offline: OfflineProgress::new(),
+            saved_at: 0,
+            auto_buyer: AutoBuyer::new(),
         };
         state.vignette.fade = false;
         state.save_local();
         state
     }
 
-    pub fn save_local(&self) {
-        let data = self.try_to_vec();
-        if let Ok(d) = data {
-            let _ = local::save(&d);
+    // A new game, achievements and stats start over too
+    pub fn fresh() -> Self {
+        let seed = GameRng::new_seed();
+        // Rolled before anything is built so stations, fields and cutscenes all land in the new sector
+        GameState::new(Simulation::new(seed, Sector::generate(0, seed), Player::default()))
+    }
+
+    // Rebuild the runtime state around saved progression
+    pub fn restore(save: &SaveData) -> Self {
+        let mut sim = save.restore();
+        GameState {
+            vignette: Vignette::new(&sim.sector, &mut sim.rng.cosmetic),
+            asteroid_field: AsteroidField::new(&sim.sector),
+            nebula_storm: NebulaStorm::new(&mut sim.rng.cosmetic),
+            sim,
+            offline: save.offline.clone(),
+            saved_at: save.saved_at,
+            auto_buyer: save.auto_buyer.clone(),
+        }
+    }
+
+    pub fn save_local(&mut self) {
+        self.write_local(false);
+    }
+
+    // Autosaves also go into the rolling backups
+    pub fn autosave(&mut self) {
+        self.write_local(true);
+    }
+
+    fn write_local(&mut self, autosave: bool) {
+        // A replay runs someone else's session, it must not overwrite the player's save
+        if Replay::is_replaying() {
+            return;
+        }
+        self.saved_at = now_millis();
+        let save = SaveData::capture(&self.sim, &self.offline, self.saved_at, &self.auto_buyer);
+        if let Ok(d) = save.try_to_vec() {
+            SaveSlots::refresh(&save, &d);
+            if autosave {
+                Autosaves::push(&save, &d);
+            }
+            let _ = local::save(&SaveFile::new(d).encode());
         } else {
             log!("error saving");
         }
@@ -50,12 +85,115 @@ impl GameState {
 
     pub fn load_local() -> GameState {
         let data = local::load().unwrap_or_else(|_| vec![]);
-        let mut state = GameState::try_from_slice(&data).unwrap_or_else(|_| GameState::new(false, 0, 0, 0, vec![]));
+        let loaded = SaveFile::decode(&data).and_then(|file| {
+            let save = SaveData::read(&file)?;
+            if let Some(backup) = file.backup {
+                SaveFile::keep_backup(backup);
+            }
+            SaveSlots::keep(file.slots);
+            Autosaves::keep(file.autosaves);
+            Ok(GameState::restore(&save))
+        });
+        let mut state = match loaded {
+            Ok(state) => state,
+            Err(SaveError::Empty) => GameState::fresh(),
+            Err(err) => {
+                // Keep the unreadable save rather than overwrite it with a fresh game
+                log!("could not load save ({}), keeping it as a backup", err);
+                SaveFile::keep_backup(data);
+                GameState::fresh()
+            }
+        };
+        state.vignette.fade = false;
+        state.vignette.fade_prog = 255.;
+        state.catch_up(now_millis());
+        state
+    }
+
+    // Copyable string of the current progress
+    pub fn export(&mut self) -> String {
+        self.saved_at = now_millis();
+        let state = SaveData::capture(&self.sim, &self.offline, self.saved_at, &self.auto_buyer).try_to_vec().unwrap_or_default();
+        SaveFile::new(state).export()
+    }
+
+    // Load an exported save string, an error leaves the running game untouched
+    pub fn import(text: &str) -> Result<GameState, SaveError> {
+        let save = SaveData::read(&SaveFile::import(text)?)?;
+        Ok(GameState::resume(&save))
+    }
+
+    // Switch to another run, it may be older than the one it replaces so there is no offline catch-up
+    fn resume(save: &SaveData) -> GameState {
+        let mut state = GameState::restore(save);
+        state.saved_at = now_millis();
         state.vignette.fade = false;
         state.vignette.fade_prog = 255.;
         state
     }
 
+    // Restart from a snapshot so the recording and its replay begin from the same state
+    pub fn start_recording(&mut self) {
+        let save = SaveData::capture(&self.sim, &self.offline, now_millis(), &self.auto_buyer);
+        *self = GameState::resume(&save);
+        camera::set_xyz(320, 240, 1.);
+        Replay::record(&save);
+    }
+
+    // Save the running game, then play the recording from its starting state
+    pub fn start_replay(&mut self, recording: Recording) -> Result<(), SaveError> {
+        let save = recording.start()?;
+        self.save_local();
+        *self = GameState::resume(&save);
+        camera::set_xyz(320, 240, 1.);
+        Replay::play(recording);
+        Ok(())
+    }
+
+    // Carry out a slot picker or autosave action, returns what to tell the player
+    pub fn use_slot(&mut self, event: &Event) -> Result<String, SaveError> {
+        let message = match event {
+            Event::StoreSlot(index) => {
+                SaveSlots::activate(*index);
+                format!("SAVED TO {}", SLOT_NAMES[*index])
+            }
+            Event::LoadSlot(index) => {
+                *self = GameState::resume(&SaveSlots::load(*index)?);
+                format!("LOADED {}", SLOT_NAMES[*index])
+            }
+            Event::CopySlot(index) => {
+                let copy = SaveSlots::duplicate(*index)?;
+                format!("COPIED {} TO {}", SLOT_NAMES[*index], SLOT_NAMES[copy])
+            }
+            Event::DeleteSlot(index) => {
+                SaveSlots::delete(*index);
+                format!("DELETED {}", SLOT_NAMES[*index])
+            }
+            Event::RestoreAutosave(index) => {
+                *self = GameState::resume(&Autosaves::load(*index)?);
+                "ROLLED BACK TO AUTOSAVE".to_string()
+            }
+            _ => return Ok(String::new()),
+        };
+        self.save_local();
+        Ok(message)
+    }
+
+    // Credit the production drones would have made while the game was closed
+    pub fn catch_up(&mut self, now: u64) {
+        let Some(credited) = self.offline.credited_secs(self.saved_at, now) else {
+            return;
+        };
+        let secs = self.offline.idle_secs(credited, self.sim.achievements.offline_bonus());
+        let mut report = AwayReport::new((now - self.saved_at) / 1000, credited);
+
+        self.sim.idle(secs, &mut report);
+
+        if !report.is_empty() && self.sim.event_manager.dialogue.is_none() {
+            self.sim.event_manager.dialogue = Some(report.dialogue().start());
+        }
+    }
+
 }
 
 // This is where your main game loop code goes
@@ -63,6 +201,12 @@ impl GameState {
 turbo::go! ({
     let mut state = GameState::load();
     let mut sfx = GLOBAL.lock().unwrap();
+
+    // Recorded input replaces the live input, once it runs out go back to the player's save
+    if Replay::begin_frame() {
+        state = GameState::load_local();
+        sfx.notify("REPLAY FINISHED".to_string());
+    }
     
     
     for x in -1..=1 {
@@ -73,57 +217,56 @@ turbo::go! ({
     // text!("pos: ({}, {}), target: ({}, {}), last: ({}, {})", state.player.camera.pos.0, state.player.camera.pos.1, camera::x(), camera::y(), state.player.camera.last_pointer_pos.0, state.player.camera.last_pointer_pos.1; fixed = true, y = 28);
     //rect!(xy = (-320, -200), wh = (1280, 800), border_size = 1, color = 0xffffff00, border_color = 0xffffffff);
 
-    if state.event_manager.dialogue.is_none() {
-        state.player.update(&mut state.event_manager);
+    // Gather this tick's input
+    let mut input = SimInput::default();
+    if state.sim.event_manager.dialogue.is_none() {
+        state.sim.player.update(&mut state.sim.event_manager, &state.sim.sector, &mut input);
     } else {
-        state.player.camera.update_cam(); // Only update the camera
+        state.sim.player.camera.update_cam(); // Only update the camera
     }
-    state.asteroid_field.update();
-    if state.asteroid_mines.unlockable {
-        state.asteroid_field.draw();
-    }
-    state.nebula_storm.update();
-    if state.power_plant.unlockable {
-        state.nebula_storm.draw();
+    let Split { player, event_manager, research, rng, stations, .. } = state.sim.split_stations();
+    for station in stations.into_iter().filter(|station| station.unlockable()) {
+        station.update(player, event_manager, research, rng, &mut input);
     }
 
-    state.exoplanet.update(&mut state.player, &mut state.event_manager);
-    state.exoplanet.draw();
-    if state.asteroid_mines.unlockable {
-        state.asteroid_mines.update(&mut state.player, &mut state.event_manager, &mut state.asteroid_field);
-        state.asteroid_mines.draw();
-    }
-    if state.drone_depot.unlockable {
-        state.drone_depot.update(&mut state.player, &mut state.event_manager);
-        state.drone_depot.draw();
+    state.auto_buyer.update(&state.sim, &mut input);
+
+    // Advance the economy
+    let events = state.sim.step(&input);
+    for rejection in state.sim.rejected.iter() {
+        sfx.notify(rejection.to_string().to_uppercase());
     }
-    if state.power_plant.unlockable {
-        state.power_plant.update(&mut state.player, &mut state.event_manager, &mut state.nebula_storm);
-        state.power_plant.draw();
+
+    // Animate what the simulation produced
+    state.asteroid_field.update(&mut state.sim.rng.cosmetic);
+    if state.sim.asteroid_mines.unlockable {
+        state.asteroid_field.draw();
     }
-    if state.jumpgate.unlockable {
-        state.jumpgate.update(&mut state.player, &mut state.event_manager);
-        state.jumpgate.draw();
+    state.nebula_storm.update(&mut state.sim.rng.cosmetic, &state.sim.sector);
+    if state.sim.power_plant.unlockable {
+        state.nebula_storm.draw();
     }
-    if state.research_complex.unlockable {
-        state.research_complex.update(&mut state.player, &mut state.event_manager);
-        state.research_complex.draw();
+
+    let Split { player, research, sector, rng, stations, .. } = state.sim.split_stations();
+    let mut scene = Scene { player, sector, rng, asteroid_field: &mut state.asteroid_field, nebula_storm: &mut state.nebula_storm };
+    for station in stations.into_iter().filter(|station| station.unlockable()) {
+        station.animate(&mut scene);
+        station.draw(research);
     }
 
     // Event subscribers
     let mut prestige = false;
     let mut reset = false;
     let mut save = false;
+    let mut export = false;
+    let mut import = false;
+    let mut slot_action = None;
+    let mut toggle_recording = Replay::is_recording() && Replay::progress().0 >= MAX_RECORDING_FRAMES;
+    let mut play_replay = false;
 
-    state.event_manager.process_events(|event| {
-        state.player.handle_event(event);
+    for event in events.iter() {
         state.vignette.handle_event(event);
-        state.exoplanet.handle_event(event);
-        state.drone_depot.handle_event(event);
-        state.asteroid_mines.handle_event(event);
-        state.power_plant.handle_event(event);
-        state.jumpgate.handle_event(event);
-        state.research_complex.handle_event(event);
+        state.auto_buyer.handle_event(event);
         match event {
             Event::ResetGame => {
                 reset = true;
@@ -131,50 +274,123 @@ turbo::go! ({
             Event::SaveGame => {
                 save = true;
             }
+            Event::ExportSave => {
+                export = true;
+            }
+            Event::ImportSave => {
+                import = true;
+            }
+            Event::ToggleRecording => {
+                toggle_recording = true;
+            }
+            Event::PlayReplay => {
+                play_replay = true;
+            }
+            Event::StoreSlot(_) | Event::LoadSlot(_) | Event::CopySlot(_) | Event::DeleteSlot(_) | Event::RestoreAutosave(_) => {
+                slot_action = Some(event.clone());
+            }
             Event::EndGame => {
                 state.vignette.fade = true;
                 prestige = true;
             }
+            Event::ProjectComplete(station, id) => {
+                if let Some(upgrade) = station_upgrades(*station).get(*id) {
+                    sfx.notify(format!("{} COMPLETE", upgrade.name));
+                }
+            }
             _ => {}
         }
-    });
+    }
 
     if prestige {
-        let leftover = state.player.resources
-            .iter()
-            .find(|(res, _)| *res == Resources::Prestige)
-            .map(|(_, x)| *x)
-            .unwrap_or(0);
-        state = GameState::new(true, leftover + state.player.prestige_earned, state.player.prestige_prog, state.player.prestige_index, state.player.avail_upgrades.clone());
+        let auto_buyer = state.auto_buyer.next_run();
+        state = GameState::new(state.sim.next_run(GameRng::new_seed()));
+        state.auto_buyer = auto_buyer;
+        state.save_local();
     }
     if reset {
-        state = GameState::new(false, 0, 0, 0, vec![]);
+        state = GameState::fresh();
     }
     if save {
         state.save_local();
     }
+    if export {
+        let text = state.export();
+        Clipboard::copy(&text);
+        sfx.show_transfer(Transfer::ExportSave(text));
+        sfx.notify("SAVE STRING COPIED".to_string());
+    }
+    if import {
+        match GameState::import(&Clipboard::paste()) {
+            Ok(imported) => {
+                state = imported;
+                state.save_local();
+                sfx.transfer = None;
+                sfx.notify("SAVE IMPORTED".to_string());
+            }
+            Err(err) => {
+                log!("could not import save ({})", err);
+                sfx.notify(format!("IMPORT FAILED: {}", err).to_uppercase());
+            }
+        }
+    }
+    // Clicks on these inside a replay are part of the recording, not requests to record or replay again
+    if toggle_recording && !Replay::is_replaying() {
+        if let Some(recording) = Replay::stop() {
+            let text = recording.export();
+            Clipboard::copy(&text);
+            sfx.show_transfer(Transfer::ExportReplay(text));
+            sfx.notify(format!("RECORDED {} FRAMES, COPIED", recording.frames.len()));
+        } else {
+            state.start_recording();
+            sfx.notify("RECORDING INPUT".to_string());
+            sfx.options = false;
+        }
+    }
+    if play_replay && !Replay::is_replaying() {
+        let started = Recording::import(&Clipboard::paste()).and_then(|recording| state.start_replay(recording));
+        match started {
+            Ok(()) => {
+                sfx.options = false;
+                sfx.transfer = None;
+                sfx.notify("REPLAYING".to_string());
+            }
+            Err(err) => {
+                log!("could not start replay ({})", err);
+                sfx.notify(format!("REPLAY FAILED: {}", err).to_uppercase());
+            }
+        }
+    }
+    if let Some(action) = slot_action {
+        match state.use_slot(&action) {
+            Ok(message) => sfx.notify(message),
+            Err(err) => sfx.notify(err.to_string().to_uppercase()),
+        }
+    }
     
-    sfx.update(&mut state.event_manager);
+    let unlocked = state.sim.take_unlocked();
+    sfx.update(&mut state.sim.event_manager, unlocked);
+    sfx.update_auto_buyer(&state.auto_buyer, &mut state.sim.event_manager);
     state.vignette.update();
 
     // Drawing
     state.vignette.draw();
-    state.player.draw();
+    state.sim.player.draw(&state.sim.sector);
     if tick() > 100 {
-        state.event_manager.update(&mut state.player);
+        state.sim.event_manager.update(&mut state.sim.player);
     }
     
-    state.exoplanet.draw_ui();
-    state.drone_depot.draw_ui();
-    state.asteroid_mines.draw_ui();
-    state.power_plant.draw_ui();
-    state.jumpgate.draw_ui();
-    state.player.draw_ui();
+    let Split { research, stations, .. } = state.sim.split_stations();
+    for station in stations {
+        station.draw_ui(research);
+    }
+    state.sim.player.draw_ui();
 
-    sfx.draw();
+    sfx.draw(&state.sim);
+    sfx.draw_auto_buyer(&state.auto_buyer);
 
     if sfx.autosave && tick() % 1000 == 0 {
-        state.save_local();
+        state.autosave();
     }
     if tick() < 200 {
         //camera::set_xy(320, 296);
@@ -0,0 +1,143 @@
+use super::*;
+use std::collections::BTreeMap;
+
+// Stations FULL SECTOR wants built
+const SECTOR_STATIONS: [StationId; 6] = [
+    StationId::DroneDepot,
+    StationId::Fabricator,
+    StationId::AsteroidMines,
+    StationId::PowerPlant,
+    StationId::Jumpgate,
+    StationId::ResearchComplex,
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Goal {
+    DronesAssigned(u32),
+    Collected(Resources, u64),
+    Fabricated(u64),
+    Prestiges(u32),
+    // Every station in SECTOR_STATIONS, over any number of runs
+    AllStations,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Achievement {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub goal: Goal,
+    // Added to the offline efficiency for good once unlocked
+    pub offline_bonus: f32,
+}
+
+pub const ACHIEVEMENTS: [Achievement; 8] = [
+    Achievement { name: "FIRST DRONE", description: "Assign a DRONE to a station", goal: Goal::DronesAssigned(1), offline_bonus: 0. },
+    Achievement { name: "SWARM", description: "Assign 50 DRONES", goal: Goal::DronesAssigned(50), offline_bonus: 0.05 },
+    Achievement { name: "DEEP SURVEY", description: "Collect 1M RESEARCH", goal: Goal::Collected(Resources::Research, 1_000_000), offline_bonus: 0. },
+    Achievement { name: "METAL HOARD", description: "Collect 1M METALS", goal: Goal::Collected(Resources::Metals, 1_000_000), offline_bonus: 0.05 },
+    Achievement { name: "ASSEMBLY LINE", description: "Fabricate 100 DRONES", goal: Goal::Fabricated(100), offline_bonus: 0.05 },
+    Achievement { name: "FULL SECTOR", description: "Build every station", goal: Goal::AllStations, offline_bonus: 0.1 },
+    Achievement { name: "FIRST JUMP", description: "Jump to the next sector", goal: Goal::Prestiges(1), offline_bonus: 0. },
+    Achievement { name: "VETERAN", description: "Jump 5 times", goal: Goal::Prestiges(5), offline_bonus: 0.1 },
+];
+
+// Counters behind the goals, kept across prestiges
+#[derive(Debug, Clone, Default, PartialEq, BorshDeserialize, BorshSerialize)]
+pub struct Achievements {
+    // By index into ACHIEVEMENTS
+    pub unlocked: Vec<bool>,
+    pub drones_assigned: u32,
+    pub collected: BTreeMap<Resources, BigNum>,
+    pub fabricated: BigNum,
+    pub prestiges: u32,
+    // Stations constructed in any run, by StationId::ALL
+    pub built: Vec<bool>,
+}
+
+impl Achievements {
+    pub fn new() -> Self {
+        Achievements {
+            unlocked: vec![false; ACHIEVEMENTS.len()],
+            built: vec![false; StationId::ALL.len()],
+            ..Default::default()
+        }
+    }
+
+    // Saves from before an achievement or station was added have shorter lists
+    pub fn restore(achievements: &Achievements) -> Self {
+        let mut achievements = achievements.clone();
+        achievements.unlocked.resize(ACHIEVEMENTS.len(), false);
+        achievements.built.resize(StationId::ALL.len(), false);
+        achievements
+    }
+
+    // Fed by Simulation::collect_from, drones deposited by anything but a grant came from the fabricator
+    pub fn collected(&mut self, resource: &(Resources, BigNum), source: Source) {
+        if resource.0 == Resources::Drones && source != Source::Manual {
+            self.fabricated += resource.1;
+        } else {
+            *self.collected.entry(resource.0.clone()).or_default() += resource.1;
+        }
+    }
+
+    // Fed by Simulation::purchase with every upgrade bought
+    pub fn bought(&mut self, station: StationId, upgrade: &Upgrade) {
+        for effect in upgrade.effects.iter() {
+            match effect {
+                UpgradeEffect::AddDrone => self.drones_assigned += 1,
+                UpgradeEffect::Construct => self.built[station.index()] = true,
+                UpgradeEffect::UnlockBuilding(Building::Fabricator) => self.built[StationId::Fabricator.index()] = true,
+                _ => {}
+            }
+        }
+    }
+
+    pub fn handle_event(&mut self, event: &Event) {
+        if *event == Event::Prestige {
+            self.prestiges += 1;
+        }
+    }
+
+    // Unlock every achievement whose goal is now reached, returns them by index for the toasts
+    pub fn unlock(&mut self) -> Vec<usize> {
+        let mut unlocked = vec![];
+        for (i, achievement) in ACHIEVEMENTS.iter().enumerate() {
+            if !self.unlocked[i] && self.reached(&achievement.goal) {
+                self.unlocked[i] = true;
+                unlocked.push(i);
+            }
+        }
+        unlocked
+    }
+
+    fn reached(&self, goal: &Goal) -> bool {
+        match goal {
+            Goal::DronesAssigned(count) => self.drones_assigned >= *count,
+            Goal::Collected(resource, amount) => self.collected.get(resource).is_some_and(|collected| *collected >= BigNum::from(*amount)),
+            Goal::Fabricated(count) => self.fabricated >= BigNum::from(*count),
+            Goal::Prestiges(count) => self.prestiges >= *count,
+            Goal::AllStations => SECTOR_STATIONS.iter().all(|station| self.built[station.index()]),
+        }
+    }
+
+    // Offline efficiency earned from unlocked achievements
+    pub fn offline_bonus(&self) -> f32 {
+        ACHIEVEMENTS.iter().zip(self.unlocked.iter()).filter(|(_, unlocked)| **unlocked).map(|(achievement, _)| achievement.offline_bonus).sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unlock_reports_each_achievement_once() {
+        let mut achievements = Achievements::new();
+        assert!(achievements.unlock().is_empty());
+        achievements.handle_event(&Event::Prestige);
+        // FIRST JUMP
+        assert_eq!(achievements.unlock(), vec![6]);
+        assert!(achievements.unlock().is_empty());
+        assert!(achievements.unlocked[6]);
+    }
+}
@@ -0,0 +1,266 @@
+use super::*;
+
+// Ticks between purchases, about two a second
+pub const AUTO_BUY_INTERVAL: usize = 30;
+// Reserve percentages the options cycle through
+pub const RESERVE_STEPS: [u32; 4] = [10, 25, 50, 75];
+// Added to an upgrade's price for every earlier purchase at its station, so buying spreads out
+// rather than putting every drone into the first station that takes them
+const SPREAD: f64 = 0.05;
+// What an upgrade with no measurable effect on production, like a construct, counts as
+const UNMEASURED_GAIN: f64 = 0.5;
+
+#[derive(Debug, Clone, Copy, PartialEq, BorshDeserialize, BorshSerialize)]
+pub enum BuyPolicy {
+    // Lowest price first
+    Cheapest,
+    // Most production gained for the share of resources spent
+    BestRoi,
+    // Cheapest first, never spending below the reserve
+    Reserve,
+}
+
+impl BuyPolicy {
+    pub fn next(&self) -> Self {
+        match self {
+            BuyPolicy::Cheapest => BuyPolicy::BestRoi,
+            BuyPolicy::BestRoi => BuyPolicy::Reserve,
+            BuyPolicy::Reserve => BuyPolicy::Cheapest,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            BuyPolicy::Cheapest => "CHEAPEST",
+            BuyPolicy::BestRoi => "BEST ROI",
+            BuyPolicy::Reserve => "RESERVE",
+        }
+    }
+}
+
+// Spends resources on station upgrades by policy, unlocked through the probe upgrades
+#[derive(Debug, Clone, PartialEq, BorshDeserialize, BorshSerialize)]
+pub struct AutoBuyer {
+    pub unlocked: bool,
+    pub policy: BuyPolicy,
+    // Percent of each resource the reserve policy keeps
+    pub reserve: u32,
+    // By StationId::ALL
+    pub enabled: Vec<bool>,
+    // Upgrades bought at each station this run, by StationId::ALL
+    pub purchases: Vec<u32>,
+}
+
+impl AutoBuyer {
+    pub fn new() -> Self {
+        AutoBuyer {
+            unlocked: false,
+            policy: BuyPolicy::Cheapest,
+            reserve: RESERVE_STEPS[1],
+            // PRESTIGE and the jump to the next sector are the player's call
+            enabled: StationId::ALL.iter().map(|station| !matches!(station, StationId::Probe | StationId::Jumpgate)).collect(),
+            purchases: vec![0; StationId::ALL.len()],
+        }
+    }
+
+    // Same settings for the run after a prestige
+    pub fn next_run(&self) -> Self {
+        AutoBuyer {
+            purchases: vec![0; StationId::ALL.len()],
+            ..self.clone()
+        }
+    }
+
+    pub fn is_enabled(&self, station: StationId) -> bool {
+        self.enabled.get(station.index()).copied().unwrap_or(false)
+    }
+
+    pub fn toggle(&mut self, station: StationId) {
+        self.enabled.resize(StationId::ALL.len(), false);
+        self.enabled[station.index()] = !self.enabled[station.index()];
+    }
+
+    pub fn cycle_reserve(&mut self) {
+        let next = RESERVE_STEPS.iter().position(|step| *step == self.reserve).map_or(0, |i| (i + 1) % RESERVE_STEPS.len());
+        self.reserve = RESERVE_STEPS[next];
+    }
+
+    pub fn handle_event(&mut self, event: &Event) {
+        match event {
+            Event::UnlockAutoBuyer => self.unlocked = true,
+            Event::AutoBuyStation(station) => self.toggle(*station),
+            Event::CycleBuyPolicy => self.policy = self.policy.next(),
+            Event::CycleBuyReserve => self.cycle_reserve(),
+            _ => {}
+        }
+    }
+
+    // Queue a purchase every AUTO_BUY_INTERVAL ticks once unlocked, not while a cutscene plays
+    pub fn update(&mut self, sim: &Simulation, input: &mut SimInput) {
+        if !self.unlocked || sim.event_manager.dialogue.is_some() || !sim.tick.is_multiple_of(AUTO_BUY_INTERVAL) {
+            return;
+        }
+        self.buy(sim, input);
+    }
+
+    // Queue the upgrade the policy picks, returns false when there is nothing to buy
+    pub fn buy(&mut self, sim: &Simulation, input: &mut SimInput) -> bool {
+        let Some(command) = self.choose(sim) else {
+            return false;
+        };
+        self.purchases.resize(StationId::ALL.len(), 0);
+        self.purchases[command.station.index()] += 1;
+        input.commands.push(command);
+        true
+    }
+
+    pub fn choose(&self, sim: &Simulation) -> Option<Command> {
+        let offers = self.offers(sim);
+        let cheapest = offers.iter().min_by(|a, b| a.1.total_cmp(&b.1)).map(|(command, _)| command.clone());
+        if self.policy != BuyPolicy::BestRoi {
+            return cheapest;
+        }
+        let mut best: Option<(f64, Command)> = None;
+        for (command, _) in offers {
+            let score = AutoBuyer::gain(sim, &command) / AutoBuyer::share(sim, &command);
+            if best.as_ref().is_none_or(|(best_score, _)| score > *best_score) {
+                best = Some((score, command));
+            }
+        }
+        best.map(|(_, command)| command).or(cheapest)
+    }
+
+    // Upgrades the policy may buy at enabled stations, with their price for comparison
+    fn offers(&self, sim: &Simulation) -> Vec<(Command, f64)> {
+        let resources = &sim.player.resources;
+        let mut offers = vec![];
+        for station in StationId::ALL {
+            if !self.is_enabled(station) {
+                continue;
+            }
+            let bought = self.purchases.get(station.index()).copied().unwrap_or(0);
+            for (index, upgrade) in sim.offers(station).iter().enumerate() {
+                if !upgrade.affordable(resources) || !sim.research.accepts(station, upgrade) {
+                    continue;
+                }
+                if self.policy == BuyPolicy::Reserve {
+                    let spendable = 1. - self.reserve as f64 / 100.;
+                    if upgrade.cost.iter().any(|(resource, amount)| *amount > resources.amount(resource).mul_f64(spendable)) {
+                        continue;
+                    }
+                }
+                let price = upgrade.cost.iter().map(|(_, amount)| amount.log10()).fold(0., f64::max) + bought as f64 * SPREAD;
+                offers.push((Command { station, order: Order::Upgrade(index) }, price));
+            }
+        }
+        offers
+    }
+
+    // Production per second of each producing station
+    fn rates(sim: &Simulation) -> [f32; 4] {
+        [sim.exoplanet.idle_rate(), sim.asteroid_mines.idle_rate(), sim.power_plant.idle_rate(), sim.drone_depot.idle_rate()]
+    }
+
+    // Summed relative change in production from buying on a copy of the simulation
+    fn gain(sim: &Simulation, command: &Command) -> f64 {
+        let before = AutoBuyer::rates(sim);
+        let mut trial = sim.clone();
+        // Offers are all affordable, so the trial purchase goes through
+        let _ = trial.apply(command);
+        let after = AutoBuyer::rates(&trial);
+        let gain: f64 = before.iter().zip(after.iter()).map(|(before, after)| ((after - before) / before.max(1.)) as f64).sum();
+        if gain == 0. { UNMEASURED_GAIN } else { gain }
+    }
+
+    // Largest share of a held resource the upgrade costs
+    fn share(sim: &Simulation, command: &Command) -> f64 {
+        let Order::Upgrade(index) = command.order else {
+            return 1.;
+        };
+        let cost = &sim.offers(command.station)[index].cost;
+        cost.iter().map(|(resource, amount)| amount.ratio(sim.player.amount(resource))).fold(0., f64::max).max(f64::MIN_POSITIVE)
+    }
+}
+
+impl Default for AutoBuyer {
+    fn default() -> Self {
+        AutoBuyer::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A fresh run with only the exoplanet on offer, selling these upgrades at these RESEARCH prices
+    fn offering(held: u64, offers: &[(u64, Vec<UpgradeEffect>)]) -> Simulation {
+        let mut sim = Simulation::new(0, Sector::home(), Player::default());
+        sim.player.resources = Wallet::from(vec![(Resources::Research, BigNum::from(held))]);
+        let template = sim.offers(StationId::Exoplanet)[0].clone();
+        let upgrades = sim.station_mut(StationId::Exoplanet).and_then(|station| station.offers_mut(StationId::Exoplanet)).unwrap();
+        *upgrades = offers.iter().enumerate().map(|(id, (price, effects))| Upgrade {
+            id,
+            cost: vec![(Resources::Research, BigNum::from(*price))],
+            effects: effects.clone(),
+            unlocks: vec![],
+            max_level: 1,
+            ..template.clone()
+        }).collect();
+        sim
+    }
+
+    fn buyer(policy: BuyPolicy) -> AutoBuyer {
+        AutoBuyer {
+            unlocked: true,
+            policy,
+            enabled: StationId::ALL.iter().map(|station| *station == StationId::Exoplanet).collect(),
+            ..AutoBuyer::new()
+        }
+    }
+
+    fn chosen(buyer: &AutoBuyer, sim: &Simulation) -> Option<usize> {
+        buyer.choose(sim).map(|command| match command.order {
+            Order::Upgrade(index) => index,
+            _ => panic!("not a station upgrade"),
+        })
+    }
+
+    #[test]
+    fn cheapest_takes_the_lowest_price() {
+        let sim = offering(1_000, &[(300, vec![]), (20, vec![]), (2_000, vec![]), (40, vec![UpgradeEffect::AddDrone])]);
+        assert_eq!(chosen(&buyer(BuyPolicy::Cheapest), &sim), Some(1));
+    }
+
+    // A drone pays for itself, a change with nothing to measure only counts as UNMEASURED_GAIN
+    #[test]
+    fn best_roi_weighs_gain_against_price() {
+        let sim = offering(1_000, &[(20, vec![]), (22, vec![UpgradeEffect::AddDrone]), (2_000, vec![UpgradeEffect::AddDrone])]);
+        assert_eq!(chosen(&buyer(BuyPolicy::BestRoi), &sim), Some(1));
+        assert_eq!(chosen(&buyer(BuyPolicy::Cheapest), &sim), Some(0));
+    }
+
+    #[test]
+    fn reserve_keeps_the_floor() {
+        // 25% of 1,000 RESEARCH kept back, so at most 750 can go on one upgrade
+        let reserve = AutoBuyer { reserve: 25, ..buyer(BuyPolicy::Reserve) };
+        let sim = offering(1_000, &[(800, vec![]), (760, vec![])]);
+        assert_eq!(chosen(&reserve, &sim), None);
+        assert_eq!(chosen(&buyer(BuyPolicy::Cheapest), &sim), Some(1));
+        let sim = offering(1_000, &[(800, vec![]), (750, vec![])]);
+        assert_eq!(chosen(&reserve, &sim), Some(1));
+
+        // Bought one after another, the balance never drops under the floor
+        let mut sim = offering(1_000, &(0..8).map(|_| (100, vec![])).collect::<Vec<_>>());
+        let mut reserve = reserve;
+        for _ in 0..8 {
+            let mut input = SimInput { scanning: false, commands: vec![] };
+            if !reserve.buy(&sim, &mut input) {
+                break;
+            }
+            let before = sim.player.amount(&Resources::Research);
+            sim.step(&input);
+            assert!(sim.player.amount(&Resources::Research) >= before.mul_f64(0.25));
+        }
+        assert!(sim.player.amount(&Resources::Research) < BigNum::from(1_000u64));
+    }
+}
@@ -0,0 +1,287 @@
+use super::*;
+use std::cmp::Ordering;
+use std::ops::{Add, AddAssign, Div, Mul, Sub, SubAssign};
+
+// Mantissas at or above this are shifted into the exponent, kept under 2^53 so whole numbers stay exact
+const MANTISSA_LIMIT: f64 = 1e15;
+// Past this many digits the smaller side of a sum no longer shows in the mantissa
+const DIGITS: i64 = 17;
+
+// Non-negative amount of mantissa * 10^exponent, used for resources, costs and production.
+// Values below MANTISSA_LIMIT keep exponent 0 and behave like plain integers, larger ones
+// trade precision for range. Arithmetic saturates at zero instead of wrapping.
+#[derive(Debug, Clone, Copy, Default, BorshDeserialize, BorshSerialize)]
+pub struct BigNum {
+    mantissa: f64,
+    exponent: i64,
+}
+
+impl BigNum {
+    pub const ZERO: BigNum = BigNum { mantissa: 0., exponent: 0 };
+
+    pub fn new(mantissa: f64, exponent: i64) -> Self {
+        BigNum { mantissa, exponent }.normalize()
+    }
+
+    pub fn from_f64(value: f64) -> Self {
+        BigNum::new(value, 0)
+    }
+
+    fn normalize(mut self) -> Self {
+        if self.mantissa.is_nan() || self.mantissa <= 0. {
+            return BigNum::ZERO;
+        }
+        if self.mantissa.is_infinite() {
+            self.mantissa = f64::MAX;
+        }
+        if self.mantissa >= MANTISSA_LIMIT {
+            let shift = (self.mantissa / MANTISSA_LIMIT).log10().floor() as i64 + 1;
+            self.mantissa /= 10f64.powi(shift as i32);
+            self.exponent += shift;
+        } else if self.exponent > 0 {
+            // Pull digits back out of the exponent after a subtraction
+            let room = (MANTISSA_LIMIT / 10. / self.mantissa).log10().ceil() as i64;
+            let shift = room.min(self.exponent);
+            if shift > 0 {
+                self.mantissa *= 10f64.powi(shift as i32);
+                self.exponent -= shift;
+            }
+            if self.mantissa >= MANTISSA_LIMIT {
+                self.mantissa /= 10.;
+                self.exponent += 1;
+            }
+        } else if self.exponent < 0 {
+            self.mantissa /= 10f64.powi((-self.exponent).min(400) as i32);
+            self.exponent = 0;
+            return self.normalize();
+        }
+        self
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.mantissa == 0.
+    }
+
+    // Base 10 logarithm, -inf for zero
+    pub fn log10(&self) -> f64 {
+        self.mantissa.log10() + self.exponent as f64
+    }
+
+    // Lossy, infinite when out of f64 range
+    pub fn to_f64(&self) -> f64 {
+        if self.exponent > 400 {
+            return f64::INFINITY;
+        }
+        self.mantissa * 10f64.powi(self.exponent as i32)
+    }
+
+    // Whole part, saturating at u64::MAX
+    pub fn to_u64(&self) -> u64 {
+        let value = self.to_f64();
+        if value >= u64::MAX as f64 {
+            u64::MAX
+        } else {
+            value.floor() as u64
+        }
+    }
+
+    // Drop the fractional part, large values have none to drop
+    pub fn floor(&self) -> Self {
+        if self.exponent == 0 {
+            BigNum::from_f64(self.mantissa.floor())
+        } else {
+            *self
+        }
+    }
+
+    pub fn round(&self) -> Self {
+        if self.exponent == 0 {
+            BigNum::from_f64(self.mantissa.round())
+        } else {
+            *self
+        }
+    }
+
+    pub fn mul_f64(&self, x: f64) -> Self {
+        BigNum::new(self.mantissa * x, self.exponent)
+    }
+
+    pub fn powi(&self, mut n: u32) -> Self {
+        let mut base = *self;
+        let mut result = BigNum::from(1u64);
+        while n > 0 {
+            if n & 1 == 1 {
+                result = result * base;
+            }
+            base = base * base;
+            n >>= 1;
+        }
+        result
+    }
+
+    // Fraction of total, clamped to 0..=1, for progress bars
+    pub fn ratio(&self, total: BigNum) -> f64 {
+        if total.is_zero() {
+            return 1.;
+        }
+        (*self / total).to_f64().clamp(0., 1.)
+    }
+
+    pub fn min(self, other: BigNum) -> Self {
+        if other < self { other } else { self }
+    }
+
+    pub fn max(self, other: BigNum) -> Self {
+        if other > self { other } else { self }
+    }
+
+    // Mantissa of other scaled to this exponent, zero when too small to matter
+    fn aligned(&self, other: &BigNum) -> f64 {
+        let diff = self.exponent - other.exponent;
+        if diff > DIGITS {
+            0.
+        } else {
+            other.mantissa / 10f64.powi(diff as i32)
+        }
+    }
+}
+
+impl From<u64> for BigNum {
+    fn from(value: u64) -> Self {
+        BigNum::from_f64(value as f64)
+    }
+}
+
+impl From<u32> for BigNum {
+    fn from(value: u32) -> Self {
+        BigNum::from_f64(value as f64)
+    }
+}
+
+impl Add for BigNum {
+    type Output = BigNum;
+
+    fn add(self, other: BigNum) -> BigNum {
+        if self.exponent >= other.exponent {
+            BigNum::new(self.mantissa + self.aligned(&other), self.exponent)
+        } else {
+            BigNum::new(other.mantissa + other.aligned(&self), other.exponent)
+        }
+    }
+}
+
+impl AddAssign for BigNum {
+    fn add_assign(&mut self, other: BigNum) {
+        *self = *self + other;
+    }
+}
+
+// Saturates at zero
+impl Sub for BigNum {
+    type Output = BigNum;
+
+    fn sub(self, other: BigNum) -> BigNum {
+        if other >= self {
+            return BigNum::ZERO;
+        }
+        BigNum::new(self.mantissa - self.aligned(&other), self.exponent)
+    }
+}
+
+impl SubAssign for BigNum {
+    fn sub_assign(&mut self, other: BigNum) {
+        *self = *self - other;
+    }
+}
+
+impl Mul for BigNum {
+    type Output = BigNum;
+
+    fn mul(self, other: BigNum) -> BigNum {
+        BigNum::new(self.mantissa * other.mantissa, self.exponent + other.exponent)
+    }
+}
+
+// Dividing by zero gives zero
+impl Div for BigNum {
+    type Output = BigNum;
+
+    fn div(self, other: BigNum) -> BigNum {
+        if other.is_zero() {
+            return BigNum::ZERO;
+        }
+        BigNum::new(self.mantissa / other.mantissa, self.exponent - other.exponent)
+    }
+}
+
+impl PartialEq for BigNum {
+    fn eq(&self, other: &BigNum) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for BigNum {}
+
+impl PartialOrd for BigNum {
+    fn partial_cmp(&self, other: &BigNum) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for BigNum {
+    fn cmp(&self, other: &BigNum) -> Ordering {
+        if self.exponent == other.exponent {
+            self.mantissa.total_cmp(&other.mantissa)
+        } else {
+            self.log10().total_cmp(&other.log10())
+        }
+    }
+}
+
+impl std::fmt::Display for BigNum {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", Numbers::format(*self))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sub_saturates_at_zero() {
+        assert_eq!(BigNum::from(3u64) - BigNum::from(5u64), BigNum::ZERO);
+        assert_eq!(BigNum::from(5u64) - BigNum::new(1., 30), BigNum::ZERO);
+        assert_eq!(BigNum::new(1., 30) - BigNum::from(5u64), BigNum::new(1., 30));
+    }
+
+    #[test]
+    fn div_by_zero_is_zero() {
+        assert_eq!(BigNum::from(7u64) / BigNum::ZERO, BigNum::ZERO);
+        assert_eq!(BigNum::from(8u64) / BigNum::from(2u64), BigNum::from(4u64));
+    }
+
+    // Mantissas alone would order these the other way round
+    #[test]
+    fn orders_across_exponents() {
+        let small = BigNum::from_f64(9e14);
+        let large = BigNum::new(2., 15);
+        assert_ne!(small.exponent, large.exponent);
+        assert!(small.mantissa > large.mantissa);
+        assert!(small < large);
+        assert_eq!(small.max(large), large);
+        assert!(BigNum::new(1., 300) > BigNum::new(9.9, 299));
+    }
+
+    #[test]
+    fn add_and_mul_normalize() {
+        let sum = BigNum::from_f64(9e14) + BigNum::from_f64(2e14);
+        assert!(sum.mantissa < MANTISSA_LIMIT && sum.exponent > 0);
+        assert_eq!(sum, BigNum::from(1_100_000_000_000_000u64));
+
+        let product = BigNum::from_f64(1e10) * BigNum::from_f64(1e10);
+        assert!(product.mantissa < MANTISSA_LIMIT && product.exponent > 0);
+        assert_eq!(product, BigNum::new(1., 20));
+        assert_eq!(product.log10(), 20.);
+    }
+}
@@ -0,0 +1,51 @@
+#[cfg(not(target_family = "wasm"))]
+use once_cell::sync::Lazy;
+#[cfg(not(target_family = "wasm"))]
+use std::sync::Mutex;
+
+// The game has no text input, save and replay strings leave and enter it through the system clipboard.
+// The page provides these imports, see the CLIPBOARD section of www/main.js
+#[cfg(target_family = "wasm")]
+#[link(wasm_import_module = "clipboard")]
+unsafe extern "C" {
+    // Copy len bytes of UTF-8 text at ptr to the clipboard
+    fn clipboard_write(ptr: *const u8, len: u32);
+    // Copy the text last pasted into the page to ptr when it fits in cap bytes, returns its length
+    fn clipboard_read(ptr: *mut u8, cap: u32) -> u32;
+}
+
+// Native builds have no page to paste into, what was copied is what gets pasted
+#[cfg(not(target_family = "wasm"))]
+static CLIPBOARD: Lazy<Mutex<String>> = Lazy::new(|| Mutex::new(String::new()));
+
+pub struct Clipboard;
+
+impl Clipboard {
+    #[cfg(target_family = "wasm")]
+    pub fn copy(text: &str) {
+        unsafe { clipboard_write(text.as_ptr(), text.len() as u32) }
+    }
+
+    // The text last pasted into the page, empty when nothing was
+    #[cfg(target_family = "wasm")]
+    pub fn paste() -> String {
+        let len = unsafe { clipboard_read(std::ptr::null_mut(), 0) };
+        let mut bytes = vec![0u8; len as usize];
+        // A paste landing in between leaves a length that no longer matches, read nothing then
+        let read = unsafe { clipboard_read(bytes.as_mut_ptr(), len) };
+        if read != len {
+            return String::new();
+        }
+        String::from_utf8_lossy(&bytes).into_owned()
+    }
+
+    #[cfg(not(target_family = "wasm"))]
+    pub fn copy(text: &str) {
+        *CLIPBOARD.lock().unwrap() = text.to_string();
+    }
+
+    #[cfg(not(target_family = "wasm"))]
+    pub fn paste() -> String {
+        CLIPBOARD.lock().unwrap().clone()
+    }
+}
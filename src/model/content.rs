@@ -0,0 +1,247 @@
+use super::*;
+use once_cell::sync::Lazy;
+use serde::Deserialize;
+
+// Content packs bundled with the game by file name, see tests/fixtures/packs/example.toml for the format.
+// Packs are read in this order, each adding its resources and stations after the ones before
+pub const PACKS: [(&str, &str); 0] = [];
+
+// Resources the packs declare, Resources::Pack indexes into this. A pack that fails to parse is logged
+// and the game starts without any, rather than not at all
+pub static PACK_RESOURCES: Lazy<Vec<ResourceDef>> = Lazy::new(|| {
+    Content::parse_resources(&PACKS).unwrap_or_else(|err| {
+        log!("content packs not loaded ({})", err);
+        vec![]
+    })
+});
+
+pub static CONTENT: Lazy<Content> = Lazy::new(|| {
+    Content::parse(&PACKS).unwrap_or_else(|err| {
+        log!("content packs not loaded ({})", err);
+        Content { stations: vec![] }
+    })
+});
+
+// Effects a pack station can apply, the rest are tied to the stations and prestige tree of the base game
+fn pack_effect(effect: &UpgradeEffect) -> bool {
+    matches!(effect, UpgradeEffect::Construct | UpgradeEffect::AddDrone | UpgradeEffect::AddEfficiency(_) | UpgradeEffect::AddSpeed(_)
+        | UpgradeEffect::ScaleInterval(_) | UpgradeEffect::TriggerEvent(_) | UpgradeEffect::Grant(..))
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ResourceDef {
+    // Also the sprite key of its icon
+    pub name: String,
+    pub description: String,
+}
+
+// Only the resources of a pack file, its stations are left for Content::parse
+#[derive(Debug, Clone, Deserialize)]
+struct ResourceFile {
+    #[serde(default)]
+    resource: Vec<ResourceDef>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct StationFile {
+    name: String,
+    bounds: (i32, i32, i32, i32),
+    sprite: String,
+    drones: DroneMode,
+    // Resolved against the resources of the packs being read
+    produces: String,
+    drone_base: f32,
+    unlock: Event,
+    #[serde(default)]
+    story: Vec<String>,
+    upgrades: Vec<UpgradeDef>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PackFile {
+    // Already read by Content::parse_resources
+    #[serde(default, rename = "resource")]
+    _resources: Vec<ResourceDef>,
+    #[serde(default)]
+    station: Vec<StationFile>,
+}
+
+// A station declared by a pack
+#[derive(Debug, Clone, PartialEq)]
+pub struct PackStationDef {
+    // Shown in the pop up and the key its progress is saved under
+    pub name: String,
+    pub bounds: (i32, i32, i32, i32),
+    // Drawn as is, with "_hovered" and "_locked" versions on top when they exist
+    pub sprite: String,
+    // Sets the drones' work cycle, they circle the station whatever the preset
+    pub mode: DroneMode,
+    pub produces: Resources,
+    pub drone_base: f32,
+    // The station shows up once this event is handled, after its story if it has one
+    pub unlock: Event,
+    pub story: Vec<String>,
+    pub upgrades: Vec<Upgrade>,
+}
+
+impl PackStationDef {
+    fn build(file: &StationFile, resources: &[ResourceDef]) -> Result<Self, String> {
+        if file.bounds.2 <= 0 || file.bounds.3 <= 0 {
+            return Err("bounds need a width and height".to_string());
+        }
+        if file.drones == DroneMode::Shipping {
+            return Err("shipping drones need the depot and mines, use survey, mining or conduit".to_string());
+        }
+        let produces = Resources::lookup(&file.produces, resources)?;
+        let upgrades = UpgradeTables::build_list(&file.name, &file.upgrades, resources)?;
+        for upgrade in upgrades.iter() {
+            if let Some(effect) = upgrade.effects.iter().find(|effect| !pack_effect(effect)) {
+                return Err(format!("upgrade {} ({}): {:?} is not available at pack stations", upgrade.id, upgrade.name, effect));
+            }
+        }
+        if !upgrades.first().is_some_and(|upgrade| upgrade.effects.contains(&UpgradeEffect::Construct)) {
+            return Err("the first upgrade must construct the station".to_string());
+        }
+        Ok(PackStationDef {
+            name: file.name.clone(),
+            bounds: file.bounds,
+            sprite: file.sprite.clone(),
+            mode: file.drones.clone(),
+            produces,
+            drone_base: file.drone_base,
+            unlock: file.unlock.clone(),
+            story: file.story.clone(),
+            upgrades,
+        })
+    }
+
+    // Played when the unlock event comes, the camera moves to the station with the second message
+    pub fn cutscene(&self) -> Option<Dialogue> {
+        if self.story.is_empty() {
+            return None;
+        }
+        let (x, y, w, h) = self.bounds;
+        Some(Dialogue {
+            messages: self.story.clone(),
+            camera_pos: vec![((320, 200), 0), ((x + w/2, y + h/2), 1)],
+            d_box: DialogueBox::new(),
+            event_broadcast: 1,
+            prompt: false,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Content {
+    // PackUnlockable and Order::PackUpgrade index into this
+    pub stations: Vec<PackStationDef>,
+}
+
+impl Content {
+    fn parse_resources(packs: &[(&str, &str)]) -> Result<Vec<ResourceDef>, String> {
+        let mut resources: Vec<ResourceDef> = vec![];
+        for (file, src) in packs.iter() {
+            let pack: ResourceFile = toml::from_str(src).map_err(|err| format!("{}: {}", file, err))?;
+            for resource in pack.resource {
+                let taken = resources.iter().any(|other| other.name == resource.name);
+                if resource.name.is_empty() || taken || ["RESEARCH", "DRONES", "METALS", "POWER", "PRESTIGE"].contains(&resource.name.as_str()) {
+                    return Err(format!("{}: [resource] \"{}\" is empty or already taken", file, resource.name));
+                }
+                resources.push(resource);
+            }
+        }
+        Ok(resources)
+    }
+
+    // Parse and validate the packs' stations, errors name the file and station at fault
+    pub fn parse(packs: &[(&str, &str)]) -> Result<Self, String> {
+        let resources = Content::parse_resources(packs)?;
+        let mut stations: Vec<PackStationDef> = vec![];
+        for (file, src) in packs.iter() {
+            let pack: PackFile = toml::from_str(src).map_err(|err| format!("{}: {}", file, err))?;
+            for (i, station) in pack.station.iter().enumerate() {
+                let def = PackStationDef::build(station, &resources).map_err(|err| format!("{}: [station] {} ({}): {}", file, i, station.name, err))?;
+                if stations.iter().any(|other| other.name == def.name) {
+                    return Err(format!("{}: [station] {} ({}): another pack has a station by this name", file, i, station.name));
+                }
+                stations.push(def);
+            }
+        }
+        Ok(Content { stations })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Not bundled, the game ships the packs in PACKS
+    const EXAMPLE: &str = include_str!("../../tests/fixtures/packs/example.toml");
+
+    #[test]
+    fn bundled_packs_parse() {
+        assert_eq!(*PACK_RESOURCES, Content::parse_resources(&PACKS).unwrap());
+        assert_eq!(*CONTENT, Content::parse(&PACKS).unwrap());
+    }
+
+    #[test]
+    fn example_pack_parses() {
+        let pack = [("example.toml", EXAMPLE)];
+        let resources = Content::parse_resources(&pack).unwrap();
+        assert_eq!(resources.iter().map(|resource| resource.name.as_str()).collect::<Vec<_>>(), ["ICE"]);
+
+        let content = Content::parse(&pack).unwrap();
+        assert_eq!(content.stations.len(), 1);
+        let station = &content.stations[0];
+        assert_eq!(station.name, "CRYO MOON");
+        assert_eq!(station.produces, Resources::Pack(0));
+        assert_eq!(station.unlock, Event::LateGame);
+        assert!(station.cutscene().is_some());
+        for (i, upgrade) in station.upgrades.iter().enumerate() {
+            assert_eq!(upgrade.id, i);
+            assert!(upgrade.effects.iter().all(pack_effect), "{}", upgrade.name);
+        }
+        assert_eq!(station.upgrades[2].cost[0].0, Resources::Pack(0));
+    }
+
+    #[test]
+    fn broken_packs_are_rejected() {
+        let taken = EXAMPLE.replacen("name = \"ICE\"", "name = \"METALS\"", 1);
+        assert!(Content::parse_resources(&[("taken.toml", &taken)]).unwrap_err().starts_with("taken.toml: [resource] \"METALS\""));
+
+        let shipping = EXAMPLE.replacen("drones = \"mining\"", "drones = \"shipping\"", 1);
+        assert!(Content::parse(&[("shipping.toml", &shipping)]).unwrap_err().starts_with("shipping.toml: [station] 0 (CRYO MOON)"));
+
+        let unbuilt = EXAMPLE.replacen("effects = [\"construct\"]", "effects = []", 1);
+        assert!(Content::parse(&[("unbuilt.toml", &unbuilt)]).unwrap_err().ends_with("the first upgrade must construct the station"));
+
+        let tied = EXAMPLE.replacen("effects = [\"add_drone\"]", "effects = [\"auto_scan\"]", 1);
+        assert!(Content::parse(&[("tied.toml", &tied)]).unwrap_err().contains("not available at pack stations"));
+
+        assert!(Content::parse(&[("twice.toml", EXAMPLE), ("twice.toml", EXAMPLE)]).unwrap_err().contains("[resource] \"ICE\""));
+        let renamed = EXAMPLE.replacen("name = \"ICE\"", "name = \"SNOW\"", 1);
+        assert!(Content::parse(&[("twice.toml", EXAMPLE), ("renamed.toml", &renamed)]).unwrap_err().contains("another pack has a station by this name"));
+    }
+    // Saves name pack resources, so loading other packs cannot move a balance onto another resource
+    #[test]
+    fn pack_resources_save_by_name() {
+        let resources = Content::parse_resources(&[("example.toml", EXAMPLE)]).unwrap();
+        let mut bytes = vec![];
+        Resources::Pack(0).write(&mut bytes, &resources).unwrap();
+        assert_eq!(bytes, [&[5u8, 3, 0, 0, 0][..], b"ICE"].concat());
+        assert_eq!(Resources::read(&mut bytes.as_slice(), &resources).unwrap(), Some(Resources::Pack(0)));
+        assert_eq!(Resources::read(&mut bytes.as_slice(), &[]).unwrap(), None);
+
+        // A balance of ICE once no pack declares it is dropped along with the pack
+        let mut wallet = u32::MAX.try_to_vec().unwrap();
+        wallet.extend(1u32.try_to_vec().unwrap());
+        wallet.extend(&bytes);
+        wallet.extend(Balance::default().try_to_vec().unwrap());
+        wallet.extend(0u32.try_to_vec().unwrap());
+        let wallet = Wallet::try_from_slice(&wallet).unwrap();
+        assert_eq!(wallet.iter().count(), 0);
+    }
+}
@@ -1,8 +1,9 @@
 use super::*;
 use once_cell::sync::Lazy;
 use std::sync::Mutex;
+use serde::Deserialize;
 
-#[derive(Debug, Clone, PartialEq, BorshDeserialize, BorshSerialize)]
+#[derive(Debug, Clone, PartialEq, BorshDeserialize, BorshSerialize, Deserialize)]
 pub enum Event {
     StartGame,
     SaveGame,
@@ -15,6 +16,28 @@ pub enum Event {
     LateGame,
     Prestige,
     EndGame,
+    // New events go last so saved events keep their tags
+    ExportSave,
+    ImportSave,
+    // Slot picker actions, by index into SLOT_NAMES
+    StoreSlot(usize),
+    LoadSlot(usize),
+    CopySlot(usize),
+    DeleteSlot(usize),
+    // Roll back to an autosave, newest first
+    RestoreAutosave(usize),
+    ToggleRecording,
+    PlayReplay,
+    // Auto-buyer unlock and settings
+    UnlockAutoBuyer,
+    AutoBuyStation(StationId),
+    CycleBuyPolicy,
+    CycleBuyReserve,
+    ComplexUnlockable,
+    // A queued project finished, by station and upgrade id
+    ProjectComplete(StationId, usize),
+    // A content pack station's unlock event came, by index into CONTENT.stations
+    PackUnlockable(usize),
 }
 
 #[derive(Debug, Clone, PartialEq, BorshDeserialize, BorshSerialize)]
@@ -25,30 +48,47 @@ pub struct EventManager {
 }
 
 impl EventManager {
-    pub fn new() -> Self {
+    pub fn new(sector: &Sector) -> Self {
         Self { 
             events: Vec::new(),
-            dialogue: Some(CUTSCENES[0].clone().start()),
+            dialogue: Some(cutscene(0, sector).start()),
             over: false,
         }
     }
 
+    pub fn restore(events: Vec<Event>) -> Self {
+        Self {
+            events,
+            dialogue: None,
+            over: false,
+        }
+    }
+
+    // Events still waiting to be handled
+    pub fn pending(&self) -> Vec<Event> {
+        self.events.clone()
+    }
+
+    // Take every queued event without playing cutscenes, for changes applied all at once
+    pub fn drain(&mut self) -> Vec<Event> {
+        std::mem::take(&mut self.events)
+    }
+
     // Add an event to the queue
     pub fn trigger(&mut self, event: Event) {
         self.events.push(event);
     }
 
-    // Process all events in the queue
-    pub fn process_events<F>(&mut self, mut handler: F)
-    where
-        F: FnMut(&Event),
-    {
+    // Take the next event that is ready to be handled, starting its cutscene first if it has one.
+    // Only the front event leaves the queue, the rest wait for later polls
+    pub fn poll(&mut self, sector: &Sector) -> Option<Event> {
+        let mut ready = None;
         if !self.events.is_empty() {
-            let event = &self.events[0];
+            let event = self.events[0].clone();
             if let Some(dialogue) = &mut self.dialogue {
                 if dialogue.event_broadcast <= 0 {
-                    handler(event);
-                    self.events.clear();
+                    ready = Some(event);
+                    self.events.remove(0);
                     if dialogue.prompt {
                         self.dialogue = None;
                     }
@@ -56,42 +96,48 @@ impl EventManager {
             } else {
                 match event {
                     Event::StartGame => { 
-                        self.dialogue = Some(CUTSCENES[0].clone().start()); 
+                        self.dialogue = Some(cutscene(0, sector).start()); 
                     }
                     Event::DroneDepotUnlockable => { 
-                        self.dialogue = Some(CUTSCENES[1].clone().start()); 
+                        self.dialogue = Some(cutscene(1, sector).start()); 
                     }
                     Event::MinesUnlockable => { 
-                        self.dialogue = Some(CUTSCENES[2].clone().start()); 
+                        self.dialogue = Some(cutscene(2, sector).start()); 
                     }
                     Event::PowerPlantUnlockable => { 
-                        self.dialogue = Some(CUTSCENES[3].clone().start()); 
+                        self.dialogue = Some(cutscene(3, sector).start()); 
                     }
                     Event::LateGame => { 
-                        self.dialogue = Some(CUTSCENES[4].clone().start()); 
+                        self.dialogue = Some(cutscene(4, sector).start()); 
                     }
                     Event::Prestige => {
                         if self.over {
-                            self.events.clear();
+                            self.events.remove(0);
                             self.over = false;
                         } else {
-                            self.dialogue = Some(CUTSCENES[7].clone().start());
+                            self.dialogue = Some(cutscene(7, sector).start());
                         }
                     }
                     Event::ResetGame => {
                         if self.over {
-                            self.events.clear();
+                            self.events.remove(0);
                             self.over = false;
                         } else {
-                            self.dialogue = Some(CUTSCENES[6].clone().start());
+                            self.dialogue = Some(cutscene(6, sector).start());
                         }
                     }
                     Event::EndGame => {
-                        self.dialogue = Some(CUTSCENES[8].clone().start());
+                        self.dialogue = Some(cutscene(8, sector).start());
+                    }
+                    Event::ComplexUnlockable => {
+                        self.dialogue = Some(cutscene(9, sector).start());
+                    }
+                    Event::PackUnlockable(station) if CONTENT.stations.get(station).is_some_and(|def| !def.story.is_empty()) => {
+                        self.dialogue = CONTENT.stations[station].cutscene().map(|mut dialogue| dialogue.start());
                     }
                     _ => {
-                        handler(event);
-                        self.events.clear();
+                        ready = Some(event);
+                        self.events.remove(0);
                     }
                 }
             }
@@ -99,6 +145,18 @@ impl EventManager {
         if self.over {
             self.over = false;
         }
+        ready
+    }
+
+    // Read and confirm the current dialogue at once, for runs without a player
+    pub fn skip_dialogue(&mut self) {
+        if let Some(dialogue) = &mut self.dialogue {
+            if self.events.is_empty() {
+                self.dialogue = None;
+            } else {
+                dialogue.event_broadcast = 0;
+            }
+        }
     }
 
     pub fn update(&mut self, player: &mut Player) {
@@ -114,7 +172,7 @@ impl EventManager {
 
 impl Default for EventManager {
     fn default() -> Self {
-        EventManager::new()
+        EventManager::new(&Sector::home())
     }
 }
 
@@ -312,3 +370,34 @@ impl DialogueBox {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Saves store events by tag, the first release's events must keep theirs
+    #[test]
+    fn event_tags_are_stable() {
+        let tag = |event: Event| event.try_to_vec().unwrap()[0];
+        assert_eq!(tag(Event::StartGame), 0);
+        assert_eq!(tag(Event::ResetGame), 2);
+        assert_eq!(tag(Event::LateGame), 8);
+        assert_eq!(tag(Event::EndGame), 10);
+        assert_eq!(tag(Event::ExportSave), 11);
+        assert_eq!(tag(Event::PlayReplay), 19);
+        assert_eq!(tag(Event::PackUnlockable(0)), 26);
+    }
+
+    #[test]
+    fn poll_takes_one_event_at_a_time() {
+        let sector = Sector::home();
+        let mut manager = EventManager::new(&sector);
+        manager.trigger(Event::SaveGame);
+        manager.trigger(Event::ExportSave);
+        manager.trigger(Event::CycleBuyPolicy);
+        assert_eq!(manager.poll(&sector), Some(Event::SaveGame));
+        assert_eq!(manager.poll(&sector), Some(Event::ExportSave));
+        assert_eq!(manager.poll(&sector), Some(Event::CycleBuyPolicy));
+        assert_eq!(manager.poll(&sector), None);
+    }
+}
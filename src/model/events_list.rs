@@ -1,7 +1,13 @@
 use super::*;
-use once_cell::sync::Lazy;
 
-pub static CUTSCENES: Lazy<Vec<Dialogue>> = Lazy::new(|| vec![
+// Built when played so the camera follows the stations to where the sector put them
+pub fn cutscene(index: usize, sector: &Sector) -> Dialogue {
+    let depot = sector.place(StationId::DroneDepot, DEPOT_BOX);
+    let mines = sector.place(StationId::AsteroidMines, MINES_BOX);
+    let plant = sector.place(StationId::PowerPlant, PLANT_BOX);
+    let gate = sector.place(StationId::Jumpgate, GATE_BOX);
+    let complex = sector.place(StationId::ResearchComplex, COMPLEX_BOX);
+    let mut cutscenes = vec![
     Dialogue {
         messages: vec![
             "Exoplanet detected!".to_string(),
@@ -19,7 +25,7 @@ pub static CUTSCENES: Lazy<Vec<Dialogue>> = Lazy::new(|| vec![
             "Authorizing construction of DRONE DEPOT. ".to_string(),
             "Establish a hub for additional autonomous workers and deploy them to gather RESEARCH.".to_string(),
         ],
-        camera_pos: vec![((320, 200), 0), ((DEPOT_BOX.0 + DEPOT_BOX.2/2, DEPOT_BOX.1 - 16), 1)],
+        camera_pos: vec![((320, 200), 0), ((depot.0 + depot.2/2, depot.1 - 16), 1)],
         d_box: DialogueBox::new(),
         event_broadcast: 1,
         prompt: false,
@@ -31,7 +37,7 @@ pub static CUTSCENES: Lazy<Vec<Dialogue>> = Lazy::new(|| vec![
             "Authorizing construction of ASTEROID MINES. ".to_string(),
             "Gather METALS from the asteroids to build advanced tech.".to_string(),
         ],
-        camera_pos: vec![((320, 200), 0), ((MINES_BOX.0 - 16, MINES_BOX.1 + MINES_BOX.3/2), 2)],
+        camera_pos: vec![((320, 200), 0), ((mines.0 - 16, mines.1 + mines.3/2), 2)],
         d_box: DialogueBox::new(),
         event_broadcast: 2,
         prompt: false,
@@ -43,7 +49,7 @@ pub static CUTSCENES: Lazy<Vec<Dialogue>> = Lazy::new(|| vec![
             "Authorizing construction of POWER PLANT.".to_string(),
             "Harvest POWER from the storm to amplify other stations.".to_string(),
         ],
-        camera_pos: vec![((64, 32), 0), ((PLANT_BOX.0 + PLANT_BOX.2/2, PLANT_BOX.1 - 16), 2)],
+        camera_pos: vec![((64, 32), 0), ((plant.0 + plant.2/2, plant.1 - 16), 2)],
         d_box: DialogueBox::new(),
         event_broadcast: 2,
         prompt: false,
@@ -57,7 +63,7 @@ pub static CUTSCENES: Lazy<Vec<Dialogue>> = Lazy::new(|| vec![
             "Authorizing construction of JUMPGATE.".to_string(),
             "Use the JUMPGATE to leave this sector and start again in a new sector.".to_string(),
         ],
-        camera_pos: vec![((PLANT_BOX.0 + PLANT_BOX.2/2, PLANT_BOX.1 + PLANT_BOX.3/2), 0), ((GATE_BOX.0 + GATE_BOX.2/2, GATE_BOX.1 - 32), 2)], // ((COMPLEX_BOX.0 + COMPLEX_BOX.2/2, COMPLEX_BOX.1 + COMPLEX_BOX.3/2), 2),
+        camera_pos: vec![((plant.0 + plant.2/2, plant.1 + plant.3/2), 0), ((gate.0 + gate.2/2, gate.1 - 32), 2)], // ((COMPLEX_BOX.0 + COMPLEX_BOX.2/2, COMPLEX_BOX.1 + COMPLEX_BOX.3/2), 2),
         d_box: DialogueBox::new(),
         event_broadcast: 2,
         prompt: false,
@@ -67,7 +73,7 @@ pub static CUTSCENES: Lazy<Vec<Dialogue>> = Lazy::new(|| vec![
             "Jumpgate initiated. Prepare for imminent jump.".to_string(),
             "Good work, researcher! There's more work in the next sector.".to_string(),
         ],
-        camera_pos: vec![((GATE_BOX.0 + GATE_BOX.2/2, GATE_BOX.1 - 32), 0)], // ((COMPLEX_BOX.0 + COMPLEX_BOX.2/2, COMPLEX_BOX.1 + COMPLEX_BOX.3/2), 2),
+        camera_pos: vec![((gate.0 + gate.2/2, gate.1 - 32), 0)], // ((COMPLEX_BOX.0 + COMPLEX_BOX.2/2, COMPLEX_BOX.1 + COMPLEX_BOX.3/2), 2),
         d_box: DialogueBox::new(),
         event_broadcast: 0,
         prompt: false,
@@ -101,4 +107,21 @@ pub static CUTSCENES: Lazy<Vec<Dialogue>> = Lazy::new(|| vec![
         event_broadcast: 1,
         prompt: false,
     },
-]);
\ No newline at end of file
+    Dialogue {
+        messages: vec![
+            "Sector POWER grid stable.".to_string(),
+            "Authorizing construction of RESEARCH COMPLEX.".to_string(),
+            "Assign DRONES to the complex to turn RESEARCH into tech projects that improve every station.".to_string(),
+        ],
+        camera_pos: vec![((plant.0 + plant.2/2, plant.1 + plant.3/2), 0), ((complex.0 + complex.2/2, complex.1 - 16), 1)],
+        d_box: DialogueBox::new(),
+        event_broadcast: 1,
+        prompt: false,
+    },
+    ];
+    // The intro ends with the readings of a new sector
+    if let Some(briefing) = sector.briefing() {
+        cutscenes[0].messages.push(briefing);
+    }
+    cutscenes.swap_remove(index)
+}
\ No newline at end of file
@@ -10,13 +10,13 @@ pub struct Debris {
     pub timer: u32,
 }
 impl Debris {
-    pub fn new(pos: (f32, f32), size: f32) -> Self {
+    pub fn new(pos: (f32, f32), size: f32, rng: &mut Rng) -> Self {
         Debris {
             pos,
-            angle: rand() as f32 % std::f32::consts::TAU, // Random angle in range [0, 2*PI]
-            speed: -(((rand() % 101) as f32 / 100.0) * 0.2 + 0.1), // Negative angular speed for clockwise motion
-            size: 6.0 + size/3. * ((rand() % 101) as f32 / 100.0), // Random radius in range [960.0, 1280.0]
-            lifetime: 30 + (rand() % 30) as u32, // Random lifetime in range [60, 120]
+            angle: rng.next_u32() as f32 % std::f32::consts::TAU, // Random angle in range [0, 2*PI]
+            speed: -(((rng.next_u32() % 101) as f32 / 100.0) * 0.2 + 0.1), // Negative angular speed for clockwise motion
+            size: 6.0 + size/3. * ((rng.next_u32() % 101) as f32 / 100.0), // Random radius in range [960.0, 1280.0]
+            lifetime: 30 + rng.next_u32() % 30, // Random lifetime in range [60, 120]
             timer: 0,
         }
     }
@@ -58,13 +58,13 @@ pub struct Asteroid {
 }
 
 impl Asteroid {
-    pub fn new() -> Self {
-        // Use the assumed `rand()` function to generate random values
+    pub fn new(rng: &mut Rng) -> Self {
+        // Asteroids are only drawn, so they use the cosmetic stream
         let angle = std::f32::consts::FRAC_PI_2 + 0.62; // Start at the top middle (90 degrees or π/2 radians)
-        let speed = -(((rand() % 101) as f32 / 100.0) * 0.0001 + 0.0001); // Negative angular speed for clockwise motion
-        let radius = 1920.0 + 384.0 * ((rand() % 101) as f32 / 100.0); // Random radius in range [960.0, 1280.0]
-        let size = 8.0 + ((rand() % 17) as f32); // Random size in range [8.0, 24.0]
-        let id = rand() as u32; // Unique ID for the asteroid
+        let speed = -(((rng.next_u32() % 101) as f32 / 100.0) * 0.0001 + 0.0001); // Negative angular speed for clockwise motion
+        let radius = 1920.0 + 384.0 * ((rng.next_u32() % 101) as f32 / 100.0); // Random radius in range [960.0, 1280.0]
+        let size = 8.0 + ((rng.next_u32() % 17) as f32); // Random size in range [8.0, 24.0]
+        let id = rng.next_u32(); // Unique ID for the asteroid
         
         Self {
             pos: (-320.0, -320.0),
@@ -75,12 +75,12 @@ impl Asteroid {
             id,
             drilling: false,
             debris: vec![],
-            sprite: rand() % 4,
-            rot: rand() % 4
+            sprite: rng.next_u32() % 4,
+            rot: rng.next_u32() % 4
         }
     }
 
-    pub fn update(&mut self) {
+    pub fn update(&mut self, rng: &mut Rng, centre: (f32, f32)) {
         // Update the angle based on the angular speed
         self.angle -= self.speed;
 
@@ -89,13 +89,13 @@ impl Asteroid {
             self.angle -= std::f32::consts::TAU;
         }
         self.pos = (
-            1344.0 + self.radius * self.angle.cos(),
-            1344.0 - self.radius * self.angle.sin()
+            centre.0 + self.radius * self.angle.cos(),
+            centre.1 - self.radius * self.angle.sin()
         );
 
         if self.drilling {
             if self.debris.len() < 10 {
-                self.debris.push(Debris::new(self.pos, self.size));
+                self.debris.push(Debris::new(self.pos, self.size, rng));
             }
         }
         self.debris.retain_mut(|chunk| !chunk.update(self.pos)); // Remove debris that has reached its lifetime
@@ -138,24 +138,27 @@ pub struct AsteroidField {
     pub spawn_interval: u32, // Interval between spawns (in frames)
     pub timer: u32,         // Timer to track spawn intervals
     belt_index: usize,
+    centre: (f32, f32),     // Where the belt circles
 }
 
 impl AsteroidField {
-    pub fn new() -> Self {
+    pub fn new(sector: &Sector) -> Self {
         Self {
             asteroids: vec![vec![], vec![], vec![]],
-            limit: 350,
+            // Denser or sparser belts depending on the sector, and none at all in sectors without one
+            limit: sector.belt.map_or(0, |_| (350. * sector.fields().0) as usize),
             spawn_interval: 10,
             timer: 0,
             belt_index: 0,
+            centre: sector.belt.unwrap_or_default(),
         }
     }
 
-    pub fn update(&mut self) {
+    pub fn update(&mut self, rng: &mut Rng) {
         // Update existing asteroids
         for belt in self.asteroids.iter_mut() {
             for asteroid in belt.iter_mut() {
-                asteroid.update();
+                asteroid.update(rng, self.centre);
             }
         }
 
@@ -170,7 +173,7 @@ impl AsteroidField {
         // Spawn new asteroids if below the limit and the interval has passed
         let stroids: usize = self.asteroids.iter().map(|belt| belt.len()).sum();
         if stroids < self.limit && self.timer >= self.spawn_interval {
-            self.asteroids[self.belt_index].push(Asteroid::new());
+            self.asteroids[self.belt_index].push(Asteroid::new(rng));
             self.timer = 0; // Reset the timer
             self.belt_index += 1;
             if self.belt_index >= 3 {
@@ -22,7 +22,7 @@ pub struct Circle {
 }
 
 impl Cloud {
-    pub fn new(center: (i32, i32), radius: u32, start: u32) -> Self {
+    pub fn new(center: (i32, i32), radius: u32, start: u32, rng: &mut Rng) -> Self {
         let mut rings = Vec::new();
         let size_range = (16, 72); // Larger minimum size for inner rings
 
@@ -47,15 +47,15 @@ impl Cloud {
 
                 // Scale wobble amplitude based on radius (inner rings wobble less)
                 let wobble_amplitude = 0.1 + (current_radius as f32 / radius as f32) * 0.04;
-                let color = if rand()%2==0 { 0x1a1229ff } else { 0x1f122bff };
+                let color = if rng.next_u32().is_multiple_of(2) { 0x1a1229ff } else { 0x1f122bff };
 
                 ring.push(Circle {
                     pos,
-                    size: size_range.0 + (size_range.1 as f32 * ((rand()as f32%100.)/100.)) as u32,
+                    size: size_range.0 + (size_range.1 as f32 * ((rng.next_u32()as f32%100.)/100.)) as u32,
                     color,
                     angle,
-                    speed: 0.1 + (rand() as f32%100.)/100. * 0.04, // Random wobble speed
-                    wobble_phase: (rand() as f32%100.)/100. * std::f32::consts::TAU, // Random wobble phase
+                    speed: 0.1 + (rng.next_u32() as f32%100.)/100. * 0.04, // Random wobble speed
+                    wobble_phase: (rng.next_u32() as f32%100.)/100. * std::f32::consts::TAU, // Random wobble phase
                     wobble_amplitude,
                 });
             }
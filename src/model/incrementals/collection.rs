@@ -5,16 +5,16 @@ pub struct Collection {
     pub is_active: bool,
     created_at: usize,
     pos: (f32, f32),
-    value: (Resources, u64),
+    value: (Resources, BigNum),
     positive: bool,
 }
 
 impl Collection {
-    pub fn new(pos: (f32, f32), value: (Resources, u64)) -> Self {
+    pub fn new(pos: (f32, f32), value: (Resources, BigNum)) -> Self {
         Collection::new_detail(pos, value, true)
     }
 
-    pub fn new_detail(pos: (f32, f32), value: (Resources, u64), positive: bool) -> Self {
+    pub fn new_detail(pos: (f32, f32), value: (Resources, BigNum), positive: bool) -> Self {
         Self {
             is_active: true,
             created_at: tick(),
@@ -1,8 +1,10 @@
 use std::f32::MIN;
 
 use super::*;
+use serde::Deserialize;
 
-#[derive(Debug, Clone, PartialEq, BorshDeserialize, BorshSerialize)]
+#[derive(Debug, Clone, PartialEq, BorshDeserialize, BorshSerialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum DroneMode {
     Survey,
     Mining,
@@ -10,6 +12,40 @@ pub enum DroneMode {
     Conduit,
 }
 
+impl DroneMode {
+    // Ticks per work cycle before speed upgrades
+    pub fn interval(&self) -> f32 {
+        match self {
+            DroneMode::Survey => 800.,
+            DroneMode::Mining => 500.,
+            DroneMode::Shipping => 300.,
+            DroneMode::Conduit => 400.,
+        }
+    }
+}
+
+// Economic side of a drone, advanced by the simulation
+#[derive(Debug, Clone, Default, PartialEq, BorshDeserialize, BorshSerialize)]
+pub struct Work {
+    pub timer: f32,
+    pub cargo: BigNum,
+    // Set on the tick the drone delivers or picks up, read by the drawing code
+    pub produced: Option<BigNum>,
+    pub consumed: Option<BigNum>,
+}
+
+impl Work {
+    // Returns true when a cycle of period ticks completes
+    pub fn advance(&mut self, step: f32, period: f32) -> bool {
+        self.timer += step;
+        if self.timer >= period {
+            self.timer -= period;
+            return true;
+        }
+        false
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, BorshDeserialize, BorshSerialize)]
 pub struct Drone {
     pub pos: (f32, f32),
@@ -28,26 +64,23 @@ pub struct Drone {
     asteroid_id: u32, 
     pub cargo: Vec<(Resources, u64)>,
     pub on_site: bool,
+    pub work: Work,
 
     wander_progress: f32,
     wander_forward: bool,
 }
 
 impl Drone {
-    pub fn new(mode: DroneMode, level: u32, speed: u32, target_pos: (i32, i32)) -> Self {
+    pub fn new(mode: DroneMode, level: u32, speed: u32, target_pos: (i32, i32), rng: &mut Rng, sector: &Sector) -> Self {
+        let depot = sector.place(StationId::DroneDepot, DEPOT_BOX);
         Drone {
-            pos: ((DEPOT_BOX.0 + DEPOT_BOX.2/2) as f32, (DEPOT_BOX.1 + DEPOT_BOX.3/2) as f32), // Position of drone depot
+            pos: ((depot.0 + depot.2/2) as f32, (depot.1 + depot.3/2) as f32), // Position of drone depot
             target_pos: (target_pos.0 as f32, target_pos.1 as f32),
             front: true,
-            interval: match mode {
-                DroneMode::Survey => 800.,
-                DroneMode::Mining => 500.,
-                DroneMode::Shipping => 300.,
-                DroneMode::Conduit => 400.,
-            },
+            interval: mode.interval(),
             mode,
             timer: 0.,
-            phase: (rand() as f32 % 101.) / 100., 
+            phase: (rng.next_u32() as f32 % 101.) / 100., 
             angle: 0.,
 
             level,
@@ -57,34 +90,33 @@ impl Drone {
             asteroid_id: 0,
             cargo: vec![],
             on_site: false,
+            work: Work::default(),
 
             wander_progress: 0.,
             wander_forward: false,
         }
     }
 
-    pub fn conduit(&mut self, nebula: &mut NebulaStorm) -> bool {
+    pub fn conduit(&mut self, nebula: &mut NebulaStorm, rng: &mut Rng, sector: &Sector) -> bool {
         let bounds = (640.0, 208., 64., 64.0);
 
         if self.on_site {
-            self.wander( 200.0);
-            self.timer += 1.;
-            if self.timer >= self.interval {
-                self.timer = 0.;
+            self.wander( 200.0, sector.storm_centre());
+            if self.work.produced.is_some() {
                 self.target_pos = nebula.get_drone_pos();
-                nebula.generate_drone_lightning(self.pos, 15);
+                nebula.generate_drone_lightning(self.pos, 15, rng, sector);
                 return true;
             }
         } else {
-            self.target_pos = ((PLANT_BOX.0 + PLANT_BOX.2/2) as f32, (PLANT_BOX.1 + PLANT_BOX.3/2) as f32);
+            let plant = sector.place(StationId::PowerPlant, PLANT_BOX);
+            self.target_pos = ((plant.0 + plant.2/2) as f32, (plant.1 + plant.3/2) as f32);
             self.on_site = self.follow(0.1); 
         }
         false
     }
 
-    pub fn wander(&mut self, min_duration: f32) -> bool {
+    pub fn wander(&mut self, min_duration: f32, center: (f32, f32)) -> bool {
         // Arc parameters
-        let center = (640.0 + 240. + 64., -240. - 64.);
         let base_radius = 240. + self.phase * 120.;
         let arc_start = 0.35; // radians, adjust as needed
         let arc_end = 4.0;   // radians, adjust as needed
@@ -122,14 +154,19 @@ impl Drone {
         false
     }
 
-    pub fn survey(&mut self, station: &Station) -> bool {
+    pub fn survey(&mut self, station: &Station, rng: &mut Rng, sector: &Sector) {
+        let planet = sector.place(StationId::Exoplanet, PLANET_BOX);
+        let center = ((planet.0 + planet.2/2) as f32, (planet.1 + planet.3/2) as f32); // Center of the ellipse
+        self.orbit(station, center, (100., 25.), rng);
+    }
+
+    // Circle an ellipse around center, scanning it whenever work is produced
+    pub fn orbit(&mut self, station: &Station, center: (f32, f32), radius: (f32, f32), rng: &mut Rng) {
         // Calculate the angle based on the timer and interval
         let angle = ((self.timer as f32 / station.drone_speed as f32) + self.phase) * std::f32::consts::TAU; // TAU = 2 * PI
 
         // Define the ellipse dimensions
-        let center = ((PLANET_BOX.0 + PLANET_BOX.2/2) as f32, (PLANET_BOX.1 + PLANET_BOX.3/2) as f32); // Center of the ellipse
-        let radius_x = 100.0; // Horizontal radius
-        let radius_y = 25.0;  // Vertical radius
+        let (radius_x, radius_y) = radius;
 
         // Oscillation factor (sinusoidal oscillation between 0.75 and 1.0)
         let raw_oscillation = ((self.timer as f32 / station.drone_speed as f32) * std::f32::consts::TAU).sin();
@@ -143,11 +180,9 @@ impl Drone {
             self.front = self.pos.1 >= center.1;
     
             self.timer += 1.;
-            let delta = (self.timer as f32 - station.drone_speed as f32).abs() % (station.drone_speed as f32/2.);
-            if delta <= 1.0 && self.scan.is_none() {
+            if self.work.produced.is_some() && self.scan.is_none() {
                 let scan = (center.0 + 32. * angle.cos(), center.1 + 32. * oscillation * angle.sin()); 
-                self.scan = Some(Scan::new(self.pos, scan));
-                return true;
+                self.scan = Some(Scan::new(self.pos, scan, rng));
             }
             if self.timer >= station.drone_speed {
                 self.timer = 0.;
@@ -158,7 +193,6 @@ impl Drone {
                     self.scan = None;
                 }
             } 
-            false
         } else {
             self.timer = station.drone_speed/2.-1.;
             self.target_pos = (center.0 + radius_x * angle.cos(),
@@ -166,16 +200,22 @@ impl Drone {
             if self.follow(0.1) {
                 self.on_site = true;
             }
-            false
         }
     }
 
-    pub fn shipping(&mut self) -> Option<(Resources, u64)> {
+    pub fn shipping(&mut self, sector: &Sector) {
         // Define the start and bounds for the random target
-        let home = ((DEPOT_BOX.0 + DEPOT_BOX.2/2) as f32, (DEPOT_BOX.1 + DEPOT_BOX.3 - 8) as f32);
-        let mines = ((MINES_BOX.0 + MINES_BOX.2/2) as f32 -6. - (self.phase * 2.).round() * 8., (MINES_BOX.1 + 2*MINES_BOX.3/3) as f32);
+        let (depot, mines) = (sector.place(StationId::DroneDepot, DEPOT_BOX), sector.place(StationId::AsteroidMines, MINES_BOX));
+        let home = ((depot.0 + depot.2/2) as f32, (depot.1 + depot.3 - 8) as f32);
+        let mines = ((mines.0 + mines.2/2) as f32 -6. - (self.phase * 2.).round() * 8., (mines.1 + 2*mines.3/3) as f32);
         
-        if self.on_site {
+        if self.work.cargo.is_zero() {
+            // Head back to the mines for the next load
+            self.on_site = false;
+            self.target_pos = mines;
+            self.follow(0.2);
+        } else if self.on_site {
+            // Circle the depot while unloading
             self.timer += 1. * (1. + self.speed as f32 * 0.2);
             let angle = (self.timer / self.interval) * std::f32::consts::TAU; // TAU = 2 * PI
             
@@ -184,51 +224,13 @@ impl Drone {
                 home.1 + (8. + self.phase * 16.) * angle.cos(),
             );
             self.follow(0.1);
-            // if self.phase % 0.02 == 0.0 {
-            //     self.target_pos = (
-            //         home.0 + (16. + self.phase * 16.) * angle.sin(),
-            //         home.1 + (16. + self.phase * 16.) * angle.cos(),
-            //     );
-            // } else {
-            // }
-//            log!("{}", (16. + self.phase * 16.) * angle.sin());
-            if self.timer >= self.interval {
-                self.timer = 0.;
-                let amount = ((1.0 + self.speed as f32 * 0.2) * 10. + (self.level as f32 * 0.75 * 5.)).round() as u64;
-                if amount >= self.cargo[0].1 {
-                    self.cargo.clear();
-                    self.target_pos = mines;
-                    self.on_site = false;
-                } else {
-                    self.cargo[0].1 -= amount;
-                }
-                return Some((Resources::Metals, amount));
-            }
         } else {
-            if self.cargo.is_empty() {
-                self.target_pos = mines;
-            } else {
-                self.target_pos = home;
-            }
-            if self.follow(0.2) {
-                if !self.cargo.is_empty() {
-                    self.on_site = true;
-                } else {
-                    self.timer += 1.;
-                    if self.timer >= self.interval {
-                        self.timer = 0.;
-                        let amount = ((1. + self.level as f32 * 0.75) * 32.) as u64;
-                        self.cargo.push((Resources::Metals, amount));
-                        self.target_pos = home; // Reset target to home after mining
-                        return Some((Resources::Metals, amount));
-                    }
-                }
-            }
+            self.target_pos = home;
+            self.on_site = self.follow(0.2);
         }
-        None
     }
 
-    pub fn update_mining(&mut self, field: &mut AsteroidField) -> bool {
+    pub fn update_mining(&mut self, field: &mut AsteroidField, rng: &mut Rng, sector: &Sector) -> bool {
         if self.on_site {
             self.timer += 1.0 * (1.0 + self.speed as f32 * 0.5);
             if self.timer >= self.interval / 4. {
@@ -242,7 +244,7 @@ impl Drone {
                         .collect();
             
                     if !matching_asteroids.is_empty() {
-                        let random_index = (rand() as usize) % matching_asteroids.len();
+                        let random_index = (rng.next_u32() as usize) % matching_asteroids.len();
                         Some(matching_asteroids[random_index])
                     } else {
                         None
@@ -265,7 +267,7 @@ impl Drone {
                     if self.timer >= self.interval {
                         self.timer = 0.;
                         self.cargo.push((Resources::Metals, 0));
-                        self.target_pos = (15.0 + (MINES_BOX.0 + rand() as i32 % 33) as f32, 0.0); // Reset target to home after mining
+                        self.target_pos = (15.0 + (sector.place(StationId::AsteroidMines, MINES_BOX).0 + rng.next_u32() as i32 % 33) as f32, 0.0); // Reset target to home after mining
                         asteroid.drilling = false; // Stop drilling animation
                         
                     }
@@ -277,7 +279,7 @@ impl Drone {
                         .collect();
             
                     if !matching_asteroids.is_empty() {
-                        let random_index = (rand() as usize) % matching_asteroids.len();
+                        let random_index = (rng.next_u32() as usize) % matching_asteroids.len();
                         Some(matching_asteroids[random_index])
                     } else {
                         None
@@ -297,7 +299,7 @@ impl Drone {
                         .collect();
                     
                     if !matching_asteroids.is_empty() {
-                        let random_index = (rand() as usize) % matching_asteroids.len();
+                        let random_index = (rng.next_u32() as usize) % matching_asteroids.len();
                         Some(matching_asteroids[random_index])
                     } else {
                         None
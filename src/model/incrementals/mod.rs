@@ -31,4 +31,7 @@ mod resources;
 pub use resources::*;
 
 mod vignette;
-pub use vignette::*;
\ No newline at end of file
+pub use vignette::*;
+
+mod wallet;
+pub use wallet::*;
\ No newline at end of file
@@ -133,27 +133,27 @@ pub struct NebulaStorm {
 }
 
 impl NebulaStorm {
-    pub fn new() -> NebulaStorm {
+    pub fn new(rng: &mut Rng) -> NebulaStorm {
         NebulaStorm {
             bolts: vec![],
             spawn_timer: 0.0,
-            field: Nebulous::new(),
+            field: Nebulous::new(rng),
         }
     }
 
-    pub fn update(&mut self) {
+    pub fn update(&mut self, rng: &mut Rng, sector: &Sector) {
         let dt = 1.0 / 60.0; // Simulate 60 FPS time step
         self.spawn_timer += dt;
-        let center = (640.0 + 240. + 64., -240. - 64.);
 
-        // Spawn a new bolt every 0.2 seconds
-        if self.spawn_timer >= 0.1 {
+        // Spawn a new bolt every 0.1 seconds, more often in heavier storms. Sectors without a storm only
+        // see the bolts the conduit drones draw
+        if let Some(center) = sector.storm && self.spawn_timer >= 0.1 / sector.fields().1 {
             self.spawn_timer = 0.0;
             let mut center = center;
-            center.0 += rand() as f32 % 64.0; // Randomize x position within bounds
-            let radius = 120.0 + (rand() as f32 % 241.0);        // Varying arc radius
-            let start_angle = 64. + rand() as f32 % 65.0;             // Anywhere around the circle
-            let arc_span = 90.0 + (rand() as f32 % 32.0) - radius/360. * 32.;        // Arc length 15°–30°
+            center.0 += rng.next_u32() as f32 % 64.0; // Randomize x position within bounds
+            let radius = 120.0 + (rng.next_u32() as f32 % 241.0);        // Varying arc radius
+            let start_angle = 64. + rng.next_u32() as f32 % 65.0;             // Anywhere around the circle
+            let arc_span = 90.0 + (rng.next_u32() as f32 % 32.0) - radius/360. * 32.;        // Arc length 15°–30°
             let segments = NebulaStorm::generate_arc_lightning(
                 center,
                 radius,
@@ -161,6 +161,7 @@ impl NebulaStorm {
                 arc_span,
                 15,
                 25.0,  // jaggedness
+                rng,
             );
 
             self.bolts.push(Bolt {
@@ -206,9 +207,12 @@ impl NebulaStorm {
         &mut self,
         origin: (f32, f32),
         segments: usize,
+        rng: &mut Rng,
+        sector: &Sector,
     ) -> (f32, f32) {
         let mut points = Vec::new();
-        let target = ((PLANT_BOX.0 + PLANT_BOX.2) as f32 - 24.0, PLANT_BOX.1 as f32 + 16.0);
+        let plant = sector.place(StationId::PowerPlant, PLANT_BOX);
+        let target = ((plant.0 + plant.2) as f32 - 24.0, plant.1 as f32 + 16.0);
 
         for i in 0..segments {
             let t = i as f32 / segments as f32;
@@ -218,8 +222,8 @@ impl NebulaStorm {
             let mut jitter_x = 0.0;
             let mut jitter_y = 0.0;
             if i < segments - 4 {
-                jitter_x = (rand() as f32 % 101. / 100. - 0.5) * 25.0;
-                jitter_y = (rand() as f32 % 101. / 100. - 0.5) * 25.0;
+                jitter_x = (rng.next_u32() as f32 % 101. / 100. - 0.5) * 25.0;
+                jitter_y = (rng.next_u32() as f32 % 101. / 100. - 0.5) * 25.0;
             }
 
             points.push((x + jitter_x, y + jitter_y));
@@ -259,6 +263,7 @@ impl NebulaStorm {
         arc_span_deg: f32,
         segments: usize,
         jaggedness: f32,
+        rng: &mut Rng,
     ) -> Vec<Segment> {
         let mut points = Vec::new();
     
@@ -269,8 +274,8 @@ impl NebulaStorm {
             let x = center.0 + radius * angle.cos();
             let y = center.1 + radius * angle.sin();
     
-            let jitter_x = ((rand() as f32 % 101. / 100. - 0.5) * jaggedness);
-            let jitter_y = ((rand() as f32 % 101. / 100. - 0.5) * jaggedness);
+            let jitter_x = ((rng.next_u32() as f32 % 101. / 100. - 0.5) * jaggedness);
+            let jitter_y = ((rng.next_u32() as f32 % 101. / 100. - 0.5) * jaggedness);
     
             points.push((x + jitter_x, y + jitter_y));
         }
@@ -295,9 +300,9 @@ impl NebulaStorm {
             });
     
             // Branch with 20% chance
-            if rand() as f32 % 100. < 20.0 {
-                let branch_angle = ((rand() as f32 % 60.0) - 30.0).to_radians(); // ±30° spread
-                let branch_length = 30.0 + rand() as f32 % 20.0;
+            if rng.next_u32() as f32 % 100. < 20.0 {
+                let branch_angle = ((rng.next_u32() as f32 % 60.0) - 30.0).to_radians(); // ±30° spread
+                let branch_length = 30.0 + rng.next_u32() as f32 % 20.0;
     
                 let dx = end.0 - start.0;
                 let dy = end.1 - start.1;
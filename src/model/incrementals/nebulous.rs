@@ -22,13 +22,13 @@ const BASE_COLOR: u32 = 0x4b77bc33;
 
 
 impl Nebulous {
-    pub fn new() -> Self {
+    pub fn new(rng: &mut Rng) -> Self {
         Self {
             center: (944.0, -304.0),
             counter: 0,
             k: 24,
             flow_array: vec![],
-            start_col: rand() as f32 % 361.0,
+            start_col: rng.next_u32() as f32 % 361.0,
             rez1: 0.006,
             rez2: 0.005,
             gap: 4.0,
@@ -42,10 +42,10 @@ impl Nebulous {
         }
     }
 
-    pub fn update(&mut self) {
+    pub fn update(&mut self, rng: &mut Rng) {
         
         if self.counter%120 == 0 {
-            self.make_segments();
+            self.make_segments(rng);
         }
 
         for segment in self.segments.iter_mut() {
@@ -64,7 +64,7 @@ impl Nebulous {
     }
 
 
-    fn make_segments(&mut self) {
+    fn make_segments(&mut self, rng: &mut Rng) {
         if self.segments.len() > self.flow_array.len() * 5 {
             for i in 0..self.flow_array.len() + 1 {
                 self.segments.remove(i);
@@ -84,7 +84,7 @@ impl Nebulous {
             self.segments.push(Segment {
                 start: (x, y),
                 end: (new_x, new_y),
-                thickness: 8 as f32 + rand() as f32 % 40.,
+                thickness: 8 as f32 + rng.next_u32() as f32 % 40.,
                 direction: (0., 0.),
                 color: color as u32,
             });
@@ -1,5 +1,4 @@
 use super::*;
-use once_cell::sync::Lazy;
 
 #[derive(Debug, Clone, PartialEq, BorshDeserialize, BorshSerialize)]
 pub struct PopUp {
@@ -14,6 +13,10 @@ pub struct PopUp {
     title: String,
     pub drones: u32,
     drone_resource: Resources,
+    // Measured income of drone_resource, refreshed on update
+    income: String,
+    // What the hovered upgrade's cost is missing, shown in place of the income
+    shortfall: String,
     fab: bool,
 }
 impl PopUp {
@@ -35,6 +38,8 @@ impl PopUp {
             title,
             drones: 0,
             drone_resource: resource,
+            income: String::new(),
+            shortfall: String::new(),
             fab: false,
         }
     }
@@ -57,28 +62,31 @@ impl PopUp {
             title,
             drones: 0,
             drone_resource: resource,
+            income: String::new(),
+            shortfall: String::new(),
             fab: true,
         }
     }
 
     pub fn hovered(&mut self) -> bool {
         let p = pointer().xy_fixed();
-        self.hovered = self.hitbox.intersects_xy((p.0 as i32, p.1 as i32));
+        self.hovered = self.hitbox.intersects_xy(p);
         self.hovered
     }
 
     pub fn inspecting(&mut self) -> bool {
         if self.drones > 0 {
             let p = pointer().xy_fixed();
-            self.inspecting = self.drone_inspect.bounds.intersects_xy((p.0 as i32, p.1 as i32)) || (self.inspecting && self.drone_hitbox.intersects_xy((p.0 as i32, p.1 as i32)));
+            self.inspecting = self.drone_inspect.bounds.intersects_xy(p) || (self.inspecting && self.drone_hitbox.intersects_xy(p));
         } else {
             self.inspecting = false;
         }
         self.inspecting
     }
 
-    pub fn update(&mut self, anchor: Bounds, station: &Station, upgrades: &mut Vec<Upgrade>, upgrade_list: &Lazy<Vec<Upgrade>>, resources: &Vec<(Resources, u64)>) -> Option<Upgrade> {
+    pub fn update(&mut self, anchor: Bounds, upgrades: &mut [Upgrade], resources: &Wallet) -> Option<Order> {
         let mut upgraded = None;
+        self.income = resources.balance(&self.drone_resource).income.label();
         // Size based on available upgrades
         let mut height = upgrades.iter().map(|u| u.cost.len() as i32).sum::<i32>();
         if self.fab {
@@ -148,7 +156,7 @@ impl PopUp {
                     );
                 self.unassaign.update();
                 if self.unassaign.on_click() {
-                    upgraded = Some(UNASSGIN.clone());
+                    upgraded = Some(Order::Unassign);
                 }
             }
 
@@ -163,43 +171,27 @@ impl PopUp {
         }
 
         // Update upgrade buttons
+        self.shortfall = String::new();
         if !self.inspecting {
-            
-            let mut index = 0;
             for i in 0..upgrades.len() {
                 let upgrade = &mut upgrades[i];
                 // Pass the players current resource value for the upgrade
                 upgrade.update(resources);
                 // Player purchases the upgrade
                 if upgrade.on_click() {
-                    upgraded = Some(upgrade.clone());
-                    index = i;
+                    upgraded = Some(Order::Upgrade(i));
                 }
-            }
-        
-            let mut maxed = false;
-            if let Some(upgrade) = &mut upgraded {
-                maxed = upgrades[index].next_level();
-                // Push next level upgrade to avail_upgrades
-                if upgrade.unlocks.len() > 0 {
-                    for i in 0..upgrade.unlocks.len() {
-                        Upgrade::add_upgrade(upgrades, &upgrade_list, upgrade.unlocks[i], self.panel);
-                    }
-                    upgrades[index].unlocks = vec![]; // Clear unlocks after applying upgrade
+                if let Some(shortfall) = upgrade.shortfall.as_ref().filter(|_| upgrade.hovered) {
+                    self.shortfall = format!("NEED {} {}", Numbers::format(shortfall.missing()), shortfall.resource);
                 }
             }
-            if maxed {
-                upgrades.remove(index);
-            }
         }
         upgraded
     }
 
-    pub fn update_fabricator(&mut self, anchor: Bounds, station: &Station, upgrades: &mut Vec<Upgrade>, upgrade_list: &Lazy<Vec<Upgrade>>, resources: &Vec<(Resources, u64)>) -> Option<Upgrade> {
+    pub fn update_fabricator(&mut self, anchor: Bounds, upgrades: &mut [Upgrade], resources: &Wallet) -> Option<Order> {
         // Update upgrade buttons
-        let mut upgraded = self.update(anchor, station, upgrades, upgrade_list, resources);
-        
-        upgraded
+        self.update(anchor, upgrades, resources)
     }
 
     pub fn draw(&self, station: &Station, upgrades: &Vec<Upgrade>) {
@@ -246,6 +238,13 @@ impl PopUp {
             );
             sprite!("DRONES", fixed = true, xy = (self.panel.right() - 40, self.panel.y() + 3), w = 16, h = 16, color = 0xffffffff);
         }
+        let (label, color) = if self.shortfall.is_empty() { (&self.income, 0x847e87ff) } else { (&self.shortfall, 0xe43b44ff) };
+        text!(
+            label,
+            fixed = true,
+            xy = (self.panel.right() - 44 - label.len() as i32 * 5, self.panel.y() + 8),
+            color = color
+        );
         rect!(
             fixed = true, 
             x = self.panel.left() + 4,
@@ -427,7 +426,7 @@ impl PopUp {
                 fixed = true,
                 xy = (anchor.0 + 70, anchor.1 + 28),
             );
-            let t = Numbers::format((self.drones as f32 * ((station.drone_base * station.drone_eff) / (station.drone_speed / 60.))) as u64);
+            let t = Numbers::format(BigNum::from_f64((self.drones as f32 * ((station.drone_base * station.drone_eff) / (station.drone_speed / 60.))) as f64).floor());
             text!(
                 &t,
                 fixed = true,
@@ -469,7 +468,7 @@ impl PopUp {
     }
 
 
-    pub fn draw_fabricator(&self, station: &Station, upgrades: &Vec<Upgrade>, fab_prog: u64, fab_limit: u64) {
+    pub fn draw_fabricator(&self, station: &Station, upgrades: &Vec<Upgrade>, fab_prog: BigNum, fab_limit: BigNum) {
         self.draw(station, upgrades);
 
         rect!(
@@ -482,7 +481,7 @@ impl PopUp {
 
         let bar = ( 
             self.panel.x() + 4, 24 + self.panel.y(),
-            (self.panel.w() as u64 - 8) * fab_prog / fab_limit, 20
+            ((self.panel.w() as u64 - 8) as f64 * fab_prog.ratio(fab_limit)) as u64, 20
         );
         rect!(
             fixed = true, 
@@ -528,7 +527,43 @@ impl PopUp {
         );
     }
 
-    pub fn draw_jumpgate(&self, station: &Station, upgrades: &Vec<Upgrade>, prestige_earn: u64, prestige_prog: u64, prestige_limit: u64) {
+    // Queued projects under the panel, the one underway with its progress
+    pub fn draw_projects(&self, research: &Research, stations: &[StationId]) {
+        let projects: Vec<Project> = stations.iter().flat_map(|station| research.projects(*station)).collect();
+        if projects.is_empty() {
+            return;
+        }
+        let bounds = self.panel.translate_y(self.panel.h() as i32 + 2).height(8 + projects.len() as u32 * 14);
+        rect!(
+            fixed = true,
+            xy = bounds.xy(),
+            wh = bounds.wh(),
+            border_radius = 4,
+            border_size = 1,
+            color = 0x1f122bff,
+            border_color = 0xffffffff,
+        );
+        for (i, project) in projects.iter().enumerate() {
+            let y = bounds.y() + 4 + i as i32 * 14;
+            text!(project.name(), fixed = true, xy = (bounds.x() + 6, y + 3), color = 0xffffffff);
+            let bar = (bounds.right() - 64, y, 58, 12);
+            rect!(
+                fixed = true,
+                xy = (bar.0, bar.1),
+                wh = (bar.2, bar.3),
+                border_radius = 2,
+                border_size = 1,
+                color = 0x1f122bff,
+                border_color = 0xffffffff,
+            );
+            let filled = ((bar.2 - 4) as f32 * project.progress()) as u32;
+            if filled > 0 {
+                rect!(fixed = true, xy = (bar.0 + 2, bar.1 + 2), wh = (filled, bar.3 - 4), color = 0xffffffff);
+            }
+        }
+    }
+
+    pub fn draw_jumpgate(&self, station: &Station, upgrades: &Vec<Upgrade>, prestige_earn: BigNum, prestige_prog: BigNum, prestige_limit: BigNum) {
         self.draw(station, upgrades);
 
         rect!(
@@ -541,7 +576,7 @@ impl PopUp {
 
         let bar = ( 
             self.panel.x() + 4, 24 + self.panel.y(),
-            (self.panel.w() as u64 - 8) * prestige_prog / prestige_limit, 20
+            ((self.panel.w() as u64 - 8) as f64 * prestige_prog.ratio(prestige_limit)) as u64, 20
         );
         rect!(
             fixed = true, 
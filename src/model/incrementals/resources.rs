@@ -1,15 +1,60 @@
 use super::*;
+use borsh::maybestd::io::{Error, ErrorKind, Read, Result as IoResult, Write};
+use serde::Deserialize;
 
-#[derive(Debug, Clone, PartialEq, BorshDeserialize, BorshSerialize)]
+// Saved ahead of a pack resource's name, the built-in resources are saved as their index in BUILT_IN
+const PACK_TAG: u8 = 5;
+
+// Ordered by declaration, which is also the display order
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum Resources {
     Research,
     Drones,
     Metals,
     Power,
     Prestige,
+    // Declared by a content pack, by index into PACK_RESOURCES
+    Pack(usize),
 }
 
 impl Resources {
+    pub const BUILT_IN: [Resources; 5] = [Resources::Research, Resources::Drones, Resources::Metals, Resources::Power, Resources::Prestige];
+
+    // A resource by display name, packs is the table Resources::Pack indexes into
+    pub fn lookup(name: &str, packs: &[ResourceDef]) -> Result<Resources, String> {
+        if let Some(resource) = Resources::BUILT_IN.iter().find(|resource| resource.to_string() == name) {
+            return Ok(resource.clone());
+        }
+        match packs.iter().position(|resource| resource.name == name) {
+            Some(index) => Ok(Resources::Pack(index)),
+            None => Err(format!("unknown resource \"{}\"", name)),
+        }
+    }
+
+    // Saved form, built-in resources by their index in BUILT_IN and pack resources by name
+    pub fn write<W: Write>(&self, writer: &mut W, packs: &[ResourceDef]) -> IoResult<()> {
+        match self {
+            Resources::Pack(index) => {
+                PACK_TAG.serialize(writer)?;
+                packs.get(*index).map_or("", |resource| resource.name.as_str()).serialize(writer)
+            }
+            _ => (Resources::BUILT_IN.iter().position(|resource| resource == self).unwrap_or_default() as u8).serialize(writer),
+        }
+    }
+
+    // Read a saved resource, None for a pack resource that packs does not declare
+    pub fn read<R: Read>(reader: &mut R, packs: &[ResourceDef]) -> IoResult<Option<Resources>> {
+        let tag = u8::deserialize_reader(reader)?;
+        if let Some(resource) = Resources::BUILT_IN.get(tag as usize) {
+            return Ok(Some(resource.clone()));
+        }
+        if tag != PACK_TAG {
+            return Err(Error::new(ErrorKind::InvalidData, format!("unknown resource tag {}", tag)));
+        }
+        let name = String::deserialize_reader(reader)?;
+        Ok(packs.iter().position(|resource| resource.name == name).map(Resources::Pack))
+    }
+
     pub fn description(&self) -> String {
         match self {
             Resources::Research => "RESEARCH. Scientific data about the Exoplanet.".to_string(),
@@ -17,6 +62,7 @@ impl Resources {
             Resources::Metals => "METALS. Crafting components for advanced tech.".to_string(), 
             Resources::Power => "POWER. Energy for amplifying other systems.".to_string(),
             Resources::Prestige => "PRESTIGE. Used to upgrade the autonomous probe.".to_string(),
+            Resources::Pack(index) => PACK_RESOURCES.get(*index).map_or(String::new(), |resource| resource.description.clone()),
         }
     }
 }
@@ -29,7 +75,36 @@ impl std::fmt::Display for Resources {
             Resources::Metals => "METALS",
             Resources::Power => "POWER",
             Resources::Prestige => "PRESTIGE",
+            Resources::Pack(index) => PACK_RESOURCES.get(*index).map_or("?", |resource| resource.name.as_str()),
         };
         write!(f, "{}", name)
     }
-}
\ No newline at end of file
+}
+impl std::str::FromStr for Resources {
+    type Err = String;
+
+    // Parse the display name, e.g. "RESEARCH"
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Resources::lookup(s, &PACK_RESOURCES)
+    }
+}
+
+// By display name, so data files can name pack resources too
+impl<'de> Deserialize<'de> for Resources {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        <String as Deserialize>::deserialize(deserializer)?.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+// Pack resources by name, their index depends on which packs are loaded
+impl BorshSerialize for Resources {
+    fn serialize<W: Write>(&self, writer: &mut W) -> IoResult<()> {
+        self.write(writer, &PACK_RESOURCES)
+    }
+}
+
+impl BorshDeserialize for Resources {
+    fn deserialize_reader<R: Read>(reader: &mut R) -> IoResult<Self> {
+        Resources::read(reader, &PACK_RESOURCES)?.ok_or_else(|| Error::new(ErrorKind::InvalidData, "resource of a pack that is not loaded"))
+    }
+}
@@ -1,12 +1,15 @@
 use super::*;
-use once_cell::sync::Lazy;
+use serde::Deserialize;
 
 #[derive(Debug, Clone, PartialEq, BorshDeserialize, BorshSerialize)]
 pub struct Upgrade {
+    // Index in the upgrade list this was loaded from
+    pub id: usize,
     pub name: String,
     pub description: String,
-    pub cost: Vec<(Resources, u64)>,
+    pub cost: Vec<(Resources, BigNum)>,
     pub unlocks: Vec<usize>, // Which index of the upgrade tree this upgrade leads to
+    pub effects: Vec<UpgradeEffect>,
     pub level: u32,
     pub max_level: u32,
 
@@ -16,20 +19,78 @@ pub struct Upgrade {
     pub tooltip: WrapBox,
     pub hovered: bool,
     pub display_lvl: bool,
+    // What the cost is missing, refreshed on update
+    pub shortfall: Option<Shortfall>,
 
     // Function to calculate the cost of the upgrade based on level
-    pub base_cost: Vec<(Resources, u64)>,
+    pub base_cost: Vec<(Resources, BigNum)>,
     pub cost_formula: CostFormula,
 }
 
+// What buying an upgrade does, applied by the station that sells it
+#[derive(Debug, Clone, PartialEq, BorshDeserialize, BorshSerialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UpgradeEffect {
+    // Build the station selling the upgrade
+    Construct,
+    // Build an attachment to the station
+    UnlockBuilding(Building),
+    AddDrone,
+    RemoveDrone,
+    // Raise the RESEARCH of each manual scan
+    AddScanner(u32),
+    // Added to the station's drone efficiency, one level per purchase
+    AddEfficiency(f32),
+    // Speed levels for stations with level based timings
+    AddSpeed(u32),
+    // Multiply the time a drone takes per cycle
+    ScaleInterval(f32),
+    TriggerEvent(Event),
+    // Give the player resources outright
+    Grant(Resources, u64),
+    // Prestige tree, read from the probe's upgrade levels rather than applied once
+    // Added per level to the multiplier on automated production of a resource
+    Boost(Resources, f32),
+    // Per level, one free level of the station's upgrade by id at the start of a run
+    HeadStart(StationId, usize),
+    // Scan the Exoplanet without holding it
+    AutoScan,
+    // Levels of the station's upgrade by id bought last run are granted again after a jump
+    Keep(StationId, usize),
+    // A tech the research complex's drones take this many seconds to finish, one drone alone,
+    // its other effects count once it is done
+    Research(u32),
+    // Queued at its station when bought, the upgrade applies this many seconds later
+    Project(u32),
+}
+
+#[derive(Debug, Clone, PartialEq, BorshDeserialize, BorshSerialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Building {
+    Fabricator,
+}
+
 #[derive(Debug, Clone, PartialEq, BorshDeserialize, BorshSerialize)]
 pub enum CostFormula {
     None,
     Double,
     Exponential,
 }
+impl std::str::FromStr for CostFormula {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "none" => Ok(CostFormula::None),
+            "double" => Ok(CostFormula::Double),
+            "exponential" => Ok(CostFormula::Exponential),
+            _ => Err(format!("unknown cost formula \"{}\"", s)),
+        }
+    }
+}
+
 impl CostFormula {
-    pub fn calculate_cost(&self, base_cost: Vec<(Resources, u64)>, n: u32) -> Vec<(Resources, u64)> {
+    pub fn calculate_cost(&self, base_cost: Vec<(Resources, BigNum)>, n: u32) -> Vec<(Resources, BigNum)> {
         match self {
             CostFormula::None => {
                 base_cost
@@ -37,7 +98,7 @@ impl CostFormula {
             CostFormula::Double => {
                 let mut new_cost = vec![];
                 for cost in base_cost.iter() {
-                    let prod = cost.1 * (2u64.pow(n));
+                    let prod = cost.1 * BigNum::from(2u64).powi(n);
                     new_cost.push((cost.0.clone(), prod));
                 }
                 new_cost
@@ -45,7 +106,7 @@ impl CostFormula {
             CostFormula::Exponential => {
                 let mut new_cost = vec![];
                 for cost in base_cost.iter() {
-                    let prod= (cost.1 as f32 * (1.1 as f32).powf(n as f32)) as u64;
+                    let prod = (cost.1 * BigNum::from_f64(1.1).powi(n)).floor();
                     // if n <= 5 {
                     //     prod = (cost.1 as f32 * (1.07 as f32).powf(n as f32)) as u64;
                     // } else if n <= 20 {
@@ -65,7 +126,33 @@ impl CostFormula {
 }
 
 impl Upgrade {
-    pub fn add_upgrade(mut_list: &mut Vec<Upgrade>, upgrade_list: &Lazy<Vec<Upgrade>>, index: usize, pop_up: Bounds) {
+    pub fn new(name: String, description: String, cost: Vec<(Resources, BigNum)>, cost_formula: CostFormula, max_level: u32, unlocks: Vec<usize>, display_lvl: bool) -> Self {
+        Upgrade {
+            id: 0,
+            name,
+            description,
+            cost: cost.clone(),
+            unlocks,
+            effects: vec![],
+            level: 0,
+            max_level,
+            entry: Btn::new("".to_string(), Bounds::new(-320, -320, 0, 0), true, 0),
+            buy_button: Btn::buy(),
+            tooltip: WrapBox::new("".to_string(), 0),
+            hovered: false,
+            display_lvl,
+            shortfall: None,
+            base_cost: cost,
+            cost_formula,
+        }
+    }
+
+    pub fn with_effects(mut self, effects: Vec<UpgradeEffect>) -> Self {
+        self.effects = effects;
+        self
+    }
+
+    pub fn add_upgrade(mut_list: &mut Vec<Upgrade>, upgrade_list: &[Upgrade], index: usize, pop_up: Bounds) {
         if index < upgrade_list.len() {
             let mut upgrade = upgrade_list[index].clone();
             upgrade.init(pop_up, mut_list.len());
@@ -73,6 +160,27 @@ impl Upgrade {
         }
     }
 
+    pub fn save(upgrades: &[Upgrade]) -> Vec<UpgradeSave> {
+        upgrades.iter().map(|upgrade| UpgradeSave { id: upgrade.id, level: upgrade.level }).collect()
+    }
+
+    // Rebuild saved upgrades from their list, bought upgrades have already added their unlocks
+    pub fn restore(saves: &[UpgradeSave], upgrade_list: &[Upgrade], pop_up: Bounds) -> Vec<Upgrade> {
+        let mut upgrades = vec![];
+        for save in saves.iter() {
+            let Some(upgrade) = upgrade_list.get(save.id) else { continue };
+            let mut upgrade = upgrade.clone();
+            if save.level > 0 {
+                upgrade.level = save.level.min(upgrade.max_level);
+                upgrade.unlocks = vec![];
+                upgrade.cost = upgrade.cost_formula.calculate_cost(upgrade.base_cost.clone(), upgrade.level);
+            }
+            upgrade.init(pop_up, upgrades.len());
+            upgrades.push(upgrade);
+        }
+        upgrades
+    }
+
     pub fn init(&mut self, pop_up: Bounds, index: usize) -> Self {
         let h = 
             if self.cost.len() > 0 { self.cost.len() as i32 * 20 }
@@ -109,41 +217,44 @@ impl Upgrade {
     }
 
 
-    pub fn update(&mut self, resources: &Vec<(Resources, u64)>) {
+    pub fn update(&mut self, resources: &Wallet) {
         self.entry.update();
         self.hovered = self.entry.state == BtnState::Hovered;
 
-        let mut buyable = false;
-        if self.level < self.max_level {
-            buyable = true;
-            let mut has_resources = true;
-            for cost in self.cost.iter() {
-                if resources.len() == 0 {
-                    buyable = false;
-                } else {
-                    let mut found = false;
-                    for resource in resources.iter() {
-                        if resource.0 == cost.0 {
-                            found = true;
-                            if resource.1 < cost.1 {
-                                buyable = false;
-                            }
-                        } 
-                    }
-                    if !found {
-                        has_resources = false;
-                    }
-                }
-            }
-            if !has_resources {
-                buyable = false;
-            }
-        }
-        self.buy_button.interactable = buyable;
+        self.shortfall = resources.check(&self.cost).err();
+        self.buy_button.interactable = self.level < self.max_level && self.shortfall.is_none();
 
         self.buy_button.update();
     }
 
+    // True when below max level and every cost is covered
+    pub fn affordable(&self, resources: &Wallet) -> bool {
+        self.level < self.max_level && resources.check(&self.cost).is_ok()
+    }
+
+    // Pay for the upgrade at index and level it up, adding whatever it unlocks, returns the purchased upgrade
+    // or what the cost is missing. The upgrade must be on offer, see Simulation::offers
+    pub fn buy(upgrades: &mut Vec<Upgrade>, index: usize, upgrade_list: &[Upgrade], pop_up: Bounds, resources: &mut Wallet) -> Result<Upgrade, Shortfall> {
+        resources.spend(&upgrades[index].cost)?;
+        Ok(Upgrade::advance(upgrades, index, upgrade_list, pop_up, false))
+    }
+
+    // Level up the upgrade at index once it is paid for, returns it as it was bought. Maxed upgrades stay
+    // listed with keep_maxed, where their level is what counts as in the prestige tree
+    pub fn advance(upgrades: &mut Vec<Upgrade>, index: usize, upgrade_list: &[Upgrade], pop_up: Bounds, keep_maxed: bool) -> Upgrade {
+        let upgrade = upgrades[index].clone();
+        let maxed = upgrades[index].next_level();
+        // Push next level upgrade to avail_upgrades
+        for unlock in upgrade.unlocks.iter() {
+            Upgrade::add_upgrade(upgrades, upgrade_list, *unlock, pop_up);
+        }
+        upgrades[index].unlocks = vec![]; // Clear unlocks after applying upgrade
+        if maxed && !keep_maxed {
+            upgrades.remove(index);
+        }
+        upgrade
+    }
+
     pub fn on_click(&self) -> bool {
         self.buy_button.on_click()
     }
@@ -175,8 +286,10 @@ impl Upgrade {
             for (resource, amount) in self.cost.iter() {
                 let sprite = format!("{}", resource);
                 sprite!(&sprite, fixed = true, x = self.entry.bounds.right() - 58, y = i * 20 + self.entry.bounds.y() + 2, wh = (16, 16), color = 0xffffffff);
-                let abbr = Numbers::format(amount.clone());
-                text!("{}", abbr; fixed = true, x = self.entry.bounds.right() as i32 - 38, y = i * 20 + self.entry.bounds.y() + 6);
+                let abbr = Numbers::format(*amount);
+                // The resource that is short in red
+                let color = if self.shortfall.as_ref().is_some_and(|shortfall| shortfall.resource == *resource) { 0xe43b44ff } else { 0xffffffff };
+                text!("{}", abbr; fixed = true, x = self.entry.bounds.right() as i32 - 38, y = i * 20 + self.entry.bounds.y() + 6, color = color);
                 i += 1;
             }
         }
@@ -1,399 +1,187 @@
 use super::*;
 use once_cell::sync::Lazy;
+use serde::Deserialize;
 
-pub static UNASSGIN: Lazy<Upgrade> = Lazy::new(|| Upgrade {
-    name: "UNASSIGN".to_string(),
-    description: "Unassign a DRONE from this station.".to_string(),
-    cost: vec![],
-    entry: Btn::new("".to_string(), Bounds::new(-320, -320, 0, 0), true, 0),
-    level: 0,
-    max_level: 0,
-    unlocks: vec![],
-    buy_button: Btn::buy(),
-    tooltip: WrapBox::new("".to_string(), 0),
-    hovered: false,
-    display_lvl: false,
-    cost_formula: CostFormula::None,
-    base_cost: vec![],
+// Designer-facing upgrade definitions, see the header of the file for the format
+pub const UPGRADE_DATA: &str = include_str!("../../../data/upgrades.toml");
+
+pub static UNASSGIN: Lazy<Upgrade> = Lazy::new(|| Upgrade::new(
+    "UNASSIGN".to_string(),
+    "Unassign a DRONE from this station.".to_string(),
+    vec![],
+    CostFormula::None,
+    0,
+    vec![],
+    false,
+).with_effects(vec![UpgradeEffect::RemoveDrone]));
+
+static UPGRADE_TABLES: Lazy<UpgradeTables> = Lazy::new(|| {
+    UpgradeTables::parse(UPGRADE_DATA).unwrap_or_else(|err| panic!("data/upgrades.toml: {}", err))
 });
 
-pub static EXOPLANET_UPGRADES: Lazy<Vec<Upgrade>> = Lazy::new(|| vec![
-    Upgrade {
-        name: "FIELD SCANNER".to_string(),
-        description: "Increase the amount of research gathered by pressing the Exoplanet by 1".to_string(),
-        cost: vec![(Resources::Research, 15)],
-        entry: Btn::new("".to_string(), Bounds::new(-320, -320, 0, 0), true, 0),
-        level: 0,
-        max_level: 4,
-        unlocks: vec![],
-        buy_button: Btn::buy(),
-        tooltip: WrapBox::new("".to_string(), 0),
-        hovered: false,
-        display_lvl: true,
-        cost_formula: CostFormula::Double,
-        base_cost: vec![(Resources::Research, 15)],
-    },
-    Upgrade {
-        name: "DEPLOY SURVEY DRONE".to_string(),
-        description: "Assign a DRONE to gather RESEARCH".to_string(),
-        cost: vec![(Resources::Drones, 1)],
-        entry: Btn::new("".to_string(), Bounds::new(-320, -320, 0, 0), true, 0),
-        level: 0,
-        max_level: 100,
-        unlocks: vec![2],
-        buy_button: Btn::buy(),
-        tooltip: WrapBox::new("".to_string(), 0),
-        hovered: false,
-        display_lvl: false,
-        cost_formula: CostFormula::None,
-        base_cost: vec![(Resources::Drones, 1)],
-    },
-    Upgrade {
-        name: "ADV. SENSORS".to_string(),
-        description: "Increase the amount of RESEARCH gathered by SURVEY DRONES by 16".to_string(),
-        cost: vec![(Resources::Metals, 32)],
-        entry: Btn::new("".to_string(), Bounds::new(-320, -320, 0, 0), true, 0),
-        level: 0,
-        max_level: 100,
-        unlocks: vec![],
-        buy_button: Btn::buy(),
-        tooltip: WrapBox::new("".to_string(), 0),
-        hovered: false,
-        display_lvl: true,
-        cost_formula: CostFormula::Double,
-        base_cost: vec![(Resources::Metals, 32)],
-    },
-    Upgrade {
-        name: "BIOSCANNERS".to_string(),
-        description: "Increase the speed of SURVEY DRONES by 10%".to_string(),
-        cost: vec![(Resources::Power, 40)],
-        entry: Btn::new("".to_string(), Bounds::new(-320, -320, 0, 0), true, 0),
-        level: 0,
-        max_level: 100,
-        unlocks: vec![],
-        buy_button: Btn::buy(),
-        tooltip: WrapBox::new("".to_string(), 0),
-        hovered: false,
-        display_lvl: true,
-        cost_formula: CostFormula::Double,
-        base_cost: vec![(Resources::Power, 40)],
-    },
-]);
+pub static EXOPLANET_UPGRADES: Lazy<Vec<Upgrade>> = Lazy::new(|| UPGRADE_TABLES.exoplanet.clone());
+pub static DEPOT_UPGRADES: Lazy<Vec<Upgrade>> = Lazy::new(|| UPGRADE_TABLES.depot.clone());
+pub static MINES_UPGRADES: Lazy<Vec<Upgrade>> = Lazy::new(|| UPGRADE_TABLES.mines.clone());
+pub static POWER_UPGRADES: Lazy<Vec<Upgrade>> = Lazy::new(|| UPGRADE_TABLES.power.clone());
+pub static GATE_UPGRADES: Lazy<Vec<Upgrade>> = Lazy::new(|| UPGRADE_TABLES.gate.clone());
+pub static COMPLEX_UPGRADES: Lazy<Vec<Upgrade>> = Lazy::new(|| UPGRADE_TABLES.complex.clone());
+pub static PROBE_UPGRADES: Lazy<Vec<Upgrade>> = Lazy::new(|| UPGRADE_TABLES.probe.clone());
+
+// The list a station's upgrade ids index into
+pub fn station_upgrades(station: StationId) -> &'static [Upgrade] {
+    UPGRADE_TABLES.list(station)
+}
+
+// One entry of the data file, content packs list theirs the same way
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct UpgradeDef {
+    name: String,
+    description: String,
+    #[serde(default)]
+    cost: Vec<(String, u64)>,
+    #[serde(default = "UpgradeDef::no_formula")]
+    formula: String,
+    max_level: u32,
+    #[serde(default)]
+    unlocks: Vec<usize>,
+    #[serde(default)]
+    display_level: bool,
+    #[serde(default)]
+    effects: Vec<UpgradeEffect>,
+}
+
+impl UpgradeDef {
+    fn no_formula() -> String {
+        "none".to_string()
+    }
+
+    // Costs may name the base resources and those in packs
+    fn build(&self, packs: &[ResourceDef]) -> Result<Upgrade, String> {
+        let mut cost = vec![];
+        for (resource, amount) in self.cost.iter() {
+            cost.push((Resources::lookup(resource, packs)?, BigNum::from(*amount)));
+        }
+        let formula = self.formula.parse::<CostFormula>()?;
+        let upgrade = Upgrade::new(self.name.clone(), self.description.clone(), cost, formula, self.max_level, self.unlocks.clone(), self.display_level);
+        Ok(upgrade.with_effects(self.effects.clone()))
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct UpgradeFile {
+    exoplanet: Vec<UpgradeDef>,
+    depot: Vec<UpgradeDef>,
+    mines: Vec<UpgradeDef>,
+    power: Vec<UpgradeDef>,
+    gate: Vec<UpgradeDef>,
+    complex: Vec<UpgradeDef>,
+    probe: Vec<UpgradeDef>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct UpgradeTables {
+    pub exoplanet: Vec<Upgrade>,
+    pub depot: Vec<Upgrade>,
+    pub mines: Vec<Upgrade>,
+    pub power: Vec<Upgrade>,
+    pub gate: Vec<Upgrade>,
+    pub complex: Vec<Upgrade>,
+    pub probe: Vec<Upgrade>,
+}
+
+impl UpgradeTables {
+    // Parse and validate upgrade definitions, errors name the list and upgrade at fault
+    pub fn parse(src: &str) -> Result<Self, String> {
+        let file: UpgradeFile = toml::from_str(src).map_err(|err| err.to_string())?;
+        let tables = UpgradeTables {
+            exoplanet: UpgradeTables::build_list("exoplanet", &file.exoplanet, &PACK_RESOURCES)?,
+            depot: UpgradeTables::build_list("depot", &file.depot, &PACK_RESOURCES)?,
+            mines: UpgradeTables::build_list("mines", &file.mines, &PACK_RESOURCES)?,
+            power: UpgradeTables::build_list("power", &file.power, &PACK_RESOURCES)?,
+            gate: UpgradeTables::build_list("gate", &file.gate, &PACK_RESOURCES)?,
+            complex: UpgradeTables::build_list("complex", &file.complex, &PACK_RESOURCES)?,
+            probe: UpgradeTables::build_list("probe", &file.probe, &PACK_RESOURCES)?,
+        };
+        tables.check_tree()?;
+        Ok(tables)
+    }
 
-pub static DEPOT_UPGRADES: Lazy<Vec<Upgrade>> = Lazy::new(|| vec![
-    Upgrade {
-        name: "CONSTRUCT".to_string(),
-        description: "Construct DRONE DEPOT.".to_string(),
-        cost: vec![(Resources::Research, 60)],
-        entry: Btn::new("".to_string(), Bounds::new(-320, -320, 0, 0), true, 0),
-        level: 0,
-        max_level: 1,
-        unlocks: vec![1],
-        buy_button: Btn::buy(),
-        tooltip: WrapBox::new("".to_string(), 0),
-        hovered: false,
-        display_lvl: false,
-        cost_formula: CostFormula::None,
-        base_cost: vec![(Resources::Research, 60)],
-    },
-    Upgrade {
-        name: "DRONE SHIPMENT".to_string(),
-        description: "Exchange RESEARCH for a DRONE".to_string(),
-        cost: vec![(Resources::Research, 120)],
-        entry: Btn::new("".to_string(), Bounds::new(-320, -320, 0, 0), true, 0),
-        buy_button: Btn::buy(),
-        level: 0,
-        max_level: 100,
-        unlocks: vec![],
-        tooltip: WrapBox::new("".to_string(), 0),
-        hovered: false,
-        display_lvl: false,
-        cost_formula: CostFormula::Exponential,
-        base_cost: vec![(Resources::Research, 120)],
-    },
-    Upgrade {
-        name: "CONSTRUCT FABRICATOR".to_string(),
-        description: "Construct FABRICATOR. Assign DRONES to convert METAL into DRONES".to_string(),
-        cost: vec![(Resources::Research, 4800), (Resources::Metals, 2400)],
-        entry: Btn::new("".to_string(), Bounds::new(-320, -320, 0, 0), true, 0),
-        level: 0,
-        max_level: 1,
-        unlocks: vec![],
-        buy_button: Btn::buy(),
-        tooltip: WrapBox::new("".to_string(), 0),
-        hovered: false,
-        display_lvl: false,
-        cost_formula: CostFormula::None,
-        base_cost: vec![(Resources::Research, 3800), (Resources::Metals, 1200)],
-    },    
-    Upgrade {
-        name: "DEPLOY MAKER DRONE".to_string(),
-        description: "Assign a DRONE to fabricate DRONES".to_string(),
-        cost: vec![(Resources::Drones, 1)],
-        entry: Btn::new("".to_string(), Bounds::new(-320, -320, 0, 0), true, 0),
-        level: 0,
-        max_level: 100,
-        unlocks: vec![],
-        buy_button: Btn::buy(),
-        tooltip: WrapBox::new("".to_string(), 0),
-        hovered: false,
-        display_lvl: false,
-        cost_formula: CostFormula::None,
-        base_cost: vec![(Resources::Drones, 1)],
-    },
-    Upgrade {
-        name: "CARGO CAPACITY".to_string(),
-        description: "Increase the amount of METALS gathered by MAKER DRONES by 24".to_string(),
-        cost: vec![(Resources::Metals, 860)],
-        entry: Btn::new("".to_string(), Bounds::new(-320, -320, 0, 0), true, 0),
-        level: 0,
-        max_level: 30,
-        unlocks: vec![],
-        buy_button: Btn::buy(),
-        tooltip: WrapBox::new("".to_string(), 0),
-        hovered: false,
-        display_lvl: true,
-        cost_formula: CostFormula::Double,
-        base_cost: vec![(Resources::Metals, 860)],
-    },
-    Upgrade {
-        name: "PLASMA TOOLS".to_string(),
-        description: "Increase the speed of MAKER DRONES by 20%".to_string(),
-        cost: vec![(Resources::Power,240)],
-        entry: Btn::new("".to_string(), Bounds::new(-320, -320, 0, 0), true, 0),
-        level: 0,
-        max_level: 30,
-        unlocks: vec![],
-        buy_button: Btn::buy(),
-        tooltip: WrapBox::new("".to_string(), 0),
-        hovered: false,
-        display_lvl: true,
-        cost_formula: CostFormula::Double,
-        base_cost: vec![(Resources::Power, 240)],
-    },
-]);
+    // The list a station sells from, the fabricator shares the depot's
+    pub fn list(&self, station: StationId) -> &[Upgrade] {
+        match station {
+            StationId::Probe => &self.probe,
+            StationId::Exoplanet => &self.exoplanet,
+            StationId::DroneDepot | StationId::Fabricator => &self.depot,
+            StationId::AsteroidMines => &self.mines,
+            StationId::PowerPlant => &self.power,
+            StationId::Jumpgate => &self.gate,
+            StationId::ResearchComplex => &self.complex,
+        }
+    }
 
-pub static MINES_UPGRADES: Lazy<Vec<Upgrade>> = Lazy::new(|| vec![
-    Upgrade {
-        name: "CONSTRUCT".to_string(),
-        description: "Construct ASTEROID MINES".to_string(),
-        cost: vec![(Resources::Research, 800)],
-        entry: Btn::new("".to_string(), Bounds::new(-320, -320, 0, 0), true, 0),
-        level: 0,
-        max_level: 1,
-        unlocks: vec![1, 2],
-        buy_button: Btn::buy(),
-        tooltip: WrapBox::new("".to_string(), 0),
-        hovered: false,
-        display_lvl: false,
-        cost_formula: CostFormula::None,
-        base_cost: vec![(Resources::Research, 800)],
-    },
-    Upgrade {
-        name: "DEPLOY MINING DRONE".to_string(),
-        description: "Assign a DRONE to gather METALS".to_string(),
-        cost: vec![(Resources::Drones, 1)],
-        entry: Btn::new("".to_string(), Bounds::new(-320, -320, 0, 0), true, 0),
-        level: 0,
-        max_level: 100,
-        unlocks: vec![],
-        buy_button: Btn::buy(),
-        tooltip: WrapBox::new("".to_string(), 0),
-        hovered: false,
-        display_lvl: false,
-        cost_formula: CostFormula::None,
-        base_cost: vec![(Resources::Drones, 1)],
-    },
-    Upgrade {
-        name: "DRILL AUGMENT".to_string(),
-        description: "Increase the amount of METALS gathered by MINING DRONES by 18".to_string(),
-        cost: vec![(Resources::Metals, 120)],
-        entry: Btn::new("".to_string(), Bounds::new(-320, -320, 0, 0), true, 0),
-        level: 0,
-        max_level: 20,
-        unlocks: vec![],
-        buy_button: Btn::buy(),
-        tooltip: WrapBox::new("".to_string(), 0),
-        hovered: false,
-        display_lvl: true,
-        cost_formula: CostFormula::Double,
-        base_cost: vec![(Resources::Metals, 120)],
-    },
-    Upgrade {
-        name: "ADV. THRUSTERS".to_string(),
-        description: "Increase the speed of MINING DRONES by 10%".to_string(),
-        cost: vec![(Resources::Power, 100)],
-        entry: Btn::new("".to_string(), Bounds::new(-320, -320, 0, 0), true, 0),
-        level: 0,
-        max_level: 20,
-        unlocks: vec![],
-        buy_button: Btn::buy(),
-        tooltip: WrapBox::new("".to_string(), 0),
-        hovered: false,
-        display_lvl: true,
-        cost_formula: CostFormula::Double,
-        base_cost: vec![(Resources::Power, 100)],
-    },
-]);
+    // Prestige tree effects must name upgrades that exist
+    fn check_tree(&self) -> Result<(), String> {
+        for (i, upgrade) in self.probe.iter().enumerate() {
+            for effect in upgrade.effects.iter() {
+                let (UpgradeEffect::HeadStart(station, id) | UpgradeEffect::Keep(station, id)) = effect else { continue };
+                if *id >= self.list(*station).len() {
+                    return Err(format!("[probe] {} ({}): no upgrade {} at {:?}", i, upgrade.name, id, station));
+                }
+            }
+        }
+        Ok(())
+    }
 
-pub static POWER_UPGRADES: Lazy<Vec<Upgrade>> = Lazy::new(|| vec![
-    Upgrade {
-        name: "CONSTRUCT".to_string(),
-        description: "Construct POWER PLANT".to_string(),
-        cost: vec![(Resources::Research, 3200), (Resources::Metals, 1200)],
-        entry: Btn::new("".to_string(), Bounds::new(-320, -320, 0, 0), true, 0),
-        level: 0,
-        max_level: 1,
-        unlocks: vec![1,2,3],
-        buy_button: Btn::buy(),
-        tooltip: WrapBox::new("".to_string(), 0),
-        hovered: false,
-        display_lvl: false,
-        cost_formula: CostFormula::None,
-        base_cost: vec![(Resources::Research, 3200), (Resources::Metals, 1200)],
-    },
-    Upgrade {
-        name: "DEPLOY CONDUIT DRONE".to_string(),
-        description: "Assign a DRONE to gather POWER".to_string(),
-        cost: vec![(Resources::Drones, 1)],
-        entry: Btn::new("".to_string(), Bounds::new(-320, -320, 0, 0), true, 0),
-        level: 0,
-        max_level: 100,
-        unlocks: vec![],
-        buy_button: Btn::buy(),
-        tooltip: WrapBox::new("".to_string(), 0),
-        hovered: false,
-        display_lvl: false,
-        cost_formula: CostFormula::None,
-        base_cost: vec![(Resources::Drones, 1)],
-    },
-    Upgrade {
-        name: "REFLECTOR CELLS".to_string(),
-        description: "Increase the amount of POWER gathered by CONDUIT DRONES by 12".to_string(),
-        cost: vec![(Resources::Metals, 220)],
-        entry: Btn::new("".to_string(), Bounds::new(-320, -320, 0, 0), true, 0),
-        level: 0,
-        max_level: 100,
-        unlocks: vec![],
-        buy_button: Btn::buy(),
-        tooltip: WrapBox::new("".to_string(), 0),
-        hovered: false,
-        display_lvl: true,
-        cost_formula: CostFormula::Double,
-        base_cost: vec![(Resources::Metals, 220)],
-    },
-    Upgrade {
-        name: "ARC BATTERIES".to_string(),
-        description: "Increase the speed of CONDUIT DRONES by 10%".to_string(),
-        cost: vec![(Resources::Power, 350)],
-        entry: Btn::new("".to_string(), Bounds::new(-320, -320, 0, 0), true, 0),
-        level: 0,
-        max_level: 100,
-        unlocks: vec![],
-        buy_button: Btn::buy(),
-        tooltip: WrapBox::new("".to_string(), 0),
-        hovered: false,
-        display_lvl: true,
-        cost_formula: CostFormula::Double,
-        base_cost: vec![(Resources::Power, 350)],
-    },
-]);
+    pub fn build_list(list: &str, defs: &[UpgradeDef], packs: &[ResourceDef]) -> Result<Vec<Upgrade>, String> {
+        let mut upgrades = vec![];
+        for (i, def) in defs.iter().enumerate() {
+            let mut upgrade = def.build(packs).map_err(|err| format!("[{}] {} ({}): {}", list, i, def.name, err))?;
+            upgrade.id = i;
+            if upgrade.max_level == 0 {
+                return Err(format!("[{}] {} ({}): max_level must be at least 1", list, i, def.name));
+            }
+            for unlock in upgrade.unlocks.iter() {
+                if *unlock >= defs.len() {
+                    return Err(format!("[{}] {} ({}): unlocks {} but the list has {} upgrades", list, i, def.name, unlock, defs.len()));
+                }
+                if *unlock == i {
+                    return Err(format!("[{}] {} ({}): unlocks itself", list, i, def.name));
+                }
+            }
+            upgrades.push(upgrade);
+        }
+        Ok(upgrades)
+    }
+}
 
-pub static GATE_UPGRADES: Lazy<Vec<Upgrade>> = Lazy::new(|| vec![
-    Upgrade {
-        name: "CONSTRUCT".to_string(),
-        description: "Construct JUMP GATE".to_string(),
-        cost: vec![(Resources::Research, 160_000), (Resources::Metals, 80_000), (Resources::Power, 40_000)],
-        entry: Btn::new("".to_string(), Bounds::new(-320, -320, 0, 0), true, 0),
-        level: 0,
-        max_level: 1,
-        unlocks: vec![1],
-        buy_button: Btn::buy(),
-        tooltip: WrapBox::new("".to_string(), 0),
-        hovered: false,
-        display_lvl: false,
-        cost_formula: CostFormula::None,
-        base_cost: vec![(Resources::Research, 240_000), (Resources::Metals, 90_000), (Resources::Power, 50_000)],
-    },
-    Upgrade {
-        name: "JUMP TO NEXT SECTOR".to_string(),
-        description: "Earn Prestige. Proceed to next sector and start again".to_string(),
-        cost: vec![], //vec![(Resources::Research, 240_000), (Resources::Metals, 90_000), (Resources::Power, 50_000)],
-        entry: Btn::new("".to_string(), Bounds::new(-320, -320, 0, 0), true, 0),
-        level: 0,
-        max_level: 10000000,
-        unlocks: vec![],
-        buy_button: Btn::buy(),
-        tooltip: WrapBox::new("".to_string(), 0),
-        hovered: false,
-        display_lvl: false,
-        cost_formula: CostFormula::None,
-        base_cost: vec![], //vec![(Resources::Research, 240_000), (Resources::Metals, 120_000), (Resources::Power, 80_000)],
-    },
-]);
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-pub static COMPLEX_UPGRADES: Lazy<Vec<Upgrade>> = Lazy::new(|| vec![
-    Upgrade {
-        name: "CONSTRUCT".to_string(),
-        description: "Construct RESEARCH COMPLEX".to_string(),
-        cost: vec![(Resources::Research, 6400), (Resources::Metals, 800)],
-        entry: Btn::new("".to_string(), Bounds::new(-320, -320, 0, 0), true, 0),
-        level: 0,
-        max_level: 1,
-        unlocks: vec![1],
-        buy_button: Btn::buy(),
-        tooltip: WrapBox::new("".to_string(), 0),
-        hovered: false,
-        display_lvl: false,
-        cost_formula: CostFormula::None,
-        base_cost: vec![(Resources::Research, 0)],
-    },
-    Upgrade {
-        name: "DEPLOY RESEARCH DRONE".to_string(),
-        description: "Assign a DRONE to complete RESEARCH PROJECTS".to_string(),
-        cost: vec![(Resources::Drones, 1)],
-        entry: Btn::new("".to_string(), Bounds::new(-320, -320, 0, 0), true, 0),
-        level: 0,
-        max_level: 100,
-        unlocks: vec![],
-        buy_button: Btn::buy(),
-        tooltip: WrapBox::new("".to_string(), 0),
-        hovered: false,
-        display_lvl: false,
-        cost_formula: CostFormula::None,
-        base_cost: vec![(Resources::Drones, 1)],
-    },
-]);
+    #[test]
+    fn upgrade_data_parses() {
+        let tables = UpgradeTables::parse(UPGRADE_DATA).unwrap();
+        for station in StationId::ALL {
+            let list = tables.list(station);
+            assert!(!list.is_empty(), "{:?} has no upgrades", station);
+            for (i, upgrade) in list.iter().enumerate() {
+                assert_eq!(upgrade.id, i, "{}", upgrade.name);
+                assert!(upgrade.max_level >= 1, "{}", upgrade.name);
+                assert_eq!(upgrade.level, 0, "{}", upgrade.name);
+                assert!(upgrade.unlocks.iter().all(|unlock| *unlock < list.len() && *unlock != i), "{}", upgrade.name);
+            }
+        }
+    }
 
-pub static PROBE_UPGRADES: Lazy<Vec<Upgrade>> = Lazy::new(|| vec![
-    Upgrade {
-        name: "BASE".to_string(),
-        description: "Increase the BASE of all DRONES by 2x".to_string(),
-        cost: vec![(Resources::Prestige, 1)],
-        entry: Btn::new("".to_string(), Bounds::new(-320, -320, 0, 0), true, 0),
-        level: 0,
-        max_level: 1,
-        unlocks: vec![1],
-        buy_button: Btn::buy(),
-        tooltip: WrapBox::new("".to_string(), 0),
-        hovered: false,
-        display_lvl: false,
-        cost_formula: CostFormula::None,
-        base_cost: vec![(Resources::Research, 0)],
-    },
-    Upgrade {
-        name: "EFFECIENCY".to_string(),
-        description: "Increase the EFF. of all DRONES by 2x".to_string(),
-        cost: vec![(Resources::Drones, 1)],
-        entry: Btn::new("".to_string(), Bounds::new(-320, -320, 0, 0), true, 0),
-        level: 0,
-        max_level: 100,
-        unlocks: vec![],
-        buy_button: Btn::buy(),
-        tooltip: WrapBox::new("".to_string(), 0),
-        hovered: false,
-        display_lvl: false,
-        cost_formula: CostFormula::None,
-        base_cost: vec![(Resources::Drones, 1)],
-    },
-]);
\ No newline at end of file
+    #[test]
+    fn upgrade_data_is_checked() {
+        let src = UPGRADE_DATA.replacen("max_level = 4", "max_level = 0", 1);
+        assert!(UpgradeTables::parse(&src).unwrap_err().starts_with("[exoplanet] 0 (FIELD SCANNER)"));
+        let src = UPGRADE_DATA.replacen("unlocks = [2]", "unlocks = [99]", 1);
+        assert!(UpgradeTables::parse(&src).unwrap_err().contains("unlocks 99"));
+        let src = UPGRADE_DATA.replacen("formula = \"double\"", "formula = \"triple\"", 1);
+        assert!(UpgradeTables::parse(&src).is_err());
+    }
+}
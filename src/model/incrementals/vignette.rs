@@ -10,14 +10,15 @@ pub struct Vignette {
     clouds: Vec<Cloud>,
 }
 impl Vignette {
-    pub fn new() -> Self {
+    pub fn new(sector: &Sector, rng: &mut Rng) -> Self {
+        let depot = sector.place(StationId::DroneDepot, DEPOT_BOX);
         Vignette {
             fade: true,
             fade_prog: 255.,
             stage: 0,
-            depot: vec![Cloud::new((320, 200), 320, 240), Cloud::new((DEPOT_BOX.0-16, DEPOT_BOX.1-16), 48, 0)],
-            mines: vec![Cloud::new((320, 200), 480, 320),],
-            clouds: vec![Cloud::new((320, 200), 780, 480)],
+            depot: vec![Cloud::new((320, 200), 320, 240, rng), Cloud::new((depot.0-16, depot.1-16), 48, 0, rng)],
+            mines: vec![Cloud::new((320, 200), 480, 320, rng),],
+            clouds: vec![Cloud::new((320, 200), 780, 480, rng)],
         }
     }
 
@@ -0,0 +1,308 @@
+use super::*;
+use borsh::maybestd::io::{Read, Result as IoResult, Write};
+use std::collections::BTreeMap;
+
+// Written ahead of the balances, older saves start with the length of a Vec<(Resources, u64)> instead
+const WALLET_TAG: u32 = u32::MAX;
+// Ticks per income sample
+const INCOME_TICKS: u32 = 60;
+// Seconds of samples averaged into the income rates
+const INCOME_WINDOW: usize = 10;
+
+// Where a deposit came from, offline catch-up is left out of the income rates
+#[derive(Debug, Clone, Copy, PartialEq, BorshDeserialize, BorshSerialize)]
+pub enum Source {
+    Manual,
+    Automated,
+    Offline,
+}
+
+// Flow of one resource over one second
+#[derive(Debug, Clone, Copy, Default, PartialEq, BorshDeserialize, BorshSerialize)]
+pub struct IncomeSample {
+    pub manual: BigNum,
+    pub automated: BigNum,
+    pub consumed: BigNum,
+}
+
+// Rolling window of per second samples
+#[derive(Debug, Clone, Default, PartialEq, BorshDeserialize, BorshSerialize)]
+pub struct Income {
+    samples: Vec<IncomeSample>,
+    current: IncomeSample,
+}
+
+impl Income {
+    pub fn record(&mut self, source: Source, amount: BigNum) {
+        match source {
+            Source::Manual => self.current.manual += amount,
+            Source::Automated => self.current.automated += amount,
+            Source::Offline => {}
+        }
+    }
+
+    pub fn consume(&mut self, amount: BigNum) {
+        self.current.consumed += amount;
+    }
+
+    // Close the current second
+    pub fn roll(&mut self) {
+        self.samples.push(self.current);
+        if self.samples.len() > INCOME_WINDOW {
+            self.samples.remove(0);
+        }
+        self.current = IncomeSample::default();
+    }
+
+    // Average flow per second over the window
+    pub fn rate(&self) -> IncomeSample {
+        let mut total = IncomeSample::default();
+        for sample in self.samples.iter() {
+            total.manual += sample.manual;
+            total.automated += sample.automated;
+            total.consumed += sample.consumed;
+        }
+        let secs = BigNum::from(self.samples.len().max(1) as u64);
+        IncomeSample {
+            manual: total.manual / secs,
+            automated: total.automated / secs,
+            consumed: total.consumed / secs,
+        }
+    }
+
+    // Net change per second, e.g. "+1.2K/s" or "-30/s"
+    pub fn label(&self) -> String {
+        let rate = self.rate();
+        let gained = rate.manual + rate.automated;
+        if gained >= rate.consumed {
+            format!("+{}/s", Numbers::format(gained - rate.consumed))
+        } else {
+            format!("-{}/s", Numbers::format(rate.consumed - gained))
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, BorshDeserialize, BorshSerialize)]
+pub struct Balance {
+    pub amount: BigNum,
+    // Lifetime totals
+    pub earned: BigNum,
+    pub spent: BigNum,
+    pub income: Income,
+}
+
+// The player's resources, one balance per kind, iterated in Resources order
+#[derive(Debug, Clone, PartialEq)]
+pub struct Wallet {
+    balances: BTreeMap<Resources, Balance>,
+    ticks: u32,
+}
+
+impl Wallet {
+    pub fn new() -> Self {
+        let mut balances = BTreeMap::new();
+        for resource in Resources::BUILT_IN {
+            balances.insert(resource, Balance::default());
+        }
+        Wallet { balances, ticks: 0 }
+    }
+
+    pub fn amount(&self, resource: &Resources) -> BigNum {
+        self.balances.get(resource).map(|balance| balance.amount).unwrap_or(BigNum::ZERO)
+    }
+
+    pub fn balance(&self, resource: &Resources) -> Balance {
+        self.balances.get(resource).cloned().unwrap_or_default()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&Resources, &Balance)> {
+        self.balances.iter()
+    }
+
+    pub fn deposit(&mut self, resource: Resources, amount: BigNum, source: Source) {
+        let balance = self.balances.entry(resource).or_default();
+        balance.amount += amount;
+        balance.earned += amount;
+        balance.income.record(source, amount);
+    }
+
+    // Take up to amount, counted as spent and against income
+    fn take(&mut self, resource: &Resources, amount: BigNum) {
+        let Some(balance) = self.balances.get_mut(resource) else {
+            return;
+        };
+        let taken = amount.min(balance.amount);
+        balance.amount -= taken;
+        balance.spent += taken;
+        balance.income.consume(taken);
+    }
+
+    // Check every cost, a resource listed twice must cover both
+    pub fn check(&self, cost: &[(Resources, BigNum)]) -> Result<(), Shortfall> {
+        for (resource, _) in cost.iter() {
+            let needed = cost.iter()
+                .filter(|(res, _)| res == resource)
+                .fold(BigNum::ZERO, |total, (_, amount)| total + *amount);
+            let available = self.amount(resource);
+            if available < needed {
+                return Err(Shortfall { resource: resource.clone(), needed, available });
+            }
+        }
+        Ok(())
+    }
+
+    // All or nothing, balances are only touched once the whole cost is covered
+    pub fn spend(&mut self, cost: &[(Resources, BigNum)]) -> Result<(), Shortfall> {
+        self.check(cost)?;
+        for (resource, amount) in cost.iter() {
+            self.take(resource, *amount);
+        }
+        Ok(())
+    }
+
+    // Give back a cost spent earlier, no longer counted as spent. The income samples it was taken
+    // from have long rolled over
+    pub fn refund(&mut self, cost: &[(Resources, BigNum)]) {
+        for (resource, amount) in cost.iter() {
+            let balance = self.balances.entry(resource.clone()).or_default();
+            balance.amount += *amount;
+            balance.spent -= *amount;
+        }
+    }
+
+    // Balances without the income samples, for saving
+    pub fn persistent(&self) -> Wallet {
+        let mut wallet = self.clone();
+        for balance in wallet.balances.values_mut() {
+            balance.income = Income::default();
+        }
+        wallet.ticks = 0;
+        wallet
+    }
+
+    // Called once per simulation tick to sample income
+    pub fn tick(&mut self) {
+        self.ticks += 1;
+        if self.ticks < INCOME_TICKS {
+            return;
+        }
+        self.ticks = 0;
+        for balance in self.balances.values_mut() {
+            balance.income.roll();
+        }
+    }
+}
+
+impl Default for Wallet {
+    fn default() -> Self {
+        Wallet::new()
+    }
+}
+
+// Balances from the old layout, what is held counts as earned
+impl From<Vec<(Resources, BigNum)>> for Wallet {
+    fn from(resources: Vec<(Resources, BigNum)>) -> Self {
+        let mut wallet = Wallet::new();
+        for (resource, amount) in resources {
+            wallet.deposit(resource, amount, Source::Offline);
+        }
+        wallet
+    }
+}
+
+impl BorshSerialize for Wallet {
+    fn serialize<W: Write>(&self, writer: &mut W) -> IoResult<()> {
+        WALLET_TAG.serialize(writer)?;
+        self.balances.serialize(writer)?;
+        self.ticks.serialize(writer)
+    }
+}
+
+impl BorshDeserialize for Wallet {
+    fn deserialize_reader<R: Read>(reader: &mut R) -> IoResult<Self> {
+        let tag = u32::deserialize_reader(reader)?;
+        if tag != WALLET_TAG {
+            // Legacy save, tag is the number of (Resources, u64) entries that follow
+            let mut resources = vec![];
+            for _ in 0..tag {
+                let (resource, amount) = <(Resources, u64)>::deserialize_reader(reader)?;
+                resources.push((resource, BigNum::from(amount)));
+            }
+            return Ok(Wallet::from(resources));
+        }
+        // Balances of resources no loaded pack declares are dropped, as Packs::restore drops their stations
+        let mut balances = BTreeMap::new();
+        for _ in 0..u32::deserialize_reader(reader)? {
+            let resource = Resources::read(reader, &PACK_RESOURCES)?;
+            let balance = Balance::deserialize_reader(reader)?;
+            if let Some(resource) = resource {
+                balances.insert(resource, balance);
+            }
+        }
+        Ok(Wallet {
+            balances,
+            ticks: u32::deserialize_reader(reader)?,
+        })
+    }
+}
+
+// The first cost a wallet could not cover
+#[derive(Debug, Clone, PartialEq, BorshDeserialize, BorshSerialize)]
+pub struct Shortfall {
+    pub resource: Resources,
+    pub needed: BigNum,
+    pub available: BigNum,
+}
+
+impl Shortfall {
+    pub fn missing(&self) -> BigNum {
+        self.needed - self.available
+    }
+}
+
+impl std::fmt::Display for Shortfall {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} more {} needed", self.missing(), self.resource)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn legacy_wallet_reads() {
+        let legacy: Vec<(Resources, u64)> = vec![(Resources::Research, 1500), (Resources::Drones, 3)];
+        let wallet = Wallet::try_from_slice(&legacy.try_to_vec().unwrap()).unwrap();
+        assert_eq!(wallet.amount(&Resources::Research), BigNum::from(1500u64));
+        assert_eq!(wallet.amount(&Resources::Drones), BigNum::from(3u64));
+        assert_eq!(wallet.amount(&Resources::Metals), BigNum::ZERO);
+    }
+
+    #[test]
+    fn wallet_round_trips() {
+        let mut wallet = Wallet::new();
+        wallet.deposit(Resources::Metals, BigNum::from(42u64), Source::Manual);
+        assert!(wallet.spend(&[(Resources::Metals, BigNum::from(40u64))]).is_ok());
+        let read = Wallet::try_from_slice(&wallet.try_to_vec().unwrap()).unwrap();
+        assert_eq!(read, wallet);
+    }
+
+    #[test]
+    fn spend_is_all_or_nothing_and_consumed() {
+        let mut wallet = Wallet::new();
+        wallet.deposit(Resources::Metals, BigNum::from(100u64), Source::Automated);
+        let short = wallet.spend(&[(Resources::Metals, BigNum::from(60u64)), (Resources::Power, BigNum::from(1u64))]).unwrap_err();
+        assert_eq!(short.resource, Resources::Power);
+        assert_eq!(wallet.amount(&Resources::Metals), BigNum::from(100u64));
+
+        assert!(wallet.spend(&[(Resources::Metals, BigNum::from(60u64))]).is_ok());
+        for _ in 0..INCOME_TICKS {
+            wallet.tick();
+        }
+        let balance = wallet.balance(&Resources::Metals);
+        assert_eq!(balance.amount, BigNum::from(40u64));
+        assert_eq!(balance.spent, BigNum::from(60u64));
+        assert_eq!(balance.income.rate().consumed, BigNum::from(60u64));
+    }
+}
@@ -1,12 +1,27 @@
 pub use super::*;
 use std::any::Any;
 
+mod achievements;
+pub use achievements::*;
+
+mod auto_buyer;
+pub use auto_buyer::*;
+
+mod big_num;
+pub use big_num::*;
+
 mod btn;
 pub use btn::*;
 
 mod camera_ctrl;
 pub use camera_ctrl::*;
 
+mod clipboard;
+pub use clipboard::*;
+
+mod content;
+pub use content::*;
+
 mod events;
 pub use events::*;
 
@@ -22,14 +37,46 @@ pub use noise::*;
 mod numbers;
 pub use numbers::*;
 
+mod offline;
+pub use offline::*;
+
 mod player;
 pub use player::*;
 
 mod pois;
 pub use pois::*;
 
+mod random;
+pub use random::*;
+
+mod research;
+pub use research::*;
+
+mod replay;
+pub use replay::*;
+// Named so they win over the engine's versions every module gets from the prelude
+pub use replay::{gamepad, pointer, Pointer};
+
+mod save;
+pub use save::*;
+
+mod save_data;
+pub use save_data::*;
+
+mod save_slots;
+pub use save_slots::*;
+
+mod sector;
+pub use sector::*;
+
 mod sfx;
 pub use sfx::*;
 
+mod simulation;
+pub use simulation::*;
+
+mod stats;
+pub use stats::*;
+
 mod text_box;
 pub use text_box::*;
\ No newline at end of file
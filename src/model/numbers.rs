@@ -1,27 +1,119 @@
 use super::*;
+use once_cell::sync::Lazy;
+use std::sync::Mutex;
+
+// Suffixes for each power of a thousand, past the last one numbers fall back to scientific
+const SUFFIXES: [&str; 12] = ["", "K", "M", "B", "T", "Qa", "Qi", "Sx", "Sp", "Oc", "No", "Dc"];
+
+#[derive(Debug, Clone, Copy, PartialEq, BorshDeserialize, BorshSerialize)]
+pub enum Notation {
+    // 12.3M
+    Suffix,
+    // 1.23e7
+    Scientific,
+    // 12.3e6
+    Engineering,
+}
+
+impl Notation {
+    pub fn next(&self) -> Notation {
+        match self {
+            Notation::Suffix => Notation::Scientific,
+            Notation::Scientific => Notation::Engineering,
+            Notation::Engineering => Notation::Suffix,
+        }
+    }
+
+    pub fn label(&self) -> &str {
+        match self {
+            Notation::Suffix => "12.3K",
+            Notation::Scientific => "1.23e4",
+            Notation::Engineering => "12.3e3",
+        }
+    }
+}
+
+// Display setting shared by every formatted number, changed from the options menu
+static NOTATION: Lazy<Mutex<Notation>> = Lazy::new(|| Mutex::new(Notation::Suffix));
 
 pub struct Numbers {}
 impl Numbers {
-    pub fn format(num: u64) -> String {
-        let mut num = num as f64;
-        let mut suffix = "";
-
-        if num >= 10_000.0 {
-            if num >= 1_000_000_000.0 {
-                num /= 1_000_000_000.0;
-                suffix = "B";
-            } else if num >= 1_000_000.0 {
-                num /= 1_000_000.0;
-                suffix = "M";
-            } else if num >= 10_000.0 {
-                num /= 1_000.0;
-                suffix = "K";
+    pub fn format(num: BigNum) -> String {
+        Numbers::format_as(num, Numbers::notation())
+    }
+
+    pub fn format_as(num: BigNum, notation: Notation) -> String {
+        // For numbers under 10,000, return without decimals
+        if num < BigNum::from(10_000u64) {
+            return format!("{:.0}", num.to_f64());
+        }
+        // Round to three significant digits first, so 999.95K carries over to 1.00M
+        let log = num.log10();
+        let mut exp = log.floor() as i64;
+        let mut digits = (10f64.powf(log - exp as f64) * 100.).round() / 100.;
+        if digits >= 10. {
+            digits /= 10.;
+            exp += 1;
+        }
+        let shown = match notation {
+            Notation::Suffix => {
+                if (exp / 3) as usize >= SUFFIXES.len() {
+                    return Numbers::format_as(num, Notation::Scientific);
+                }
+                exp - exp % 3
             }
-            // Format to the tenths place
-            format!("{:.1}{}", num, suffix)
+            Notation::Scientific => exp,
+            Notation::Engineering => exp - exp % 3,
+        };
+        let value = digits * 10f64.powi((exp - shown) as i32);
+        let decimals = (2 - (exp - shown)) as usize;
+        match notation {
+            Notation::Suffix => format!("{:.*}{}", decimals, value, SUFFIXES[(shown / 3) as usize]),
+            _ => format!("{:.*}e{}", decimals, value, shown),
+        }
+    }
+
+    pub fn notation() -> Notation {
+        *NOTATION.lock().unwrap()
+    }
+
+    pub fn set_notation(notation: Notation) {
+        *NOTATION.lock().unwrap() = notation;
+    }
+
+    pub fn format_duration(secs: u64) -> String {
+        let hours = secs / 3600;
+        let minutes = (secs % 3600) / 60;
+        if hours > 0 {
+            format!("{}h {}m", hours, minutes)
+        } else if minutes > 0 {
+            format!("{}m", minutes)
         } else {
-            // For numbers under 10,000, return without decimals
-            format!("{:.0}", num)
+            format!("{}s", secs)
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_each_notation() {
+        let num = BigNum::from(12_345_678u64);
+        assert_eq!(Numbers::format_as(BigNum::from(1_234u64), Notation::Suffix), "1234");
+        assert_eq!(Numbers::format_as(num, Notation::Suffix), "12.3M");
+        assert_eq!(Numbers::format_as(BigNum::from(123_456u64), Notation::Suffix), "123K");
+        assert_eq!(Numbers::format_as(num, Notation::Scientific), "1.23e7");
+        assert_eq!(Numbers::format_as(num, Notation::Engineering), "12.3e6");
+        // Past the last suffix
+        assert_eq!(Numbers::format_as(BigNum::new(1.5, 40), Notation::Suffix), "1.50e40");
+    }
+
+    #[test]
+    fn rounding_carries_into_the_next_suffix() {
+        assert_eq!(Numbers::format_as(BigNum::from(999_950u64), Notation::Suffix), "1.00M");
+        assert_eq!(Numbers::format_as(BigNum::from(999_950u64), Notation::Scientific), "1.00e6");
+        assert_eq!(Numbers::format_as(BigNum::from(99_999u64), Notation::Engineering), "100e3");
+    }
+}
@@ -0,0 +1,157 @@
+use super::*;
+
+// Offline catch-up is skipped for absences shorter than this
+pub const OFFLINE_MIN_SECS: u64 = 60;
+// Default cap on how much absence is credited (8 hours)
+pub const OFFLINE_MAX_SECS: u64 = 8 * 60 * 60;
+
+#[derive(Debug, Clone, PartialEq, BorshDeserialize, BorshSerialize)]
+pub struct OfflineProgress {
+    pub enabled: bool,
+    pub min_secs: u64,
+    pub max_secs: u64,
+    // Fraction of the estimated production that is actually granted
+    pub efficiency: f32,
+}
+
+impl OfflineProgress {
+    pub fn new() -> Self {
+        OfflineProgress {
+            enabled: true,
+            min_secs: OFFLINE_MIN_SECS,
+            max_secs: OFFLINE_MAX_SECS,
+            efficiency: 1.0,
+        }
+    }
+
+    // Seconds of absence to credit, or None when catch-up should not run
+    pub fn credited_secs(&self, saved_at: u64, now: u64) -> Option<u64> {
+        if !self.enabled || saved_at == 0 || now <= saved_at {
+            return None;
+        }
+        let elapsed = (now - saved_at) / 1000;
+        if elapsed < self.min_secs {
+            return None;
+        }
+        Some(elapsed.min(self.max_secs))
+    }
+
+    // Seconds of production the credited absence pays out, bonus is the efficiency earned on top
+    pub fn idle_secs(&self, credited: u64, bonus: f32) -> f32 {
+        credited as f32 * (self.efficiency + bonus)
+    }
+}
+
+impl Default for OfflineProgress {
+    fn default() -> Self {
+        OfflineProgress::new()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, BorshDeserialize, BorshSerialize)]
+pub struct AwayReport {
+    pub elapsed: u64,
+    pub credited: u64,
+    pub gained: Vec<(Resources, BigNum)>,
+    pub spent: Vec<(Resources, BigNum)>,
+    // Names of the projects finished meanwhile
+    pub completed: Vec<String>,
+}
+
+impl AwayReport {
+    pub fn new(elapsed: u64, credited: u64) -> Self {
+        AwayReport {
+            elapsed,
+            credited,
+            gained: vec![],
+            spent: vec![],
+            completed: vec![],
+        }
+    }
+
+    pub fn gain(&mut self, resource: (Resources, BigNum)) {
+        if !resource.1.is_zero() {
+            self.gained.push(resource);
+        }
+    }
+
+    pub fn spend(&mut self, resource: (Resources, BigNum)) {
+        if !resource.1.is_zero() {
+            self.spent.push(resource);
+        }
+    }
+
+    pub fn complete(&mut self, project: &str) {
+        self.completed.push(project.to_string());
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.gained.is_empty() && self.spent.is_empty() && self.completed.is_empty()
+    }
+
+    // "While you were away" summary, one message per resource
+    pub fn dialogue(&self) -> Dialogue {
+        let mut messages = vec![format!("While you were away for {}, your drones kept working.", Numbers::format_duration(self.elapsed))];
+        if self.credited < self.elapsed {
+            messages.push(format!("Only the last {} could be recorded.", Numbers::format_duration(self.credited)));
+        }
+        for (resource, amount) in self.gained.iter() {
+            messages.push(format!("{} +{}", resource, Numbers::format(*amount)));
+        }
+        for (resource, amount) in self.spent.iter() {
+            messages.push(format!("{} -{}", resource, Numbers::format(*amount)));
+        }
+        for project in self.completed.iter() {
+            messages.push(format!("{} COMPLETE", project));
+        }
+        Dialogue {
+            messages,
+            camera_pos: vec![],
+            d_box: DialogueBox::new(),
+            event_broadcast: 0,
+            prompt: false,
+        }
+    }
+}
+
+// Wall clock in milliseconds since the unix epoch
+pub fn now_millis() -> u64 {
+    #[cfg(target_family = "wasm")]
+    {
+        time::now()
+    }
+    #[cfg(not(target_family = "wasm"))]
+    {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn credits_between_the_minimum_and_the_cap() {
+        let offline = OfflineProgress::new();
+        let saved_at = 1_000_000;
+        assert_eq!(offline.credited_secs(saved_at, saved_at + 59_999), None);
+        assert_eq!(offline.credited_secs(saved_at, saved_at + 60_000), Some(60));
+        assert_eq!(offline.credited_secs(saved_at, saved_at + 2 * 60 * 60 * 1000), Some(2 * 60 * 60));
+        assert_eq!(offline.credited_secs(saved_at, saved_at + 30 * 60 * 60 * 1000), Some(OFFLINE_MAX_SECS));
+        // Never saved, clock turned back or switched off
+        assert_eq!(offline.credited_secs(0, saved_at), None);
+        assert_eq!(offline.credited_secs(saved_at, saved_at - 1), None);
+        assert_eq!(OfflineProgress { enabled: false, ..OfflineProgress::new() }.credited_secs(saved_at, saved_at + 60_000), None);
+    }
+
+    #[test]
+    fn efficiency_scales_the_payout() {
+        let half = OfflineProgress { efficiency: 0.5, ..OfflineProgress::new() };
+        assert_eq!(OfflineProgress::new().idle_secs(600, 0.), 600.);
+        assert_eq!(half.idle_secs(600, 0.), 300.);
+        assert_eq!(half.idle_secs(600, 0.25), 450.);
+    }
+}
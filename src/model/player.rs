@@ -2,7 +2,7 @@ use super::*;
 
 #[derive(Debug, Clone, PartialEq, BorshDeserialize, BorshSerialize)]
 pub struct Player {
-    pub resources: Vec<(Resources, u64)>,
+    pub resources: Wallet,
     hitbox: Bounds,
     target_pos: (f32, f32),
     dir: f32,
@@ -10,15 +10,15 @@ pub struct Player {
     pub camera: CameraCtrl,
 
     scans: Vec<Scan>,
-    prestiged: bool,
+    pub prestiged: bool,
     jumping: bool,
     jump_timer: u32,
     gate_aligned: bool,
 
-    pub prestige_prog: u64,
+    pub prestige_prog: BigNum,
     pub prestige_index: u32,
-    pub prestige_limit: u64,
-    pub prestige_earned: u64,
+    pub prestige_limit: BigNum,
+    pub prestige_earned: BigNum,
     pop_up: PopUp,
     hovered: bool,
     pub hovered_else: bool,
@@ -26,16 +26,16 @@ pub struct Player {
 }
 
 impl Player {
-    pub fn load(prestiged: bool, prestige_earned: u64, prestige_prog: u64, prestige_index: u32, avail_upgrades: Vec<Upgrade>) -> Self {
+    pub fn load(prestiged: bool, prestige_earned: BigNum, prestige_prog: BigNum, prestige_index: u32, avail_upgrades: Vec<Upgrade>) -> Self {
 //        let hitbox = Bounds::new(xy)
         Player {
-            resources: vec![
-                (Resources::Research, 4000000000),
-                (Resources::Drones, 4000000000),
-                (Resources::Metals, 4000000000),
-                (Resources::Power, 4000000000),
+            resources: Wallet::from(vec![
+                (Resources::Research, BigNum::from(4000000000u64)),
+                (Resources::Drones, BigNum::from(4000000000u64)),
+                (Resources::Metals, BigNum::from(4000000000u64)),
+                (Resources::Power, BigNum::from(4000000000u64)),
                 (Resources::Prestige, prestige_earned),
-            ],
+            ]),
             hitbox: Bounds::new(320., 600., 16, 16),
             target_pos: (0., 0.),
             dir: 0.,
@@ -49,8 +49,8 @@ impl Player {
 
             prestige_prog,
             prestige_index,
-            prestige_limit: CostFormula::Exponential.calculate_cost(vec![(Resources::Prestige, 200_000)], prestige_index)[0].1,
-            prestige_earned: 0,
+            prestige_limit: CostFormula::Exponential.calculate_cost(vec![(Resources::Prestige, BigNum::from(200_000u64))], prestige_index)[0].1,
+            prestige_earned: BigNum::ZERO,
             pop_up: PopUp::new("RESEARCH PROBE".to_string(), Resources::Prestige),
             hovered: false,
             hovered_else: false,
@@ -58,7 +58,26 @@ impl Player {
         }
     } 
 
-    pub fn update(&mut self, event_manager: &mut EventManager) {
+    pub fn save(&self) -> PlayerSave {
+        PlayerSave {
+            resources: self.resources.persistent(),
+            prestiged: self.prestiged,
+            prestige_prog: self.prestige_prog,
+            prestige_index: self.prestige_index,
+            prestige_earned: self.prestige_earned,
+            upgrades: Upgrade::save(&self.avail_upgrades),
+        }
+    }
+
+    pub fn restore(save: &PlayerSave) -> Self {
+        let mut player = Player::load(save.prestiged, BigNum::ZERO, save.prestige_prog, save.prestige_index, vec![]);
+        player.resources = save.resources.clone();
+        player.prestige_earned = save.prestige_earned;
+        player.avail_upgrades = Upgrade::restore(&save.upgrades, &PROBE_UPGRADES, player.pop_up.panel);
+        player
+    }
+
+    pub fn update(&mut self, event_manager: &mut EventManager, sector: &Sector, input: &mut SimInput) {
         self.hovered_else = false;
         if !self.jumping {
             self.target_pos = camera::xy();
@@ -76,9 +95,8 @@ impl Player {
 
             if self.hovered {
                 // Pop up returns upgrade player clicks
-                if let Some(upgrade) = self.pop_up.update(self.hitbox, &Station{drone_base: 20.,drone_eff: 1.0,drone_speed: 2000.,}, &mut self.avail_upgrades, &PROBE_UPGRADES, &self.resources) {
-                    self.upgrade(&upgrade);
-                }
+                let order = self.pop_up.update(self.hitbox, &mut self.avail_upgrades, &self.resources);
+                input.push(StationId::Probe, order);
             }
 
             let dx = self.target_pos.0 - self.hitbox.xy().0 as f32;
@@ -98,30 +116,28 @@ impl Player {
             self.scans.retain_mut(|scan| {
                 scan.update((self.hitbox.x() as f32, self.hitbox.y() as f32))
             });
-            
-            if self.hovered {
-                // Pop up returns upgrade player clicks
-                if let Some(upgrade) = self.pop_up.update(self.hitbox, &Station{drone_base: 20.,drone_eff: 1.0,drone_speed: 2000.,}, &mut self.avail_upgrades, &GATE_UPGRADES, &self.resources) {
-                    self.upgrade(&upgrade);
-                }
-            }
 
         } else {
-            self.jump(event_manager);
+            self.jump(event_manager, sector);
         }
+    }
 
+    // Turn accumulated progress into PRESTIGE
+    pub fn simulate(&mut self) {
+        self.resources.tick();
         if self.prestige_prog >= self.prestige_limit {
-            self.prestige_earned += 1;
+            self.prestige_earned += BigNum::from(1u64);
             self.prestige_index += 1;
-            self.prestige_limit = CostFormula::Exponential.calculate_cost(vec![(Resources::Prestige, 200_000)], self.prestige_index)[0].1;
-            self.prestige_prog = 0;
+            self.prestige_limit = CostFormula::Exponential.calculate_cost(vec![(Resources::Prestige, BigNum::from(200_000u64))], self.prestige_index)[0].1;
+            self.prestige_prog = BigNum::ZERO;
         }
     }
 
-    pub fn jump(&mut self, event_manager: &mut EventManager) {
+    pub fn jump(&mut self, event_manager: &mut EventManager, sector: &Sector) {
         if !self.gate_aligned {
             //log!("aligning");
-            self.target_pos = ((GATE_BOX.0 + GATE_BOX.2/2) as f32, (GATE_BOX.1 - 16) as f32);
+            let gate = sector.place(StationId::Jumpgate, GATE_BOX);
+            self.target_pos = ((gate.0 + gate.2/2) as f32, (gate.1 - 16) as f32);
             self.hitbox = self.hitbox.position(
                 (self.hitbox.xy().0 as f32 + (self.target_pos.0 - self.hitbox.xy().0 as f32) * 0.1) as i32,
                 (self.hitbox.xy().1 as f32 + (self.target_pos.1 - self.hitbox.xy().1 as f32) * 0.1) as i32
@@ -147,7 +163,8 @@ impl Player {
                 self.hitbox = self.hitbox.translate_y((self.jump_timer - 50) as f32 * 0.5);
             }
 
-            if self.hitbox.xy().1 as f32 >= (GATE_BOX.1 + GATE_BOX.3/2 - 2) as f32 {
+            let gate = sector.place(StationId::Jumpgate, GATE_BOX);
+            if self.hitbox.xy().1 as f32 >= (gate.1 + gate.3/2 - 2) as f32 {
                 self.hitbox = self.hitbox.translate_y(400);
             }
 
@@ -159,57 +176,57 @@ impl Player {
         }
     }
 
-    pub fn collect(&mut self, resource: (Resources, u64)) {
+    // Into the wallet and toward the next PRESTIGE, boosts are applied by Simulation::collect_from
+    pub fn deposit(&mut self, resource: (Resources, BigNum), source: Source) {
         self.prestige_prog += resource.1;
-        // Append value to exisiting resource
-        let mut found = false;
-        for i in 0..self.resources.len() {
-            if self.resources[i].0 == resource.0 {
-                self.resources[i].1 += resource.1;
-                found = true;
-                break;
-            }
-        }
-        // Append resrouce and value
-        if !found {
-            self.resources.push(resource);
-        }   
-    }
-
-    pub fn remove(&mut self, resource: (Resources, u64)) {
-        for i in 0..self.resources.len() {
-            if self.resources[i].0 == resource.0 {
-                if self.resources[i].1 >= resource.1 {
-                    self.resources[i].1 -= resource.1;
-                } else {
-                    self.resources.remove(i);
-                }
-                break;
-            }
-        }
+        self.resources.deposit(resource.0, resource.1, source);
     }
 
-    pub fn upgrade(&mut self, upgrade: &Upgrade) {
-        // Determine if the player has sufficent resources
-        let mut found = false;
-        for cost in upgrade.cost.iter() {
-            for i in 0..self.resources.len() {
-                if self.resources[i].0 == cost.0 {
-                    // Subtract resources from player
-                    if self.resources[i].1 >= cost.1 {
-                        self.resources[i].1 -= cost.1;
-                        found = true;
-                    // Exit loops early when found
-                    } else { break; }
-                    break;
-                }
-            }
-        }
-            
-        if !found {
-            return;
-        }
+    pub fn amount(&self, resource: &Resources) -> BigNum {
+        self.resources.amount(resource)
+    }
+
+    // Pay every cost or none of them
+    pub fn spend(&mut self, cost: &[(Resources, BigNum)]) -> Result<(), Shortfall> {
+        self.resources.spend(cost)
+    }
+
+    pub fn refund(&mut self, cost: &[(Resources, BigNum)]) {
+        self.resources.refund(cost);
+    }
+
+    // Bought levels of each prestige tree effect
+    fn perks(&self) -> impl Iterator<Item = (&UpgradeEffect, u32)> {
+        self.avail_upgrades.iter().flat_map(|upgrade| upgrade.effects.iter().map(move |effect| (effect, upgrade.level)))
+            .filter(|(_, level)| *level > 0)
+    }
+
+    // Multiplier on automated production of a resource from the prestige tree, finished techs add to it
+    pub fn boost(&self, resource: &Resources) -> f64 {
+        1. + self.perks().map(|(effect, level)| match effect {
+            UpgradeEffect::Boost(boosted, amount) if boosted == resource => (*amount * level as f32) as f64,
+            _ => 0.,
+        }).sum::<f64>()
+    }
 
+    pub fn auto_scan(&self) -> bool {
+        self.perks().any(|(effect, _)| *effect == UpgradeEffect::AutoScan)
+    }
+
+    // Free levels of station upgrades at the start of a run, by station and upgrade id
+    pub fn head_starts(&self) -> Vec<(StationId, usize, u32)> {
+        self.perks().filter_map(|(effect, level)| match effect {
+            UpgradeEffect::HeadStart(station, id) => Some((*station, *id, level)),
+            _ => None,
+        }).collect()
+    }
+
+    // Station upgrades whose levels carry across a jump
+    pub fn kept(&self) -> Vec<(StationId, usize)> {
+        self.perks().filter_map(|(effect, _)| match effect {
+            UpgradeEffect::Keep(station, id) => Some((*station, *id)),
+            _ => None,
+        }).collect()
     }
 
     pub fn handle_event(&mut self, event: &Event) {
@@ -219,19 +236,23 @@ impl Player {
                 if !self.prestiged {
                     self.avail_upgrades.push(PROBE_UPGRADES[0].clone());
                     self.avail_upgrades.push(PROBE_UPGRADES[1].clone());
+                    self.avail_upgrades.push(PROBE_UPGRADES[2].clone());
                 }
             }
             _ => {}
         }
     }
 
-    pub fn scan(&mut self) {
+    pub fn scan(&mut self, rng: &mut Rng) {
         let pp = pointer().xy();
-        let pos = (pp.0 as f32 + 5., pp.1 as f32 - 5.);
-        self.scans.push(Scan::new((self.hitbox.x() as f32, self.hitbox.y() as f32), pos));
+        self.scan_at((pp.0 as f32 + 5., pp.1 as f32 - 5.), rng);
     }
 
-    pub fn draw(&self) {
+    pub fn scan_at(&mut self, pos: (f32, f32), rng: &mut Rng) {
+        self.scans.push(Scan::new((self.hitbox.x() as f32, self.hitbox.y() as f32), pos, rng));
+    }
+
+    pub fn draw(&self, sector: &Sector) {
         for scan in self.scans.iter() {
             scan.draw();
         }
@@ -261,7 +282,8 @@ impl Player {
             anim.set_fill_forwards(true);
 
             // Draw the scan effect
-            sprite!(animation_key = "jump", xy = (GATE_BOX.0, GATE_BOX.1 - 64));
+            let gate = sector.place(StationId::Jumpgate, GATE_BOX);
+            sprite!(animation_key = "jump", xy = (gate.0, gate.1 - 64));
         }
 
         PlayerDisplay::draw(&self.resources);
@@ -270,16 +292,14 @@ impl Player {
     pub fn draw_ui(&self) { 
         // pop up
         if self.hovered {
-            self.pop_up.draw(&Station{ drone_base: 20.,
-            drone_eff: 1.0,
-            drone_speed: 0.,}, &self.avail_upgrades);
+            self.pop_up.draw(&Station::new(20., 0.), &self.avail_upgrades);
         }
     }
 }
 
 impl Default for Player {
     fn default() -> Self {
-        Player::load(false, 0, 0, 0, vec![])
+        Player::load(false, BigNum::ZERO, BigNum::ZERO, 0, vec![])
     }
 }
 
@@ -297,33 +317,59 @@ impl POI for Player {
             drone_base: 20.,
             drone_eff: 1.0,
             drone_speed: 0.,
+            level: 0,
+            speed: 0,
+        }
+    }
+
+    // The probe's list is the prestige tree, bought from once the first jump is made
+    fn ids(&self) -> &'static [StationId] {
+        &[StationId::Probe]
+    }
+
+    fn offers(&self, _id: StationId) -> &[Upgrade] {
+        if self.prestiged { &self.avail_upgrades } else { &[] }
+    }
+
+    fn offers_mut(&mut self, _id: StationId) -> Option<&mut Vec<Upgrade>> {
+        Some(&mut self.avail_upgrades)
+    }
+
+    // Maxed upgrades stay listed since their level is what counts
+    fn advance(&mut self, _id: StationId, index: usize, event_manager: &mut EventManager, _rng: &mut Rng, _sector: &Sector) -> Option<Upgrade> {
+        let upgrade = Upgrade::advance(&mut self.avail_upgrades, index, &PROBE_UPGRADES, self.pop_up.panel, true);
+        for effect in upgrade.effects.iter() {
+            if let UpgradeEffect::TriggerEvent(event) = effect {
+                event_manager.trigger(event.clone());
+            }
         }
+        Some(upgrade)
     }
 
-    fn manual_produce(&mut self) -> u64 {
-        return 0;
+    fn manual_produce(&mut self) -> BigNum {
+        return BigNum::ZERO;
     }
 
-    fn produce(&mut self) -> u64 {
-        return 0;
+    fn produce(&mut self) -> BigNum {
+        return BigNum::ZERO;
     }
 
-    fn upgrade(&mut self, upgrade: &Upgrade, event_manager: &mut EventManager) {}
+    fn upgrade(&mut self, upgrade: &Upgrade, event_manager: &mut EventManager, _rng: &mut Rng, _sector: &Sector) {}
 }
 
 #[derive(Debug, Clone, PartialEq, BorshDeserialize, BorshSerialize)]
 pub struct PlayerDisplay {}
 impl PlayerDisplay {
-    pub fn draw(resources: &Vec<(Resources, u64)>) {
+    pub fn draw(resources: &Wallet) {
         let vp = Bounds::new(0, 0, 640, 400);
-        let wh = (64, resources.len() as i32 * 24 + 20);
+        let wh = (64, resources.iter().count() as i32 * 24 + 20);
         let xy = (0, vp.bottom() - wh.1);
 
         rect!(fixed = true, x = xy.0, y = xy.1, w = wh.0, h = wh.1, border_radius = 4, border_size = 1, color = 0x1f122bff, border_color = 0xffffffff);
         text!("RESOURCES", fixed = true, x = xy.0 + 4, y = xy.1 + 6, color = 0xffffffff);
         rect!(fixed = true, x = xy.0 + 4, y = xy.1 + 18, w = wh.0 - 8, h = 1, color = 0xffffffff);
 
-        for i in 0..resources.len() {
+        for (i, (resource, balance)) in resources.iter().enumerate() {
             let h = 24;
             let bb = Bounds::new(xy.0, 20 + xy.1 + i as i32 * h, wh.0, h);
             let mut button = Btn::new("".to_string(), bb.inset(2), true, 0);
@@ -331,16 +377,18 @@ impl PlayerDisplay {
             button.update();
             button.draw();
             if button.state == BtnState::Hovered {
-                let mut desc = WrapBox::new(resources[i].0.description(), 0);
+                let mut desc = WrapBox::new(resource.description(), 0);
                 desc.update(button.bounds, 6);
                 desc.draw();
             }
 
-            let t = format!("{}", resources[i].0);
+            let t = format!("{}", resource);
             sprite!(&t, fixed = true, x = bb.x() + 4, y = bb.center_y() - 8, wh = (16, 16), color = 0xffffffff);
             //text!(&t, fixed = true, x = bb.ctuenter_x() - t.len() as i32/2 * 5, y = bb.top() + 4, color = 0xffffffff);
-            let t = Numbers::format(resources[i].1);
-            text!(&t, fixed = true, x = bb.left() + 24, y = bb.center_y() - 4, color = 0xffffffff);
+            let t = Numbers::format(balance.amount);
+            text!(&t, fixed = true, x = bb.left() + 24, y = bb.center_y() - 7, color = 0xffffffff);
+            let t = balance.income.label();
+            text!(&t, fixed = true, x = bb.left() + 24, y = bb.center_y() + 2, font = "small", color = 0x847e87ff);
         }
     }
 }
@@ -355,13 +403,13 @@ pub struct Scan {
 }
 
 impl Scan {
-    pub fn new(origin: (f32, f32), pos: (f32, f32)) -> Self {
+    pub fn new(origin: (f32, f32), pos: (f32, f32), rng: &mut Rng) -> Self {
         Self {
             origin,
             pos,
             radius: 0.0,
             lifetime: 40.,
-            key: rand().to_string(),
+            key: rng.next_u32().to_string(),
         }
     }
 
@@ -1,14 +1,14 @@
 use super::*;
 
 pub const MINES_BOX: (i32, i32, i32, i32) = (128, 0, 64, 64);
+// Rough distance flown between the mines and the asteroid belt
+const ASTEROID_TRIP: f32 = 360.;
 
 #[derive(Debug, Clone, PartialEq, BorshDeserialize, BorshSerialize)]
 pub struct AsteroidMines {
     pub drones: Vec<Drone>,
 
     station: Station,
-    pub drone_level: u32,
-    pub drone_speed: u32,
 
     pub unlockable: bool,
     unlocked: bool,
@@ -21,23 +21,17 @@ pub struct AsteroidMines {
     collections: Vec<Collection>,
     collect_interval: usize,
 
-    avail_upgrades: Vec<Upgrade>,
+    pub avail_upgrades: Vec<Upgrade>,
 }
 
 impl AsteroidMines {
-    pub fn load() -> Self {
-        let hitbox = Bounds::new(128, 0, 64, 64);
+    pub fn load(sector: &Sector) -> Self {
+        let (x, y, w, h) = sector.place(StationId::AsteroidMines, MINES_BOX);
+        let hitbox = Bounds::new(x, y, w, h);
         let pop_up =  PopUp::new("ASTEROID MINES".to_string(), Resources::Metals);
         AsteroidMines {
             drones: vec![],
-            station: Station {
-                drone_base: 20.,
-                drone_eff: 1.0,
-                drone_speed: 600.,
-            },
-
-            drone_level: 0,
-            drone_speed: 0,
+            station: Station::new(15. * sector.yield_of(&Resources::Metals), 600.),
 
             unlockable: false,
             unlocked: false,
@@ -54,7 +48,133 @@ impl AsteroidMines {
         }
     }
 
-    pub fn update(&mut self, player: &mut Player, event_manager: &mut EventManager, field: &mut AsteroidField,) {
+    // METALS the drones produced, none until the mines are built
+    pub fn simulate(&mut self, stats: &mut Stats) -> Option<BigNum> {
+        let produced = self.produce();
+        if !self.unlocked {
+            return None;
+        }
+        // Each delivery is one asteroid mined out
+        stats.mined(self.drones.iter().filter(|drone| drone.work.produced.is_some()).count() as u64);
+        Some(produced)
+    }
+
+    // Ticks per round trip and METALS per trip for each mining drone
+    pub fn cycle(&self) -> (f32, BigNum) {
+        let speed = self.station.speed as f32;
+        let interval = DroneMode::Mining.interval();
+        // Unload at the mines, drill the asteroid, fly there and back
+        let ticks = interval / 4. / (1. + speed * 0.5)
+            + interval / (1. + speed * 0.15)
+            + 2. * ASTEROID_TRIP / (1. + 0.1 * speed * 0.15);
+        (ticks, BigNum::from_f64((self.station.drone_eff * self.station.drone_base).round() as f64))
+    }
+
+    fn spawn_drone(&mut self, rng: &mut Rng, sector: &Sector) {
+        let xy = self.hitbox.translate(self.hitbox.w()/2,self.hitbox.h()/2).xy();
+        self.drones.push(Drone::new(DroneMode::Mining, self.station.level, self.station.speed, xy, rng, sector));
+        self.pop_up.drones += 1;
+    }
+}
+
+impl POI for AsteroidMines {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+    
+    fn get_station(&self) -> &Station {
+        &self.station
+    }
+
+    fn ids(&self) -> &'static [StationId] {
+        &[StationId::AsteroidMines]
+    }
+
+    fn offers(&self, _id: StationId) -> &[Upgrade] {
+        &self.avail_upgrades
+    }
+
+    fn offers_mut(&mut self, _id: StationId) -> Option<&mut Vec<Upgrade>> {
+        Some(&mut self.avail_upgrades)
+    }
+
+    fn advance(&mut self, _id: StationId, index: usize, event_manager: &mut EventManager, rng: &mut Rng, sector: &Sector) -> Option<Upgrade> {
+        let upgrade = Upgrade::advance(&mut self.avail_upgrades, index, &MINES_UPGRADES, self.pop_up.panel, false);
+        self.upgrade(&upgrade, event_manager, rng, sector);
+        Some(upgrade)
+    }
+
+    fn save(&self) -> Option<StationSave> {
+        Some(StationSave {
+            station: self.station.clone(),
+            drones: self.drones.len() as u32,
+            unlockable: self.unlockable,
+            unlocked: self.unlocked,
+            upgrades: Upgrade::save(&self.avail_upgrades),
+            detail: StationDetail::None,
+        })
+    }
+
+    fn restore(&mut self, save: &StationSave, rng: &mut Rng, sector: &Sector) {
+        self.station = save.station.clone();
+        self.unlockable = save.unlockable;
+        self.unlocked = save.unlocked;
+        self.avail_upgrades = Upgrade::restore(&save.upgrades, &MINES_UPGRADES, self.pop_up.panel);
+        for _ in 0..save.drones {
+            self.spawn_drone(rng, sector);
+        }
+    }
+
+    fn unlockable(&self) -> bool {
+        self.unlockable
+    }
+
+    fn produce(&mut self) -> BigNum {
+        let (period, amount) = self.cycle();
+        let mut produced = BigNum::ZERO;
+        for drone in self.drones.iter_mut() {
+            drone.work.produced = None;
+            if drone.work.advance(1., period) {
+                drone.work.produced = Some(amount);
+                produced += amount;
+            }
+        }
+        produced
+    }
+
+    fn idle_rate(&self) -> f32 {
+        if !self.unlocked {
+            return 0.;
+        }
+        let (period, amount) = self.cycle();
+        self.drones.len() as f32 * amount.to_f64() as f32 / (period / 60.)
+    }
+
+    fn upgrade(&mut self, upgrade: &Upgrade, event_manager: &mut EventManager, rng: &mut Rng, sector: &Sector) {
+        for effect in upgrade.effects.iter() {
+            if self.station.apply(effect, &mut self.drones, event_manager) {
+                continue;
+            }
+            match effect {
+                UpgradeEffect::Construct => {
+                    self.unlocked = true;
+                }
+                UpgradeEffect::AddDrone => {
+                    self.spawn_drone(rng, sector);
+                    if self.drones.len() == 1 {
+                        event_manager.trigger(Event::PowerPlantUnlockable);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn update(&mut self, player: &mut Player, event_manager: &EventManager, research: &Research, _rng: &mut Rng, input: &mut SimInput) {
         let p = pointer();
         let rp = p.xy();
         
@@ -65,34 +185,20 @@ impl AsteroidMines {
             self.hovered = false;
         }
         
-        // Update pop up position and buttons, apply upgrades
+        // Update pop up position and buttons, queue upgrades
         if self.hovered {
             // Pop up returns upgrade player clicks
-            if let Some(upgrade) = self.pop_up.update(self.hitbox, &self.station, &mut self.avail_upgrades, &MINES_UPGRADES, &player.resources) {
-                self.upgrade(&upgrade, event_manager);
-                player.upgrade(&upgrade);
-            }
-        }
-        
-        // Produce Resources
-        let mut produced = (Resources::Metals, 0);
-        // Initial click
-        if self.hovered && p.just_pressed() && tick() - self.clicked_at >= self.collect_interval {
-            self.clicked_at = tick();
-        }
-        // Manually produce resources every 30 ticks
-        if tick() - self.clicked_at >= self.collect_interval {
-            if self.hitbox.intersects_xy(rp) && p.pressed() {
-                produced.1 += self.manual_produce();
-            }
-            self.clicked_at = tick();
+            let order = self.pop_up.update(self.hitbox, &mut self.avail_upgrades, &player.resources);
+            let order = research.gate(StationId::AsteroidMines, &mut self.avail_upgrades, order);
+            input.push(StationId::AsteroidMines, order);
         }
+    }
 
-        // Produce based on drone update
+    // Move drones and show collection numbers for what the simulation produced
+    fn animate(&mut self, scene: &mut Scene) {
         for drone in self.drones.iter_mut() {
-            if drone.update_mining(field) {
-                let amount =  ((1. + self.drone_level as f32 * 1.2) * 15.).round() as u64;
-                produced.1 += amount;
+            drone.update_mining(scene.asteroid_field, scene.rng, scene.sector);
+            if let Some(amount) = drone.work.produced {
                 self.collections.push(Collection::new(drone.pos, (Resources::Metals, amount)));
             }
         }
@@ -102,13 +208,9 @@ impl AsteroidMines {
             collection.update();
             collection.is_active // Keep only active collections
         }); 
-
-        if self.unlocked {
-            player.collect(produced);
-        }
     }
 
-    pub fn handle_event(&mut self, event: &Event) {
+    fn handle_event(&mut self, event: &Event) {
         match event {
             Event::MinesUnlockable => {
                 self.unlockable = true;
@@ -120,8 +222,8 @@ impl AsteroidMines {
             _ => {}
         }
     }
-    
-    pub fn draw(&self) {
+
+    fn draw(&self, _research: &Research) {
         let mut bob_box = self.hitbox;
         if self.unlocked {
             let bob =  f32::sin(tick() as f32 / 35.0 + 20.0) * 1.5;
@@ -172,52 +274,11 @@ impl AsteroidMines {
         
     }
 
-    pub fn draw_ui(&self) {
+    fn draw_ui(&self, research: &Research) {
         // pop up
         if self.hovered {
             self.pop_up.draw(&self.station, &self.avail_upgrades);
+            self.pop_up.draw_projects(research, &[StationId::AsteroidMines]);
         }
     }
-
 }
-
-impl POI for AsteroidMines {
-    fn as_any(&self) -> &dyn Any {
-        self
-    }
-
-    fn as_any_mut(&mut self) -> &mut dyn Any {
-        self
-    }
-    
-    fn get_station(&self) -> &Station {
-        &self.station
-    }
-
-    fn produce(&mut self) -> u64 {
-        0
-    }
-
-    fn upgrade(&mut self, upgrade: &Upgrade, event_manager: &mut EventManager) {
-        if upgrade.name == "CONSTRUCT" {
-            self.unlocked = true;
-        } else if upgrade.name.starts_with("DEPLOY") {
-            let xy = self.hitbox.translate(self.hitbox.w()/2,self.hitbox.h()/2).xy();
-            self.drones.push(Drone::new(DroneMode::Mining, self.drone_level, self.drone_speed, xy));
-            self.pop_up.drones += 1;
-            if self.drones.len() == 1 {
-                event_manager.trigger(Event::PowerPlantUnlockable);
-            }
-        } else if upgrade.name.starts_with("DRILL") {
-            self.drone_level += 1;
-            for drone in self.drones.iter_mut() {
-                drone.level += 1;
-            }   
-        } else if upgrade.name.starts_with("ADV.") {
-            self.drone_speed += 1;
-            for drone in self.drones.iter_mut() {
-                drone.speed += 1;
-            }   
-        }
-    }
-}
\ No newline at end of file
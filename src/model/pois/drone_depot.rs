@@ -7,34 +7,39 @@ pub struct DroneDepot {
     pub drones: Vec<Drone>,
 
     station: Station,
-    pub drone_level: u32,
-    drone_speed: u32,
 
     pub unlockable: bool,
     pub unlocked: bool,
 
     hitbox: Bounds,
+    // From the depot to the mines in this sector
+    distance: f32,
     pop_up: PopUp,
     hovered: bool,
 
-    fabricator_unlocked: bool,
+    pub fabricator_unlocked: bool,
     power_plant_unlocked: bool,
     fabricator: PopUp,
-    fab_prog: u64,
+    fab_prog: BigNum,
     fab_level: u32,
-    fab_limit: u64,
+    fab_limit: BigNum,
+    // DRONES completed on the current tick
+    fabricated: Option<BigNum>,
 
     clicked_at: usize,
     collections: Vec<Collection>,
     collect_interval: usize,
     
 
-    avail_upgrades: Vec<Upgrade>,
-    fab_upgrades: Vec<Upgrade>,
+    pub avail_upgrades: Vec<Upgrade>,
+    pub fab_upgrades: Vec<Upgrade>,
 }
 impl DroneDepot {
-    pub fn load() -> Self {
-        let hitbox = Bounds::new(DEPOT_BOX.0, DEPOT_BOX.1, DEPOT_BOX.2, DEPOT_BOX.3);
+    pub fn load(sector: &Sector) -> Self {
+        let (x, y, w, h) = sector.place(StationId::DroneDepot, DEPOT_BOX);
+        let hitbox = Bounds::new(x, y, w, h);
+        let mines = sector.place(StationId::AsteroidMines, MINES_BOX);
+        let distance = ((x - mines.0) as f32).hypot((y - mines.1) as f32);
         let pop_up =  PopUp::new("DRONE DEPOT".to_string(), Resources::Drones);
         let fabricator =  PopUp::new_fab("FABRICATOR".to_string(), Resources::Drones);
         let anim = animation::get("drone_locked");
@@ -42,13 +47,7 @@ impl DroneDepot {
         DroneDepot {
             drones: vec![],
 
-            station: Station {
-                drone_base: 20.,
-                drone_eff: 1.0,
-                drone_speed: 600.,
-            },
-            drone_level: 0,
-            drone_speed: 0,
+            station: Station::new(32., 600.),
 
             unlockable: false,
             unlocked: false,
@@ -56,11 +55,13 @@ impl DroneDepot {
             fabricator_unlocked: false,
             power_plant_unlocked: false,
             fabricator,
-            fab_prog: 0,
+            fab_prog: BigNum::ZERO,
             fab_level: 0,
-            fab_limit: 320,
+            fab_limit: BigNum::from(320u64),
+            fabricated: None,
 
             hitbox,
+            distance,
             pop_up,
             hovered: false,
 
@@ -74,7 +75,211 @@ impl DroneDepot {
         
     }
 
-    pub fn handle_event(&mut self, event: &Event) {
+    fn spawn_drone(&mut self, rng: &mut Rng, sector: &Sector) {
+        let xy = self.hitbox.translate(self.hitbox.w()/2,self.hitbox.h()/2).xy();
+        self.drones.push(Drone::new(DroneMode::Shipping, self.station.level, self.station.speed, xy, rng, sector));
+        self.fabricator.drones += 1;
+    }
+
+    // DRONES fabricated this tick, the METALS are spent from the player
+    pub fn simulate(&mut self, player: &mut Player) -> Option<BigNum> {
+        let (load_period, cargo) = self.cycle();
+        let (unload_period, batch) = self.unload_cycle();
+        let trip = self.trip();
+
+        let mut delivered = BigNum::ZERO;
+        for drone in self.drones.iter_mut() {
+            let work = &mut drone.work;
+            work.produced = None;
+            work.consumed = None;
+            if work.cargo.is_zero() {
+                // Wait at the mines until there are enough METALS to fill the hold
+                work.timer = (work.timer + 1.).min(load_period);
+                if work.timer >= load_period && player.spend(&[(Resources::Metals, cargo)]).is_ok() {
+                    work.cargo = cargo;
+                    work.consumed = Some(cargo);
+                    // Fly back to the depot before the first batch
+                    work.timer = -trip;
+                }
+            } else if work.advance(1., unload_period) {
+                let amount = batch.min(work.cargo);
+                work.cargo -= amount;
+                work.produced = Some(amount);
+                delivered += amount;
+                if work.cargo.is_zero() {
+                    work.timer = 0.;
+                }
+            }
+        }
+
+        let fabricated = self.fabricate(delivered);
+        self.fabricated = None;
+        if !fabricated.is_zero() {
+            self.fabricated = Some(fabricated);
+        }
+        self.fabricated
+    }
+
+    // Ticks for a shipping drone to reach the mines and load up, and the METALS it takes
+    pub fn cycle(&self) -> (f32, BigNum) {
+        let cargo = BigNum::from_f64((self.station.drone_eff * self.station.drone_base) as f64).floor();
+        (DroneMode::Shipping.interval() + self.trip(), cargo)
+    }
+
+    // Ticks between unload batches at the depot and the METALS in each batch
+    pub fn unload_cycle(&self) -> (f32, BigNum) {
+        let speed = self.station.speed as f32;
+        let batch = BigNum::from_f64(((1.0 + speed * 0.2) * 10. + (self.station.drone_eff - 1.) * 5.).round() as f64);
+        (DroneMode::Shipping.interval() / (1. + speed * 0.2), batch)
+    }
+
+    // Ticks to fly between the depot and the mines
+    fn trip(&self) -> f32 {
+        self.distance / (1. + 0.1 * self.station.speed as f32 * 0.2)
+    }
+
+    // Feed METALS into the fabricator, returns the number of DRONES completed
+    pub fn fabricate(&mut self, metals: BigNum) -> BigNum {
+        let mut metals = metals;
+        let mut fabricated = BigNum::ZERO;
+        while !metals.is_zero() {
+            let needed = self.fab_limit - self.fab_prog;
+            if metals >= needed {
+                metals -= needed;
+                self.fab_level += 1;
+                self.fab_limit = CostFormula::Exponential.calculate_cost(vec![(Resources::Metals, BigNum::from(320u64))], self.fab_level)[0].1;
+                self.fab_prog = BigNum::ZERO;
+                fabricated += BigNum::from(1u64);
+            } else {
+                self.fab_prog += metals;
+                metals = BigNum::ZERO;
+            }
+        }
+        fabricated
+    }
+
+    
+}
+
+impl POI for DroneDepot {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn get_station(&self) -> &Station {
+        &self.station
+    }
+
+    fn ids(&self) -> &'static [StationId] {
+        &[StationId::DroneDepot, StationId::Fabricator]
+    }
+
+    fn offers(&self, id: StationId) -> &[Upgrade] {
+        match id {
+            StationId::Fabricator if self.fabricator_unlocked => &self.fab_upgrades,
+            StationId::Fabricator => &[],
+            _ => &self.avail_upgrades,
+        }
+    }
+
+    fn offers_mut(&mut self, id: StationId) -> Option<&mut Vec<Upgrade>> {
+        match id {
+            StationId::Fabricator => Some(&mut self.fab_upgrades),
+            _ => Some(&mut self.avail_upgrades),
+        }
+    }
+
+    fn advance(&mut self, id: StationId, index: usize, event_manager: &mut EventManager, rng: &mut Rng, sector: &Sector) -> Option<Upgrade> {
+        let upgrade = match id {
+            StationId::Fabricator => Upgrade::advance(&mut self.fab_upgrades, index, &DEPOT_UPGRADES, self.fabricator.panel, false),
+            _ => Upgrade::advance(&mut self.avail_upgrades, index, &DEPOT_UPGRADES, self.pop_up.panel, false),
+        };
+        self.upgrade(&upgrade, event_manager, rng, sector);
+        Some(upgrade)
+    }
+
+    fn save(&self) -> Option<StationSave> {
+        Some(StationSave {
+            station: self.station.clone(),
+            drones: self.drones.len() as u32,
+            unlockable: self.unlockable,
+            unlocked: self.unlocked,
+            upgrades: Upgrade::save(&self.avail_upgrades),
+            detail: StationDetail::Depot {
+                fabricator_unlocked: self.fabricator_unlocked,
+                power_plant_unlocked: self.power_plant_unlocked,
+                fab_level: self.fab_level,
+                fab_prog: self.fab_prog,
+                fab_upgrades: Upgrade::save(&self.fab_upgrades),
+            },
+        })
+    }
+
+    fn restore(&mut self, save: &StationSave, rng: &mut Rng, sector: &Sector) {
+        self.station = save.station.clone();
+        self.unlockable = save.unlockable;
+        self.unlocked = save.unlocked;
+        self.avail_upgrades = Upgrade::restore(&save.upgrades, &DEPOT_UPGRADES, self.pop_up.panel);
+        if let StationDetail::Depot { fabricator_unlocked, power_plant_unlocked, fab_level, fab_prog, fab_upgrades } = &save.detail {
+            self.fabricator_unlocked = *fabricator_unlocked;
+            self.power_plant_unlocked = *power_plant_unlocked;
+            self.fab_level = *fab_level;
+            self.fab_prog = *fab_prog;
+            self.fab_limit = CostFormula::Exponential.calculate_cost(vec![(Resources::Metals, BigNum::from(320u64))], *fab_level)[0].1;
+            self.fab_upgrades = Upgrade::restore(fab_upgrades, &DEPOT_UPGRADES, self.fabricator.panel);
+        }
+        for _ in 0..save.drones {
+            self.spawn_drone(rng, sector);
+        }
+    }
+
+    fn unlockable(&self) -> bool {
+        self.unlockable
+    }
+
+    fn produce(&mut self) -> BigNum {
+        return BigNum::ZERO;
+    }
+
+    // METALS shipped to the fabricator per second
+    fn idle_rate(&self) -> f32 {
+        let (load, cargo) = self.cycle();
+        let (unload, batch) = self.unload_cycle();
+        // Load at the mines, fly back, unload in batches at the depot
+        let ticks = load + self.trip() + (cargo / batch).to_f64().ceil() as f32 * unload;
+        self.drones.len() as f32 * cargo.to_f64() as f32 / (ticks / 60.)
+    }
+
+    fn upgrade(&mut self, upgrade: &Upgrade, event_manager: &mut EventManager, rng: &mut Rng, sector: &Sector) {
+        for effect in upgrade.effects.iter() {
+            if self.station.apply(effect, &mut self.drones, event_manager) {
+                continue;
+            }
+            match effect {
+                UpgradeEffect::Construct => {
+                    self.unlocked = true;
+                }
+                UpgradeEffect::UnlockBuilding(Building::Fabricator) => {
+                    self.fabricator_unlocked = true;
+                    Upgrade::add_upgrade(&mut self.fab_upgrades, &DEPOT_UPGRADES, 3, self.fabricator.panel);
+                    Upgrade::add_upgrade(&mut self.fab_upgrades, &DEPOT_UPGRADES, 4, self.fabricator.panel);
+                    if self.power_plant_unlocked {
+                        Upgrade::add_upgrade(&mut self.fab_upgrades, &DEPOT_UPGRADES, 5, self.fabricator.panel);
+                    }
+                }
+                UpgradeEffect::AddDrone => {
+                    self.spawn_drone(rng, sector);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn handle_event(&mut self, event: &Event) {
         match event {
             Event::DroneDepotUnlockable => {
                 self.unlockable = true;
@@ -93,14 +298,10 @@ impl DroneDepot {
         }
     }
 
-    pub fn update(&mut self, player: &mut Player, event_manager: &mut EventManager) {
+    fn update(&mut self, player: &mut Player, event_manager: &EventManager, research: &Research, _rng: &mut Rng, input: &mut SimInput) {
         let p = pointer();
         let rp = p.xy();
 
-        if self.unlocked {
-            
-        }
-
         if event_manager.dialogue.is_none() {
             self.hovered = 
                 self.hitbox.intersects_xy(rp) 
@@ -110,57 +311,34 @@ impl DroneDepot {
             self.hovered = false;
         }
 
-        // Update pop up position and buttons, apply upgrades
+        // Update pop up position and buttons, queue upgrades
         if self.hovered {
             let z = camera::z() as i32;
             let mut offset = if self.fabricator_unlocked { self.hitbox.translate_y(-(self.pop_up.panel.h() as i32/2 + 1) * 1/z) } else { self.hitbox };
-            if let Some(upgrade) = self.pop_up.update(offset, &self.station, &mut self.avail_upgrades, &DEPOT_UPGRADES, &player.resources) {
-                self.upgrade(&upgrade, event_manager);
-                player.upgrade(&upgrade);
-            }
+            let order = self.pop_up.update(offset, &mut self.avail_upgrades, &player.resources);
+            let order = research.gate(StationId::DroneDepot, &mut self.avail_upgrades, order);
+            input.push(StationId::DroneDepot, order);
             
             if self.fabricator_unlocked {
                 offset = self.hitbox.translate_y((self.fabricator.panel.h() as i32/2 + 1) * 1/z);
-                if let Some(upgrade) = self.fabricator.update_fabricator(offset, &self.station, &mut self.fab_upgrades, &DEPOT_UPGRADES, &player.resources) {
-                    self.upgrade(&upgrade, event_manager);
-                    player.upgrade(&upgrade);
-                }
+                let order = self.fabricator.update_fabricator(offset, &mut self.fab_upgrades, &player.resources);
+                let order = research.gate(StationId::Fabricator, &mut self.fab_upgrades, order);
+                input.push(StationId::Fabricator, order);
             }
         }
-        // Produce Resources
-        
-        // Initial click
-        // if self.hovered && p.just_pressed() && tick() - self.clicked_at >= self.collect_interval {
-        //     self.clicked_at = tick();
-        // }
-        // // Manually produce resources every 30 ticks
-        // if tick() - self.clicked_at >= self.collect_interval {
-        //     if self.hitbox.intersects_xy(rp) && p.pressed() {
-        //         produced.1 += self.manual_produce();
-        //     }
-        //     self.clicked_at = tick();
-        // }
-
-        // Produce based on drone update
-        for drone in self.drones.iter_mut() {
-            if let Some(prod) = drone.shipping() {
-                if !drone.on_site && !drone.cargo.is_empty() {
-                    player.remove((Resources::Metals, prod.1));
-                    self.collections.push(Collection::new_detail(drone.pos, (Resources::Metals, prod.1), false));
-                } else {
-                    self.fab_prog += prod.1;
-                    if self.fab_prog >= self.fab_limit {
-                        self.fab_level += 1;
-                        self.fab_limit = CostFormula::Exponential.calculate_cost(vec![(Resources::Metals, 320)], self.fab_level)[0].1;
-                        self.fab_prog = 0;
-                        player.collect((Resources::Drones, 1));
-                        self.collections.push(Collection::new((self.hitbox.center_x() as f32, self.hitbox.center_y() as f32), (Resources::Drones, 1),));
-                    } 
-                }
-            } else if !drone.cargo.is_empty() {
+    }
 
+    // Move drones and show collection numbers for what the simulation produced
+    fn animate(&mut self, scene: &mut Scene) {
+        for drone in self.drones.iter_mut() {
+            drone.shipping(scene.sector);
+            if let Some(amount) = drone.work.consumed {
+                self.collections.push(Collection::new_detail(drone.pos, (Resources::Metals, amount), false));
             }
         }
+        if let Some(fabricated) = self.fabricated {
+            self.collections.push(Collection::new((self.hitbox.center_x() as f32, self.hitbox.center_y() as f32), (Resources::Drones, fabricated),));
+        }
 
         // Update collection numbers
         self.collections.retain_mut(|collection| {
@@ -169,7 +347,7 @@ impl DroneDepot {
         }); 
     }
 
-    pub fn draw(&self) {
+    fn draw(&self, _research: &Research) {
         let mut bob_box = self.hitbox;
         if self.unlocked {
             let bob =  f32::sin(tick() as f32 / 20.0) * 1.5;
@@ -210,13 +388,16 @@ impl DroneDepot {
         }
     }
 
-    
-    pub fn draw_ui(&self) {
+    fn draw_ui(&self, research: &Research) {
         // pop up
         if self.hovered {
             self.pop_up.draw(&self.station, &self.avail_upgrades);
+            // The fabricator sits below the depot, both queues go under it
             if self.fabricator_unlocked {
                 self.fabricator.draw_fabricator(&self.station, &self.fab_upgrades, self.fab_prog, self.fab_limit);
+                self.fabricator.draw_projects(research, &[StationId::DroneDepot, StationId::Fabricator]);
+            } else {
+                self.pop_up.draw_projects(research, &[StationId::DroneDepot]);
             }
         }
         // Draw collection numbers
@@ -225,49 +406,3 @@ impl DroneDepot {
         }
     }
 }
-
-impl POI for DroneDepot {
-    fn as_any(&self) -> &dyn Any {
-        self
-    }
-
-    fn as_any_mut(&mut self) -> &mut dyn Any {
-        self
-    }
-
-    fn get_station(&self) -> &Station {
-        &self.station
-    }
-
-    fn produce(&mut self) -> u64 {
-        return 0;
-    }
-
-    fn upgrade(&mut self, upgrade: &Upgrade, event_manager: &mut EventManager) {
-        if upgrade.name == "CONSTRUCT" {
-            self.unlocked = true;
-            event_manager.trigger(Event::UnlockDroneDepot);
-        } else if upgrade.name == "CONSTRUCT FABRICATOR" {
-            self.fabricator_unlocked = true;
-            Upgrade::add_upgrade(&mut self.fab_upgrades, &DEPOT_UPGRADES, 3, self.fabricator.panel);
-            Upgrade::add_upgrade(&mut self.fab_upgrades, &DEPOT_UPGRADES, 4, self.fabricator.panel);
-            if self.power_plant_unlocked {
-                Upgrade::add_upgrade(&mut self.fab_upgrades, &DEPOT_UPGRADES, 5, self.fabricator.panel);
-            }
-        } else if upgrade.name.starts_with("DEPLOY") {
-            let xy = self.hitbox.translate(self.hitbox.w()/2,self.hitbox.h()/2).xy();
-            self.drones.push(Drone::new(DroneMode::Shipping, self.drone_level, self.drone_speed, xy));
-            self.fabricator.drones += 1;
-        } else if upgrade.name.starts_with("CARGO") {
-            self.drone_level += 1;
-            for drone in self.drones.iter_mut() {
-                drone.level += 1;
-            }
-        } else if upgrade.name.starts_with("PLASMA") {
-            self.drone_speed += 1;
-            for drone in self.drones.iter_mut() {
-                drone.speed += 1;
-            }
-        }
-    }
-}
\ No newline at end of file
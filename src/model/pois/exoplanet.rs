@@ -9,6 +9,8 @@ pub struct Exoplanet {
     pub drones: Vec<Drone>,
     pub scanner_level: u32,
     collecting: bool,
+    // RESEARCH from a manual scan on the current tick
+    pub scanned: Option<BigNum>,
 
     pub station: Station,
 
@@ -23,22 +25,20 @@ pub struct Exoplanet {
 
     assigned: bool,
 
-    avail_upgrades: Vec<Upgrade>,
+    pub avail_upgrades: Vec<Upgrade>,
 }
 impl Exoplanet {
-    pub fn load() -> Self {
-        let hitbox = Bounds::new(PLANET_BOX.0, PLANET_BOX.1, PLANET_BOX.2, PLANET_BOX.3);
+    pub fn load(sector: &Sector) -> Self {
+        let (x, y, w, h) = sector.place(StationId::Exoplanet, PLANET_BOX);
+        let hitbox = Bounds::new(x, y, w, h);
         let pop_up =  PopUp::new("EXOPLANET".to_string(), Resources::Research);
         Exoplanet {
             drones: vec![],
             scanner_level: 1,
             collecting: false,
+            scanned: None,
 
-            station: Station {
-                drone_base: 20.,
-                drone_eff: 1.0,
-                drone_speed: 800.,
-            },
+            station: Station::new(20. * sector.yield_of(&Resources::Research), 800.),
 
             hitbox,
             pop_up: pop_up.clone(),
@@ -55,7 +55,140 @@ impl Exoplanet {
         }
     }
 
-    pub fn update(&mut self, player: &mut Player, event_manager: &mut EventManager) {
+    // RESEARCH the drones produced, a manual scan is left in scanned
+    pub fn simulate(&mut self, tick: usize, scanning: bool, stats: &mut Stats) -> BigNum {
+        // Manually produce resources every collect_interval ticks
+        self.scanned = None;
+        if scanning && tick - self.clicked_at >= self.collect_interval {
+            self.clicked_at = tick;
+            self.scanned = Some(self.manual_produce());
+            stats.scanned();
+        }
+
+        self.produce()
+    }
+
+    // Ticks between scans and RESEARCH per scan, survey drones scan twice per orbit
+    pub fn cycle(&self) -> (f32, BigNum) {
+        (self.station.drone_speed / 2., BigNum::from_f64((self.station.drone_eff * self.station.drone_base) as f64).floor())
+    }
+
+    fn spawn_drone(&mut self, rng: &mut Rng, sector: &Sector) {
+        let xy = self.hitbox.translate(self.hitbox.w()/2,self.hitbox.h()/2).xy();
+        self.drones.push(Drone::new(DroneMode::Survey, self.station.drone_eff as u32, self.station.drone_speed as u32, xy, rng, sector));
+        self.pop_up.drones += 1;
+    }
+
+
+}
+
+impl POI for Exoplanet {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+    
+    fn get_station(&self) -> &Station {
+        &self.station
+    }
+
+    fn ids(&self) -> &'static [StationId] {
+        &[StationId::Exoplanet]
+    }
+
+    fn offers(&self, _id: StationId) -> &[Upgrade] {
+        &self.avail_upgrades
+    }
+
+    fn offers_mut(&mut self, _id: StationId) -> Option<&mut Vec<Upgrade>> {
+        Some(&mut self.avail_upgrades)
+    }
+
+    fn advance(&mut self, _id: StationId, index: usize, event_manager: &mut EventManager, rng: &mut Rng, sector: &Sector) -> Option<Upgrade> {
+        let upgrade = Upgrade::advance(&mut self.avail_upgrades, index, &EXOPLANET_UPGRADES, self.pop_up.panel, false);
+        self.upgrade(&upgrade, event_manager, rng, sector);
+        Some(upgrade)
+    }
+
+    fn save(&self) -> Option<StationSave> {
+        Some(StationSave {
+            station: self.station.clone(),
+            drones: self.drones.len() as u32,
+            unlockable: true,
+            unlocked: true,
+            upgrades: Upgrade::save(&self.avail_upgrades),
+            detail: StationDetail::Exoplanet { scanner_level: self.scanner_level, assigned: self.assigned },
+        })
+    }
+
+    fn restore(&mut self, save: &StationSave, rng: &mut Rng, sector: &Sector) {
+        self.station = save.station.clone();
+        if let StationDetail::Exoplanet { scanner_level, assigned } = save.detail {
+            self.scanner_level = scanner_level;
+            self.assigned = assigned;
+        }
+        self.avail_upgrades = Upgrade::restore(&save.upgrades, &EXOPLANET_UPGRADES, self.pop_up.panel);
+        for _ in 0..save.drones {
+            self.spawn_drone(rng, sector);
+        }
+    }
+
+    fn manual_produce(&mut self) -> BigNum {
+        return BigNum::from(self.scanner_level);
+    } 
+
+    fn produce(&mut self) -> BigNum {
+        let (period, amount) = self.cycle();
+        let mut produced = BigNum::ZERO;
+        for drone in self.drones.iter_mut() {
+            drone.work.produced = None;
+            if drone.work.advance(1., period) {
+                drone.work.produced = Some(amount);
+                produced += amount;
+            }
+        }
+        produced
+    }
+
+    fn idle_rate(&self) -> f32 {
+        let (period, amount) = self.cycle();
+        self.drones.len() as f32 * amount.to_f64() as f32 / (period / 60.)
+    }
+
+    fn upgrade(&mut self, upgrade: &Upgrade, event_manager: &mut EventManager, rng: &mut Rng, sector: &Sector) {
+        for effect in upgrade.effects.iter() {
+            if self.station.apply(effect, &mut self.drones, event_manager) {
+                continue;
+            }
+            match effect {
+                UpgradeEffect::AddScanner(levels) => {
+                    let before = self.scanner_level;
+                    self.scanner_level += levels;
+                    if before < 3 && self.scanner_level >= 3 {
+                        event_manager.trigger(Event::DroneDepotUnlockable);
+                    }
+                }
+                UpgradeEffect::AddDrone => {
+                    self.spawn_drone(rng, sector);
+                    if !self.assigned {
+                        event_manager.trigger(Event::MinesUnlockable);
+                        self.assigned = true;
+                    }
+                }
+                UpgradeEffect::RemoveDrone => {
+                    if self.drones.len() == 0 { return; }
+                    self.drones.remove(0);
+                    self.pop_up.drones -= 1;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn update(&mut self, player: &mut Player, event_manager: &EventManager, research: &Research, _rng: &mut Rng, input: &mut SimInput) {
         let p = pointer();
         let rp = p.xy();
         
@@ -67,47 +200,56 @@ impl Exoplanet {
             self.hovered = false;
         }
 
-        // Update pop up position and buttons, apply upgrades
+        // Update pop up position and buttons, queue upgrades
         if self.hovered {
             // Pop up returns upgrade player clicks
-            if let Some(upgrade) = self.pop_up.update(self.hitbox, &self.station, &mut self.avail_upgrades, &EXOPLANET_UPGRADES, &player.resources) {
-                self.upgrade(&upgrade, event_manager);
-                player.upgrade(&upgrade);
-            }
+            let order = self.pop_up.update(self.hitbox, &mut self.avail_upgrades, &player.resources);
+            let order = research.gate(StationId::Exoplanet, &mut self.avail_upgrades, order);
+            input.push(StationId::Exoplanet, order);
         }
 
-        // Produce Resources
-        let mut produced = (Resources::Research, 0);
-        
+        // Hold to scan
         if event_manager.dialogue.is_none() {
             // Initial click
-            if self.hovered && self.hitbox.intersects_xy(rp) 
-                && p.just_pressed() && !self.collecting {
+            if self.hovered && self.hitbox.intersects_xy(rp) && p.just_pressed() {
                 self.collecting = true;
             }
-            // Manually produce resources every 30 ticks
-            if self.collecting && tick() - self.clicked_at >= self.collect_interval {
-                if !self.hitbox.intersects_xy(rp) || p.released() { self.collecting = false; }
-                else {
-                    self.clicked_at = tick();
-                    produced.1 += self.manual_produce();
-                    player.scan();
-                }
+            if !self.hitbox.intersects_xy(rp) || p.released() {
+                self.collecting = false;
             }
+            input.scanning = self.collecting;
+        }
+    }
+
+    // Move drones and show collection numbers for what the simulation produced
+    fn animate(&mut self, scene: &mut Scene) {
+        if let Some(amount) = self.scanned {
+            // Auto scans aim at the planet rather than wherever the pointer is
+            let pos = if self.collecting {
+                let pp = pointer().xy();
+                (pp.0 as f32 + 5., pp.1 as f32 - 5.)
+            } else {
+                (self.hitbox.center_x() as f32, self.hitbox.center_y() as f32)
+            };
+            self.collections.push(Collection::new(pos, (Resources::Research, amount)));
+            scene.player.scan_at(pos, scene.rng);
         }
 
-        produced.1 += self.produce();
+        for drone in self.drones.iter_mut() {
+            drone.survey(&self.station, scene.rng, scene.sector);
+            if let Some(amount) = drone.work.produced {
+                self.collections.push(Collection::new(drone.pos, (Resources::Research, amount)));
+            }
+        }
         
         // Update collection numbers
         self.collections.retain_mut(|collection| {
             collection.update();
             collection.is_active // Keep only active collections
         }); 
-        
-        player.collect(produced);
     }
 
-    pub fn handle_event(&mut self, event: &Event) {
+    fn handle_event(&mut self, event: &Event) {
         match event {
             Event::UnlockDroneDepot => {
                 Upgrade::add_upgrade(&mut self.avail_upgrades, &EXOPLANET_UPGRADES, 1, self.pop_up.panel);
@@ -118,8 +260,8 @@ impl Exoplanet {
             _ => {}
         }
     }
-    
-    pub fn draw(&self) {
+
+    fn draw(&self, _research: &Research) {
         let bob =  f32::sin((tick() as f32 + 20.0) / 40.0) * 1.5;
         let mut bob_box = self.hitbox.translate_y(bob);
         // Draw backside drones
@@ -155,76 +297,11 @@ impl Exoplanet {
         
     }
 
-
-    pub fn draw_ui(&self) {
+    fn draw_ui(&self, research: &Research) {
         // pop up
         if self.hovered {
             self.pop_up.draw(&self.station, &self.avail_upgrades);
+            self.pop_up.draw_projects(research, &[StationId::Exoplanet]);
         }
     }
-
 }
-
-impl POI for Exoplanet {
-    fn as_any(&self) -> &dyn Any {
-        self
-    }
-
-    fn as_any_mut(&mut self) -> &mut dyn Any {
-        self
-    }
-    
-    fn get_station(&self) -> &Station {
-        &self.station
-    }
-
-    fn manual_produce(&mut self) -> u64 {
-        let pp = pointer().xy();
-        let pos = (pp.0 as f32 + 5., pp.1 as f32 - 5.);
-        self.collections.push(Collection::new(pos, (Resources::Research, self.scanner_level as u64)));
-        return self.scanner_level as u64;
-    } 
-
-    fn produce(&mut self) -> u64 {
-        let mut produced = 0;
-        for drone in self.drones.iter_mut() {
-            if drone.survey(&self.station) {
-                let amount =  (self.station.drone_eff * self.station.drone_base) as u64;
-                produced += amount;
-                self.collections.push(Collection::new(drone.pos, (Resources::Research, amount)));
-            }
-        }
-        produced
-    }
-
-    fn upgrade(&mut self, upgrade: &Upgrade, event_manager: &mut EventManager) {
-        if upgrade.name.starts_with("FIELD") {
-            self.scanner_level += 1;
-            if self.scanner_level == 3 {
-                event_manager.trigger(Event::DroneDepotUnlockable);
-            }
-        } else if upgrade.name.starts_with("DEPLOY") {
-            let xy = self.hitbox.translate(self.hitbox.w()/2,self.hitbox.h()/2).xy();
-            self.drones.push(Drone::new(DroneMode::Survey, self.station.drone_eff as u32, self.station.drone_speed as u32, xy));
-            self.pop_up.drones += 1;
-            if !self.assigned {
-                event_manager.trigger(Event::MinesUnlockable);
-                self.assigned = true;
-            }
-        } else if upgrade.name.starts_with("UNASSIGN") {
-            if self.drones.len() == 0 { return; }
-            self.drones.remove(0);
-            self.pop_up.drones -= 1;
-        } else if upgrade.name.starts_with("ADV.") {
-            self.station.drone_eff += 0.8;
-            for drone in self.drones.iter_mut() {
-                drone.level += 1;
-            }
-        } else if upgrade.name.starts_with("BIO") {
-            self.station.drone_speed *= 0.95;
-            for drone in self.drones.iter_mut() {
-                drone.speed += 1;
-            }
-        }
-    }
-}
\ No newline at end of file
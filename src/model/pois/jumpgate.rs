@@ -8,9 +8,6 @@ pub struct Jumpgate {
 
     station: Station,
 
-    pub drone_level: u32,
-    pub drone_speed: u32,
-
     pub hitbox: Bounds,
     pub pop_up: PopUp,
     pub hovered: bool,
@@ -19,9 +16,9 @@ pub struct Jumpgate {
     pub collections: Vec<Collection>,
     pub collect_interval: usize,
 
-    earn: u64,
-    limit: u64,
-    prog: u64,
+    earn: BigNum,
+    limit: BigNum,
+    prog: BigNum,
     
     pub unlockable: bool,
     pub unlocked: bool,
@@ -30,20 +27,14 @@ pub struct Jumpgate {
 }
 
 impl Jumpgate {
-    pub fn load() -> Self {
-        let hitbox = Bounds::new(GATE_BOX.0, GATE_BOX.1, GATE_BOX.2, GATE_BOX.3);
+    pub fn load(sector: &Sector) -> Self {
+        let (x, y, w, h) = sector.place(StationId::Jumpgate, GATE_BOX);
+        let hitbox = Bounds::new(x, y, w, h);
         let pop_up =  PopUp::new_fab("JUMPGATE".to_string(), Resources::Prestige);
         Jumpgate {
             drones: vec![],
           
-            station: Station {
-                drone_base: 20.,
-                drone_eff: 1.0,
-                drone_speed: 600.,
-            },
-
-            drone_level: 0,
-            drone_speed: 0,
+            station: Station::new(20., 600.),
 
             hitbox,
             pop_up: pop_up.clone(),
@@ -53,9 +44,9 @@ impl Jumpgate {
             collections: vec![],
             collect_interval: 20,
 
-            earn: 0,
-            limit: 0,
-            prog: 0,
+            earn: BigNum::ZERO,
+            limit: BigNum::ZERO,
+            prog: BigNum::ZERO,
 
             unlockable: false,
             unlocked: false,
@@ -64,7 +55,83 @@ impl Jumpgate {
         }
     }
 
-    pub fn update(&mut self, player: &mut Player, event_manager: &mut EventManager) {
+    pub fn simulate(&mut self, player: &Player) {
+        self.limit = player.prestige_limit;
+        self.earn = player.prestige_earned;
+        self.prog = player.prestige_prog;
+    }
+
+}
+
+impl POI for Jumpgate {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn get_station(&self) -> &Station {
+        &self.station
+    }
+
+    fn ids(&self) -> &'static [StationId] {
+        &[StationId::Jumpgate]
+    }
+
+    fn offers(&self, _id: StationId) -> &[Upgrade] {
+        &self.avail_upgrades
+    }
+
+    fn offers_mut(&mut self, _id: StationId) -> Option<&mut Vec<Upgrade>> {
+        Some(&mut self.avail_upgrades)
+    }
+
+    fn advance(&mut self, _id: StationId, index: usize, event_manager: &mut EventManager, rng: &mut Rng, sector: &Sector) -> Option<Upgrade> {
+        let upgrade = Upgrade::advance(&mut self.avail_upgrades, index, &GATE_UPGRADES, self.pop_up.panel, false);
+        self.upgrade(&upgrade, event_manager, rng, sector);
+        Some(upgrade)
+    }
+
+    fn save(&self) -> Option<StationSave> {
+        Some(StationSave {
+            station: self.station.clone(),
+            drones: 0,
+            unlockable: self.unlockable,
+            unlocked: self.unlocked,
+            upgrades: Upgrade::save(&self.avail_upgrades),
+            detail: StationDetail::None,
+        })
+    }
+
+    fn restore(&mut self, save: &StationSave, _rng: &mut Rng, _sector: &Sector) {
+        self.station = save.station.clone();
+        self.unlockable = save.unlockable;
+        self.unlocked = save.unlocked;
+        self.avail_upgrades = Upgrade::restore(&save.upgrades, &GATE_UPGRADES, self.pop_up.panel);
+    }
+
+    fn unlockable(&self) -> bool {
+        self.unlockable
+    }
+    
+    fn produce(&mut self) -> BigNum {
+        return BigNum::ZERO;
+    }
+
+    fn upgrade(&mut self, upgrade: &Upgrade, event_manager: &mut EventManager, _rng: &mut Rng, _sector: &Sector) {
+        for effect in upgrade.effects.iter() {
+            if self.station.apply(effect, &mut self.drones, event_manager) {
+                continue;
+            }
+            if *effect == UpgradeEffect::Construct {
+                self.unlocked = true;
+            }
+        }
+    }
+
+    fn update(&mut self, player: &mut Player, event_manager: &EventManager, research: &Research, _rng: &mut Rng, input: &mut SimInput) {
         let p = pointer();
         let rp = p.xy();
         
@@ -75,40 +142,26 @@ impl Jumpgate {
             self.hovered = false;
         }
 
-        // Update pop up position and buttons, apply upgrades
+        // Update pop up position and buttons, queue upgrades
         if self.hovered {
             // Pop up returns upgrade player clicks
-            if self.unlocked {
-                if let Some(upgrade) = self.pop_up.update_fabricator(self.hitbox, &self.station, &mut self.avail_upgrades, &GATE_UPGRADES, &player.resources) {
-                    self.upgrade(&upgrade, event_manager);
-                    player.upgrade(&upgrade);
-                }
+            let order = if self.unlocked {
+                self.pop_up.update_fabricator(self.hitbox, &mut self.avail_upgrades, &player.resources)
             } else {
-                if let Some(upgrade) = self.pop_up.update(self.hitbox, &self.station, &mut self.avail_upgrades, &GATE_UPGRADES, &player.resources) {
-                    self.upgrade(&upgrade, event_manager);
-                    player.upgrade(&upgrade);
-                }
-            }
+                self.pop_up.update(self.hitbox, &mut self.avail_upgrades, &player.resources)
+            };
+            let order = research.gate(StationId::Jumpgate, &mut self.avail_upgrades, order);
+            input.push(StationId::Jumpgate, order);
         }
-
-        // Produce Resources
-        let mut produced = (Resources::Research, 0);
-        produced.1 += self.produce();
         
         // Update collection numbers
         self.collections.retain_mut(|collection| {
             collection.update();
             collection.is_active // Keep only active collections
         }); 
-        
-        player.collect(produced);
-
-        self.limit = player.prestige_limit;
-        self.earn = player.prestige_earned;
-        self.prog = player.prestige_prog;
     }
 
-    pub fn handle_event(&mut self, event: &Event) {
+    fn handle_event(&mut self, event: &Event) {
         match event {
             Event::LateGame => {
                 self.unlockable = true;
@@ -118,7 +171,7 @@ impl Jumpgate {
         }
     }
 
-    pub fn draw(&self) {
+    fn draw(&self, _research: &Research) {
         let mut bob_box = self.hitbox;
         if self.unlocked {
             let bob =  f32::sin(tick() as f32 / 25.0 + 10.0) * 1.5;
@@ -155,7 +208,7 @@ impl Jumpgate {
         }
     }
 
-    pub fn draw_ui(&self) { 
+    fn draw_ui(&self, research: &Research) { 
         
         if self.hovered {
             // pop up
@@ -164,6 +217,7 @@ impl Jumpgate {
             } else {
                 self.pop_up.draw(&self.station, &self.avail_upgrades);
             }
+            self.pop_up.draw_projects(research, &[StationId::Jumpgate]);
         }
 
         // Draw collection numbers
@@ -171,31 +225,4 @@ impl Jumpgate {
             collection.draw();
         }
     }
-
 }
-
-impl POI for Jumpgate {
-    fn as_any(&self) -> &dyn Any {
-        self
-    }
-
-    fn as_any_mut(&mut self) -> &mut dyn Any {
-        self
-    }
-
-    fn get_station(&self) -> &Station {
-        &self.station
-    }
-    
-    fn produce(&mut self) -> u64 {
-        return 0;
-    }
-
-    fn upgrade(&mut self, upgrade: &Upgrade, event_manager: &mut EventManager) {
-        if upgrade.name == "CONSTRUCT" {
-            self.unlocked = true;
-        } else if upgrade.name.starts_with("JUMP") {
-            event_manager.trigger(Event::Prestige);
-        }
-    }
-}
\ No newline at end of file
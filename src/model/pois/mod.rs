@@ -12,6 +12,9 @@ pub use exoplanet::*;
 mod jumpgate;
 pub use jumpgate::*;
 
+mod pack_station;
+pub use pack_station::*;
+
 mod research_complex;
 pub use research_complex::*;
 
@@ -0,0 +1,302 @@
+use super::*;
+
+// A station declared by a content pack, everything about it comes from its PackStationDef
+#[derive(Debug, Clone, PartialEq, BorshDeserialize, BorshSerialize)]
+pub struct PackStation {
+    // Index into CONTENT.stations
+    pub def: usize,
+    pub drones: Vec<Drone>,
+
+    station: Station,
+
+    pub unlockable: bool,
+    unlocked: bool,
+
+    pub hitbox: Bounds,
+    pop_up: PopUp,
+    hovered: bool,
+
+    collections: Vec<Collection>,
+
+    pub avail_upgrades: Vec<Upgrade>,
+}
+
+impl PackStation {
+    pub fn load(def: usize) -> Self {
+        let pack = &CONTENT.stations[def];
+        let hitbox = Bounds::new(pack.bounds.0, pack.bounds.1, pack.bounds.2, pack.bounds.3);
+        let pop_up = PopUp::new(pack.name.clone(), pack.produces.clone());
+        PackStation {
+            def,
+            drones: vec![],
+            station: Station::new(pack.drone_base, pack.mode.interval()),
+            unlockable: false,
+            unlocked: false,
+
+            hitbox,
+            pop_up,
+            hovered: false,
+
+            collections: vec![],
+
+            avail_upgrades: vec![],
+        }
+    }
+
+    fn pack(&self) -> &'static PackStationDef {
+        &CONTENT.stations[self.def]
+    }
+
+    // What the drones produced, none until the station is built
+    pub fn simulate(&mut self) -> Option<(Resources, BigNum)> {
+        let produced = self.produce();
+        if !self.unlocked {
+            return None;
+        }
+        Some((self.pack().produces.clone(), produced))
+    }
+
+    pub fn purchase(&mut self, index: usize, player: &mut Player, event_manager: &mut EventManager, rng: &mut Rng, sector: &Sector) -> Result<Upgrade, Shortfall> {
+        let pack = self.pack();
+        let upgrade = Upgrade::buy(&mut self.avail_upgrades, index, &pack.upgrades, self.pop_up.panel, &mut player.resources)?;
+        self.upgrade(&upgrade, event_manager, rng, sector);
+        Ok(upgrade)
+    }
+
+    // Ticks per work cycle and the amount each drone produces per cycle
+    pub fn cycle(&self) -> (f32, BigNum) {
+        (self.station.drone_speed, BigNum::from_f64((self.station.drone_eff * self.station.drone_base) as f64).floor())
+    }
+
+    pub fn save(&self) -> StationSave {
+        StationSave {
+            station: self.station.clone(),
+            drones: self.drones.len() as u32,
+            unlockable: self.unlockable,
+            unlocked: self.unlocked,
+            upgrades: Upgrade::save(&self.avail_upgrades),
+            detail: StationDetail::None,
+        }
+    }
+
+    pub fn restore(def: usize, save: &StationSave, rng: &mut Rng, sector: &Sector) -> Self {
+        let mut station = PackStation::load(def);
+        station.station = save.station.clone();
+        station.unlockable = save.unlockable;
+        station.unlocked = save.unlocked;
+        station.avail_upgrades = Upgrade::restore(&save.upgrades, &station.pack().upgrades, station.pop_up.panel);
+        for _ in 0..save.drones {
+            station.spawn_drone(rng, sector);
+        }
+        station
+    }
+
+    fn spawn_drone(&mut self, rng: &mut Rng, sector: &Sector) {
+        let xy = self.hitbox.translate(self.hitbox.w()/2,self.hitbox.h()/2).xy();
+        self.drones.push(Drone::new(self.pack().mode.clone(), self.station.level, self.station.speed, xy, rng, sector));
+        self.pop_up.drones += 1;
+    }
+}
+
+impl POI for PackStation {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn get_station(&self) -> &Station {
+        &self.station
+    }
+
+    fn unlockable(&self) -> bool {
+        self.unlockable
+    }
+
+    fn produce(&mut self) -> BigNum {
+        let (period, amount) = self.cycle();
+        let mut produced = BigNum::ZERO;
+        for drone in self.drones.iter_mut() {
+            drone.work.produced = None;
+            if drone.work.advance(1., period) {
+                drone.work.produced = Some(amount);
+                produced += amount;
+            }
+        }
+        produced
+    }
+
+    fn idle_rate(&self) -> f32 {
+        if !self.unlocked {
+            return 0.;
+        }
+        let (period, amount) = self.cycle();
+        self.drones.len() as f32 * amount.to_f64() as f32 / (period / 60.)
+    }
+
+    fn upgrade(&mut self, upgrade: &Upgrade, event_manager: &mut EventManager, rng: &mut Rng, sector: &Sector) {
+        for effect in upgrade.effects.iter() {
+            if self.station.apply(effect, &mut self.drones, event_manager) {
+                continue;
+            }
+            match effect {
+                UpgradeEffect::Construct => {
+                    self.unlocked = true;
+                }
+                UpgradeEffect::AddDrone => {
+                    self.spawn_drone(rng, sector);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn update(&mut self, player: &mut Player, event_manager: &EventManager, _research: &Research, _rng: &mut Rng, input: &mut SimInput) {
+        // Update pop up position and buttons, queue upgrades
+        if self.hovered {
+            let order = self.pop_up.update(self.hitbox, &mut self.avail_upgrades, &player.resources);
+            input.push_pack(self.def, order);
+        }
+
+        // Hover check
+        let rp = pointer().xy();
+        if event_manager.dialogue.is_none() {
+            self.hovered = self.hitbox.intersects_xy(rp) || (self.hovered && self.pop_up.hovered());
+        } else {
+            self.hovered = false;
+        }
+    }
+
+    // Move drones and show collection numbers for what the simulation produced
+    fn animate(&mut self, scene: &mut Scene) {
+        let center = (self.hitbox.center_x() as f32, self.hitbox.center_y() as f32);
+        let radius = (self.hitbox.w() as f32, self.hitbox.h() as f32 / 4.);
+        let resource = self.pack().produces.clone();
+        for drone in self.drones.iter_mut() {
+            drone.orbit(&self.station, center, radius, scene.rng);
+            if let Some(amount) = drone.work.produced {
+                self.collections.push(Collection::new(drone.pos, (resource.clone(), amount)));
+            }
+        }
+
+        // Update collection numbers
+        self.collections.retain_mut(|collection| {
+            collection.update();
+            collection.is_active // Keep only active collections
+        });
+    }
+
+    fn handle_event(&mut self, event: &Event) {
+        if *event == Event::PackUnlockable(self.def) && !self.unlockable {
+            let pack = self.pack();
+            self.unlockable = true;
+            Upgrade::add_upgrade(&mut self.avail_upgrades, &pack.upgrades, 0, self.pop_up.panel);
+        }
+    }
+
+    fn draw(&self, _research: &Research) {
+        let sprite = &self.pack().sprite;
+        let mut bob_box = self.hitbox;
+        if self.unlocked {
+            let bob = f32::sin(tick() as f32 / 20.0 + self.def as f32) * 1.5;
+            bob_box = self.hitbox.translate_y(bob);
+        }
+
+        // Draw backside drones
+        for drone in self.drones.iter() {
+            if !drone.front {
+                drone.draw();
+            }
+        }
+
+        if self.hovered {
+            sprite!(&format!("{}_hovered", sprite), xy = bob_box.xy());
+        }
+        sprite!(sprite, xy = bob_box.xy());
+
+        // Draw drones
+        for drone in self.drones.iter() {
+            drone.draw_scan();
+            if drone.front {
+                drone.draw();
+            }
+        }
+
+        if !self.unlocked {
+            sprite!(&format!("{}_locked", sprite), xy = bob_box.xy());
+            text!("LOCKED", xy = bob_box.translate(-15, -4).center(), color = 0xffffffff);
+        }
+
+        // Draw collection numbers
+        for collection in self.collections.iter() {
+            collection.draw();
+        }
+    }
+
+    fn draw_ui(&self, _research: &Research) {
+        // pop up
+        if self.hovered {
+            self.pop_up.draw(&self.station, &self.avail_upgrades);
+        }
+    }
+}
+
+// Every pack station, one for each in CONTENT.stations and in the same order
+#[derive(Debug, Clone, PartialEq, BorshDeserialize, BorshSerialize)]
+pub struct Packs {
+    pub stations: Vec<PackStation>,
+}
+
+impl Packs {
+    pub fn new() -> Self {
+        Packs { stations: (0..CONTENT.stations.len()).map(PackStation::load).collect() }
+    }
+
+    // Progress by station name, so adding or reordering packs leaves saves readable
+    pub fn save(&self) -> Vec<(String, StationSave)> {
+        self.stations.iter().map(|station| (station.pack().name.clone(), station.save())).collect()
+    }
+
+    // Stations of packs no longer bundled are dropped, new ones start locked
+    pub fn restore(saves: &[(String, StationSave)], rng: &mut Rng, sector: &Sector) -> Self {
+        let stations = CONTENT.stations.iter().enumerate().map(|(def, pack)| {
+            match saves.iter().find(|(name, _)| *name == pack.name) {
+                Some((_, save)) => PackStation::restore(def, save, rng, sector),
+                None => PackStation::load(def),
+            }
+        }).collect();
+        Packs { stations }
+    }
+
+    // What each station that can be built produced this tick
+    pub fn simulate(&mut self) -> Vec<(Resources, BigNum)> {
+        self.stations.iter_mut().filter(|station| station.unlockable).filter_map(|station| station.simulate()).collect()
+    }
+
+    // Buy an upgrade a pack station has on offer, None when it has no such upgrade
+    pub fn purchase(&mut self, station: usize, index: usize, player: &mut Player, event_manager: &mut EventManager, rng: &mut Rng, sector: &Sector) -> Option<Result<Upgrade, Shortfall>> {
+        let station = self.stations.get_mut(station).filter(|station| station.unlockable)?;
+        station.avail_upgrades.get(index).filter(|upgrade| upgrade.level < upgrade.max_level)?;
+        Some(station.purchase(index, player, event_manager, rng, sector))
+    }
+
+    // Stations still locked that this event unlocks
+    pub fn unlocked_by(&self, event: &Event) -> Vec<usize> {
+        self.stations.iter().filter(|station| !station.unlockable && station.pack().unlock == *event).map(|station| station.def).collect()
+    }
+
+    // Produced while away by each pack resource
+    pub fn idle(&self, secs: f32) -> Vec<(Resources, BigNum)> {
+        self.stations.iter().filter(|station| station.idle_rate() > 0.).map(|station| {
+            (station.pack().produces.clone(), BigNum::from_f64((station.idle_rate() * secs) as f64).floor())
+        }).collect()
+    }
+}
+
+impl Default for Packs {
+    fn default() -> Self {
+        Packs::new()
+    }
+}
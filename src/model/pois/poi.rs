@@ -5,6 +5,63 @@ pub struct Station {
     pub drone_base: f32,
     pub drone_eff: f32,
     pub drone_speed: f32,
+    // Purchased efficiency and speed levels, also given to new drones
+    pub level: u32,
+    pub speed: u32,
+}
+
+impl Station {
+    pub fn new(drone_base: f32, drone_speed: f32) -> Self {
+        Station {
+            drone_base,
+            drone_eff: 1.0,
+            drone_speed,
+            level: 0,
+            speed: 0,
+        }
+    }
+
+    // Apply the effects every station shares, returns false for station specific ones
+    pub fn apply(&mut self, effect: &UpgradeEffect, drones: &mut [Drone], event_manager: &mut EventManager) -> bool {
+        match effect {
+            UpgradeEffect::AddEfficiency(amount) => {
+                // Snap to avoid float drift across many levels
+                self.drone_eff = ((self.drone_eff + amount) * 1000.).round() / 1000.;
+                self.level += 1;
+                for drone in drones.iter_mut() {
+                    drone.level += 1;
+                }
+            }
+            UpgradeEffect::AddSpeed(levels) => {
+                self.speed += levels;
+                for drone in drones.iter_mut() {
+                    drone.speed += levels;
+                }
+            }
+            UpgradeEffect::ScaleInterval(scale) => {
+                self.drone_speed *= scale;
+                self.speed += 1;
+                for drone in drones.iter_mut() {
+                    drone.speed += 1;
+                }
+            }
+            UpgradeEffect::TriggerEvent(event) => {
+                event_manager.trigger(event.clone());
+            }
+            _ => return false,
+        }
+        true
+    }
+}
+
+// What a station may move while it animates, besides itself
+pub struct Scene<'a> {
+    pub player: &'a mut Player,
+    pub sector: &'a Sector,
+    // The cosmetic stream, what is only drawn must not move the simulation's
+    pub rng: &'a mut Rng,
+    pub asteroid_field: &'a mut AsteroidField,
+    pub nebula_storm: &'a mut NebulaStorm,
 }
 
 pub trait POI {
@@ -14,18 +71,68 @@ pub trait POI {
 
     fn get_station(&self) -> &Station;
 
-    fn manual_produce(&mut self) -> u64 {
-        return 0;
+    // StationIds the station's upgrade lists go by, see Simulation::registry
+    fn ids(&self) -> &'static [StationId] {
+        &[]
     }
 
-    fn produce(&mut self) -> u64 {
-        return 0;
+    // Upgrades listed under id, empty where the UI would not show any
+    fn offers(&self, _id: StationId) -> &[Upgrade] {
+        &[]
     }
 
-    fn prod_rate(drones: u32, drone_base: u32, drone_eff: u32, drone_speed: u32) -> u64 {
-        0
+    fn offers_mut(&mut self, _id: StationId) -> Option<&mut Vec<Upgrade>> {
+        None
+    }
+
+    // Level up and apply the upgrade at index under id, once it is paid for
+    fn advance(&mut self, _id: StationId, _index: usize, _event_manager: &mut EventManager, _rng: &mut Rng, _sector: &Sector) -> Option<Upgrade> {
+        None
+    }
+
+    // Progression to save, None for what is saved apart
+    fn save(&self) -> Option<StationSave> {
+        None
+    }
+
+    // Put saved progression back on a freshly loaded station, respawned drones take their phase from rng
+    fn restore(&mut self, _save: &StationSave, _rng: &mut Rng, _sector: &Sector) {}
+
+    fn manual_produce(&mut self) -> BigNum {
+        return BigNum::ZERO;
     }
 
-    fn upgrade(&mut self, upgrade: &Upgrade, event_manager: &mut EventManager) {}
+    fn produce(&mut self) -> BigNum {
+        return BigNum::ZERO;
+    }
+
+    fn prod_rate(drones: u32, drone_base: u32, drone_eff: u32, drone_speed: u32) -> BigNum where Self: Sized {
+        BigNum::ZERO
+    }
+
+    // Estimated production per second, used to credit time spent away
+    fn idle_rate(&self) -> f32 {
+        0.
+    }
+
+    // New drones take their phase from rng and start out from the depot of the sector
+    fn upgrade(&mut self, upgrade: &Upgrade, event_manager: &mut EventManager, _rng: &mut Rng, _sector: &Sector) {}
+
+    // Whether the station shows up and takes part in the frame
+    fn unlockable(&self) -> bool {
+        true
+    }
+
+    // Hover and pop up handling, clicked upgrades go into input
+    fn update(&mut self, _player: &mut Player, _event_manager: &EventManager, _research: &Research, _rng: &mut Rng, _input: &mut SimInput) {}
+
+    // Move drones and show collection numbers for what the simulation produced
+    fn animate(&mut self, _scene: &mut Scene) {}
+
+    fn draw(&self, _research: &Research) {}
+
+    fn draw_ui(&self, _research: &Research) {}
+
+    fn handle_event(&mut self, _event: &Event) {}
 }
 
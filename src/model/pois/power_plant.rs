@@ -8,9 +8,6 @@ pub struct PowerPlant {
 
     station: Station,
 
-    pub drone_level: u32,
-    pub drone_speed: u32,
-
     pub unlockable: bool,
     unlocked: bool,
 
@@ -22,21 +19,16 @@ pub struct PowerPlant {
     collections: Vec<Collection>,
     collect_interval: usize,
 
-    avail_upgrades: Vec<Upgrade>,
+    pub avail_upgrades: Vec<Upgrade>,
 }
 impl PowerPlant {
-    pub fn load() -> Self {
-        let hitbox = Bounds::new(PLANT_BOX.0, PLANT_BOX.1, PLANT_BOX.2, PLANT_BOX.3);
+    pub fn load(sector: &Sector) -> Self {
+        let (x, y, w, h) = sector.place(StationId::PowerPlant, PLANT_BOX);
+        let hitbox = Bounds::new(x, y, w, h);
         let pop_up =  PopUp::new("POWER PLANT".to_string(), Resources::Power);
         PowerPlant {
             drones: vec![],
-            station: Station {
-                drone_base: 20.,
-                drone_eff: 1.0,
-                drone_speed: 600.,
-            },
-            drone_level: 0,
-            drone_speed: 0,
+            station: Station::new(12. * sector.yield_of(&Resources::Power), 600.),
             unlockable: false,
             unlocked: false,
 
@@ -52,14 +44,137 @@ impl PowerPlant {
         }
     }
 
-    pub fn update(&mut self, player: &mut Player, event_manager: &mut EventManager, nebula: &mut NebulaStorm) {
-        // Update pop up position and buttons, apply upgrades
-        if self.hovered {
-            if let Some(upgrade) = self.pop_up.update(self.hitbox, &self.station, &mut self.avail_upgrades, &POWER_UPGRADES, &player.resources) {
-                self.upgrade(&upgrade, event_manager);
-                player.upgrade(&upgrade);
+    // POWER the drones produced, none until the plant is built
+    pub fn simulate(&mut self, stats: &mut Stats) -> Option<BigNum> {
+        let produced = self.produce();
+        if !self.unlocked {
+            return None;
+        }
+        // Each delivery is one bolt caught from the storm
+        stats.harvested(self.drones.iter().filter(|drone| drone.work.produced.is_some()).count() as u64);
+        Some(produced)
+    }
+
+    // Ticks between lightning strikes and POWER per strike for each conduit drone
+    pub fn cycle(&self) -> (f32, BigNum) {
+        (DroneMode::Conduit.interval(), BigNum::from_f64((self.station.drone_eff.round() * self.station.drone_base) as f64).floor())
+    }
+
+    fn spawn_drone(&mut self, rng: &mut Rng, sector: &Sector) {
+        let xy = self.hitbox.translate(self.hitbox.w()/2,self.hitbox.h()/2).xy();
+        self.drones.push(Drone::new(DroneMode::Conduit, self.station.level, self.station.speed, xy, rng, sector));
+        self.pop_up.drones += 1;
+    }
+}
+
+
+impl POI for PowerPlant {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn get_station(&self) -> &Station {
+        &self.station
+    }
+
+    fn ids(&self) -> &'static [StationId] {
+        &[StationId::PowerPlant]
+    }
+
+    fn offers(&self, _id: StationId) -> &[Upgrade] {
+        &self.avail_upgrades
+    }
+
+    fn offers_mut(&mut self, _id: StationId) -> Option<&mut Vec<Upgrade>> {
+        Some(&mut self.avail_upgrades)
+    }
+
+    fn advance(&mut self, _id: StationId, index: usize, event_manager: &mut EventManager, rng: &mut Rng, sector: &Sector) -> Option<Upgrade> {
+        let upgrade = Upgrade::advance(&mut self.avail_upgrades, index, &POWER_UPGRADES, self.pop_up.panel, false);
+        self.upgrade(&upgrade, event_manager, rng, sector);
+        Some(upgrade)
+    }
+
+    fn save(&self) -> Option<StationSave> {
+        Some(StationSave {
+            station: self.station.clone(),
+            drones: self.drones.len() as u32,
+            unlockable: self.unlockable,
+            unlocked: self.unlocked,
+            upgrades: Upgrade::save(&self.avail_upgrades),
+            detail: StationDetail::None,
+        })
+    }
+
+    fn restore(&mut self, save: &StationSave, rng: &mut Rng, sector: &Sector) {
+        self.station = save.station.clone();
+        self.unlockable = save.unlockable;
+        self.unlocked = save.unlocked;
+        self.avail_upgrades = Upgrade::restore(&save.upgrades, &POWER_UPGRADES, self.pop_up.panel);
+        for _ in 0..save.drones {
+            self.spawn_drone(rng, sector);
+        }
+    }
+
+    fn unlockable(&self) -> bool {
+        self.unlockable
+    }
+
+    fn produce(&mut self) -> BigNum {
+        let (period, amount) = self.cycle();
+        let mut produced = BigNum::ZERO;
+        for drone in self.drones.iter_mut() {
+            drone.work.produced = None;
+            if drone.work.advance(1., period) {
+                drone.work.produced = Some(amount);
+                produced += amount;
+            }
+        }
+        produced
+    }
+
+    fn idle_rate(&self) -> f32 {
+        if !self.unlocked {
+            return 0.;
+        }
+        let (period, amount) = self.cycle();
+        self.drones.len() as f32 * amount.to_f64() as f32 / (period / 60.)
+    }
+
+    fn upgrade(&mut self, upgrade: &Upgrade, event_manager: &mut EventManager, rng: &mut Rng, sector: &Sector) {
+        for effect in upgrade.effects.iter() {
+            if self.station.apply(effect, &mut self.drones, event_manager) {
+                continue;
+            }
+            match effect {
+                UpgradeEffect::Construct => {
+                    self.unlocked = true;
+                }
+                UpgradeEffect::AddDrone => {
+                    self.spawn_drone(rng, sector);
+                    if self.drones.len() == 1 {
+                        event_manager.trigger(Event::LateGame);
+                    }
+                    if self.drones.len() == COMPLEX_DRONES {
+                        event_manager.trigger(Event::ComplexUnlockable);
+                    }
+                }
+                _ => {}
             }
         }
+    }
+
+    fn update(&mut self, player: &mut Player, event_manager: &EventManager, research: &Research, _rng: &mut Rng, input: &mut SimInput) {
+        // Update pop up position and buttons, queue upgrades
+        if self.hovered {
+            let order = self.pop_up.update(self.hitbox, &mut self.avail_upgrades, &player.resources);
+            let order = research.gate(StationId::PowerPlant, &mut self.avail_upgrades, order);
+            input.push(StationId::PowerPlant, order);
+        }
 
         // Hover check
         let p = pointer();
@@ -69,31 +184,19 @@ impl PowerPlant {
         } else {
             self.hovered = false;
         }
-        // Produce Resources
-        let mut produced = (Resources::Power, 0);
-        
-        // Initial click
-        if self.hitbox.intersects_xy(rp) && p.just_pressed() && tick() - self.clicked_at >= self.collect_interval {
-            self.clicked_at = tick();
-        }
-        // Manually produce resources every 30 ticks
-        if tick() - self.clicked_at >= self.collect_interval {
-            if self.hitbox.intersects_xy(rp) && p.pressed() {
-                produced.1 += self.manual_produce();
-            }
-            self.clicked_at = tick();
-        }
+    }
 
+    // Move drones and show collection numbers for what the simulation produced
+    fn animate(&mut self, scene: &mut Scene) {
         for drone in self.drones.iter_mut() {
-            if drone.conduit(nebula) {
-                let amount =  ((1.0 + self.drone_level as f32 * 0.9).round() * 12.) as u64;
-                produced.1 += amount;
-                self.collections.push(
-                    Collection::new(
-                        nebula.bolts[nebula.bolts.len() - 1].segments[nebula.bolts[nebula.bolts.len() - 1].segments.len() - 1].end,
-                        (Resources::Power, amount)
-                    )
-                );
+            let struck = drone.conduit(scene.nebula_storm, scene.rng, scene.sector);
+            if let Some(amount) = drone.work.produced {
+                // Numbers pop where the bolt lands
+                let pos = match scene.nebula_storm.bolts.last().and_then(|bolt| bolt.segments.last()) {
+                    Some(segment) if struck => segment.end,
+                    _ => drone.pos,
+                };
+                self.collections.push(Collection::new(pos, (Resources::Power, amount)));
             }
         }
         
@@ -102,13 +205,9 @@ impl PowerPlant {
             collection.update();
             collection.is_active // Keep only active collections
         }); 
-        
-        if self.unlocked {
-            player.collect(produced);
-        }
     }
 
-    pub fn handle_event(&mut self, event: &Event) {
+    fn handle_event(&mut self, event: &Event) {
         match event {
             Event::PowerPlantUnlockable => {
                 self.unlockable = true;
@@ -118,7 +217,7 @@ impl PowerPlant {
         }
     }
 
-    pub fn draw(&self) {
+    fn draw(&self, _research: &Research) {
         let mut bob_box = self.hitbox;
         if self.unlocked {
             let bob =  f32::sin(tick() as f32 / 20.0 + 5.0) * 1.5;
@@ -161,57 +260,11 @@ impl PowerPlant {
         }
     }
 
-    pub fn draw_ui(&self) {
+    fn draw_ui(&self, research: &Research) {
         // pop up
         if self.hovered {
             self.pop_up.draw(&self.station, &self.avail_upgrades);
+            self.pop_up.draw_projects(research, &[StationId::PowerPlant]);
         }
     }
 }
-
-
-impl POI for PowerPlant {
-    fn as_any(&self) -> &dyn Any {
-        self
-    }
-
-    fn as_any_mut(&mut self) -> &mut dyn Any {
-        self
-    }
-
-    fn get_station(&self) -> &Station {
-        &self.station
-    }
-
-    fn produce(&mut self) -> u64 {
-        let mut produced = 0;
-
-        produced
-    }
-
-    fn upgrade(&mut self, upgrade: &Upgrade, event_manager: &mut EventManager) {
-        if upgrade.name == "CONSTRUCT" {
-            self.unlocked = true;
-            event_manager.trigger(Event::UnlockPowerPlant);
-        } else if upgrade.name.starts_with("DEPLOY") {
-            let xy = self.hitbox.translate(self.hitbox.w()/2,self.hitbox.h()/2).xy();
-            self.drones.push(Drone::new(DroneMode::Conduit, self.drone_level, self.drone_speed, xy));
-            self.pop_up.drones += 1;
-            if self.drones.len() == 1 {
-                event_manager.trigger(Event::LateGame);
-            }
-        }
-        else if upgrade.name.starts_with("REFLECT") {
-            self.drone_level += 1;
-            for drone in self.drones.iter_mut() {
-                drone.level += 1;
-            }
-        }
-        else if upgrade.name.starts_with("ARC") {
-            self.drone_speed += 1;
-            for drone in self.drones.iter_mut() {
-                drone.speed += 1;
-            }
-        }
-    }
-}
\ No newline at end of file
@@ -1,13 +1,13 @@
 use super::*;
 
 pub static COMPLEX_BOX: (i32, i32, i32, i32) = (448, 352, 64, 64);
+// Power plant drones that bring the complex into the sector
+pub const COMPLEX_DRONES: usize = 5;
 
 #[derive(Debug, Clone, PartialEq, BorshDeserialize, BorshSerialize)]
 pub struct ResearchComplex {
     pub drones: Vec<Drone>,
     station: Station,
-    pub drone_level: u32,
-    pub drone_speed: u32,
 
     pub hitbox: Bounds,
     pub pop_up: PopUp,
@@ -24,19 +24,13 @@ pub struct ResearchComplex {
 }
 
 impl ResearchComplex {
-    pub fn load() -> Self {
-        let hitbox = Bounds::new(COMPLEX_BOX.0, COMPLEX_BOX.1, COMPLEX_BOX.2, COMPLEX_BOX.3);   
+    pub fn load(sector: &Sector) -> Self {
+        let (x, y, w, h) = sector.place(StationId::ResearchComplex, COMPLEX_BOX);
+        let hitbox = Bounds::new(x, y, w, h);   
         let pop_up =  PopUp::new("RESEARCH COMPLEX".to_string(), Resources::Research);
         ResearchComplex { 
             drones: vec![],
-            station: Station {
-                drone_base: 20.,
-                drone_eff: 1.0,
-                drone_speed: 600.,
-            },
-
-            drone_level: 0,
-            drone_speed: 0,
+            station: Station::new(20., 600.),
 
             hitbox,
             pop_up: pop_up.clone(),
@@ -53,7 +47,92 @@ impl ResearchComplex {
         }
     }
 
-    pub fn update(&mut self, player: &mut Player, event_manager: &mut EventManager) {
+    fn spawn_drone(&mut self, rng: &mut Rng, sector: &Sector) {
+        let xy = self.hitbox.translate(self.hitbox.w()/2,self.hitbox.h()/2).xy();
+        self.drones.push(Drone::new(DroneMode::Survey, self.station.level, self.station.speed, xy, rng, sector));
+        self.pop_up.drones += 1;
+    }
+}
+
+impl POI for ResearchComplex {
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn get_station(&self) -> &Station {
+        &self.station
+    }
+
+    fn ids(&self) -> &'static [StationId] {
+        &[StationId::ResearchComplex]
+    }
+
+    fn offers(&self, _id: StationId) -> &[Upgrade] {
+        &self.avail_upgrades
+    }
+
+    fn offers_mut(&mut self, _id: StationId) -> Option<&mut Vec<Upgrade>> {
+        Some(&mut self.avail_upgrades)
+    }
+
+    fn advance(&mut self, _id: StationId, index: usize, event_manager: &mut EventManager, rng: &mut Rng, sector: &Sector) -> Option<Upgrade> {
+        let upgrade = Upgrade::advance(&mut self.avail_upgrades, index, &COMPLEX_UPGRADES, self.pop_up.panel, false);
+        self.upgrade(&upgrade, event_manager, rng, sector);
+        Some(upgrade)
+    }
+
+    fn save(&self) -> Option<StationSave> {
+        Some(StationSave {
+            station: self.station.clone(),
+            drones: self.drones.len() as u32,
+            unlockable: self.unlockable,
+            unlocked: self.unlocked,
+            upgrades: Upgrade::save(&self.avail_upgrades),
+            detail: StationDetail::None,
+        })
+    }
+
+    fn restore(&mut self, save: &StationSave, rng: &mut Rng, sector: &Sector) {
+        self.station = save.station.clone();
+        self.unlockable = save.unlockable;
+        self.unlocked = save.unlocked;
+        self.avail_upgrades = Upgrade::restore(&save.upgrades, &COMPLEX_UPGRADES, self.pop_up.panel);
+        for _ in 0..save.drones {
+            self.spawn_drone(rng, sector);
+        }
+    }
+
+    fn unlockable(&self) -> bool {
+        self.unlockable
+    }
+    
+    fn produce(&mut self) -> BigNum {
+        return BigNum::ZERO;
+    }
+    
+    fn upgrade(&mut self, upgrade: &Upgrade, event_manager: &mut EventManager, rng: &mut Rng, sector: &Sector) {
+        for effect in upgrade.effects.iter() {
+            if self.station.apply(effect, &mut self.drones, event_manager) {
+                continue;
+            }
+            match effect {
+                UpgradeEffect::Construct => {
+                    self.unlocked = true;
+                }
+                UpgradeEffect::AddDrone => {
+                    self.spawn_drone(rng, sector);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn update(&mut self, player: &mut Player, event_manager: &EventManager, research: &Research, rng: &mut Rng, input: &mut SimInput) {
         let p = pointer();
         let rp = p.xy();
         
@@ -64,57 +143,33 @@ impl ResearchComplex {
             self.hovered = false;
         }
 
-        // Update pop up position and buttons, apply upgrades
+        // Update pop up position and buttons, queue upgrades
         if self.hovered {
             // Pop up returns upgrade player clicks
-            if let Some(upgrade) = self.pop_up.update(self.hitbox, &self.station, &mut self.avail_upgrades, &COMPLEX_UPGRADES, &player.resources) {
-                self.upgrade(&upgrade, event_manager);
-                player.upgrade(&upgrade);
-            }
+            let order = self.pop_up.update(self.hitbox, &mut self.avail_upgrades, &player.resources);
+            let order = research.gate(StationId::ResearchComplex, &mut self.avail_upgrades, order);
+            input.push(StationId::ResearchComplex, order);
         }
 
-        // Produce Resources
-        let mut produced = (Resources::Research, 0);
-        
-        // Initial click
-        if self.hovered && self.hitbox.intersects_xy(rp) 
-            && p.just_pressed() && tick() - self.clicked_at >= self.collect_interval {
-            self.clicked_at = tick();
-            produced.1 += self.manual_produce();
-            player.scan();
-        }
-        // Manually produce resources every 30 ticks
-        if tick() - self.clicked_at >= self.collect_interval {
-            if self.hitbox.intersects_xy(rp) && p.pressed() {
-                produced.1 += self.manual_produce();
-                player.scan();
-            }
-            self.clicked_at = tick();
+        if self.hovered && self.hitbox.intersects_xy(rp) && p.just_pressed() {
+            player.scan(rng);
         }
-        //if p.released() { self.clicked_at = 0; }
-
-        produced.1 +=  self.produce();
         
         // Update collection numbers
         self.collections.retain_mut(|collection| {
             collection.update();
             collection.is_active // Keep only active collections
         }); 
-        
-        player.collect(produced);
     }
 
-    pub fn handle_event(&mut self, event: &Event) {
-        match event {
-            // Event::LateGame => {
-            //     self.unlockable = true;
-            //     Upgrade::add_upgrade(&mut self.avail_upgrades, &COMPLEX_UPGRADES, 0, self.pop_up.panel);
-            // }
-            _ => {}
+    fn handle_event(&mut self, event: &Event) {
+        if *event == Event::ComplexUnlockable && !self.unlockable {
+            self.unlockable = true;
+            Upgrade::add_upgrade(&mut self.avail_upgrades, &COMPLEX_UPGRADES, 0, self.pop_up.panel);
         }
     }
 
-    pub fn draw(&self) {
+    fn draw(&self, research: &Research) {
 
         // Draw backside drones
         for drone in self.drones.iter() {
@@ -148,9 +203,11 @@ impl ResearchComplex {
             text!("LOCKED", xy = self.hitbox.translate(-15,-3).center(), color = 0xffffffff); 
         }
 
-        if self.hovered {
-            // pop up
-            self.pop_up.draw(&self.station, &self.avail_upgrades);
+        if let Some(project) = research.projects(StationId::ResearchComplex).first() {
+            let bar = self.hitbox.translate_y(self.hitbox.h() as i32 + 4).height(8);
+            rect!(xy = bar.xy(), wh = bar.wh(), border_radius = 2, border_size = 1, color = 0x1f122bff, border_color = 0xffffffff);
+            rect!(xy = (bar.x() + 2, bar.y() + 2), wh = (((bar.w() - 4) as f32 * project.progress()) as u32, bar.h() - 4), color = 0xffffffff);
+            text!(project.name(), xy = (bar.x(), bar.bottom() + 2), color = 0xffffffff);
         }
 
         // Draw collection numbers
@@ -159,27 +216,12 @@ impl ResearchComplex {
         }
         
     }
-}
-
-impl POI for ResearchComplex {
-
-    fn as_any(&self) -> &dyn Any {
-        self
-    }
-
-    fn as_any_mut(&mut self) -> &mut dyn Any {
-        self
-    }
-
-    fn get_station(&self) -> &Station {
-        &self.station
-    }
-    
-    fn produce(&mut self) -> u64 {
-        return 0;
-    }
-    
-    fn upgrade(&mut self, upgrade: &Upgrade, event_manager: &mut EventManager) {
 
+    fn draw_ui(&self, research: &Research) {
+        // pop up
+        if self.hovered {
+            self.pop_up.draw(&self.station, &self.avail_upgrades);
+            self.pop_up.draw_projects(research, &[StationId::ResearchComplex]);
+        }
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,330 @@
+use super::*;
+use once_cell::sync::Lazy;
+use std::sync::Mutex;
+use turbo::input::Button;
+use turbo::os::encoding;
+
+// Every recording starts with this
+pub const REPLAY_MAGIC: [u8; 4] = *b"TREP";
+// Start of an exported recording, followed by the encoded recording in base64
+pub const REPLAY_PREFIX: &str = "TREP-";
+// Ten minutes at 60 frames a second
+pub const MAX_RECORDING_FRAMES: usize = 60 * 60 * 10;
+// Magic, save version and checksum
+const HEADER_LEN: usize = 4 + 2 + 4;
+
+static REPLAY: Lazy<Mutex<ReplayState>> = Lazy::new(|| Mutex::new(ReplayState::default()));
+
+// Everything the game reads from the pointer and gamepad in one frame
+#[derive(Debug, Clone, Copy, Default, PartialEq, BorshDeserialize, BorshSerialize)]
+pub struct FrameInput {
+    // Screen position, before the camera
+    pub pointer: (i32, i32),
+    pub pointer_state: u8,
+    pub scroll: (i32, i32),
+    // Up, down, left, right, a, b, x, y, start, select
+    pub gamepad: [u8; 10],
+}
+
+impl FrameInput {
+    // Read this frame's input from the engine
+    fn live() -> Self {
+        let p = turbo::input::pointer();
+        let gp = turbo::input::gamepad(0);
+        let pointer_state = if p.just_pressed() {
+            Button::JustPressed
+        } else if p.just_released() {
+            Button::JustReleased
+        } else if p.pressed() {
+            Button::Pressed
+        } else {
+            Button::Released
+        };
+        FrameInput {
+            pointer: p.xy_fixed(),
+            pointer_state: pointer_state.into(),
+            scroll: p.scroll_delta(),
+            gamepad: [gp.up, gp.down, gp.left, gp.right, gp.a, gp.b, gp.x, gp.y, gp.start, gp.select].map(|button| button.into()),
+        }
+    }
+}
+
+// A session to replay, the state it started from and the input of every frame after
+#[derive(Debug, Clone, PartialEq, BorshDeserialize, BorshSerialize)]
+pub struct Recording {
+    // Borsh SaveData at SAVE_VERSION, random streams included
+    pub start: Vec<u8>,
+    pub frames: Vec<FrameInput>,
+}
+
+impl Recording {
+    pub fn new(start: &SaveData) -> Self {
+        Recording {
+            start: start.try_to_vec().unwrap_or_default(),
+            frames: vec![],
+        }
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        let body = self.try_to_vec().unwrap_or_default();
+        let mut data = Vec::with_capacity(HEADER_LEN + body.len());
+        data.extend_from_slice(&REPLAY_MAGIC);
+        data.extend_from_slice(&SAVE_VERSION.to_le_bytes());
+        data.extend_from_slice(&crc32(&body).to_le_bytes());
+        data.extend_from_slice(&body);
+        data
+    }
+
+    // Recordings are not migrated, they only replay on the save version they were made with
+    pub fn decode(data: &[u8]) -> Result<Recording, SaveError> {
+        if data.is_empty() {
+            return Err(SaveError::Empty);
+        }
+        if !data.starts_with(&REPLAY_MAGIC) {
+            return Err(SaveError::Corrupt("not a recording".to_string()));
+        }
+        if data.len() < HEADER_LEN {
+            return Err(SaveError::Truncated);
+        }
+        let version = u16::from_le_bytes([data[4], data[5]]);
+        if version > SAVE_VERSION {
+            return Err(SaveError::TooNew(version));
+        }
+        if version < SAVE_VERSION {
+            return Err(SaveError::Corrupt(format!("recorded on save version {}", version)));
+        }
+        let expected = u32::from_le_bytes([data[6], data[7], data[8], data[9]]);
+        let body = &data[HEADER_LEN..];
+        let found = crc32(body);
+        if found != expected {
+            return Err(SaveError::Checksum { expected, found });
+        }
+        Recording::try_from_slice(body).map_err(|err| SaveError::Corrupt(err.to_string()))
+    }
+
+    // Text to attach to a bug report
+    pub fn export(&self) -> String {
+        format!("{}{}", REPLAY_PREFIX, encoding::encode_base64(self.encode()))
+    }
+
+    pub fn import(text: &str) -> Result<Recording, SaveError> {
+        let text: String = text.split_whitespace().collect();
+        let body = text.strip_prefix(REPLAY_PREFIX).ok_or(SaveError::NotExport)?;
+        let data = encoding::decode_base64(body).map_err(|err| SaveError::Corrupt(err.to_string()))?;
+        Recording::decode(&data)
+    }
+
+    pub fn start(&self) -> Result<SaveData, SaveError> {
+        SaveData::try_from_slice(&self.start).map_err(|err| SaveError::Corrupt(err.to_string()))
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+enum Mode {
+    #[default]
+    Live,
+    Recording(Recording),
+    Replaying { recording: Recording, frame: usize },
+}
+
+#[derive(Debug, Clone, Default)]
+struct ReplayState {
+    mode: Mode,
+    // What pointer() and gamepad() return this frame
+    input: FrameInput,
+}
+
+// Records live input or feeds a recording back in, one frame at a time
+pub struct Replay;
+
+impl Replay {
+    // Called first thing every frame, returns true once a replay has run out of frames
+    pub fn begin_frame() -> bool {
+        let mut replay = REPLAY.lock().unwrap();
+        let state = &mut *replay;
+        match &mut state.mode {
+            Mode::Live => {
+                state.input = FrameInput::live();
+            }
+            Mode::Recording(recording) => {
+                state.input = FrameInput::live();
+                recording.frames.push(state.input);
+            }
+            Mode::Replaying { recording, frame } => {
+                if let Some(input) = recording.frames.get(*frame) {
+                    state.input = *input;
+                    *frame += 1;
+                } else {
+                    state.mode = Mode::Live;
+                    state.input = FrameInput::live();
+                    return true;
+                }
+            }
+        }
+        false
+    }
+
+    pub fn record(start: &SaveData) {
+        REPLAY.lock().unwrap().mode = Mode::Recording(Recording::new(start));
+    }
+
+    // Stop recording, returns the recording if there was one
+    pub fn stop() -> Option<Recording> {
+        let mut replay = REPLAY.lock().unwrap();
+        match std::mem::take(&mut replay.mode) {
+            Mode::Recording(recording) => Some(recording),
+            mode => {
+                replay.mode = mode;
+                None
+            }
+        }
+    }
+
+    pub fn play(recording: Recording) {
+        REPLAY.lock().unwrap().mode = Mode::Replaying { recording, frame: 0 };
+    }
+
+    pub fn is_recording() -> bool {
+        matches!(REPLAY.lock().unwrap().mode, Mode::Recording(_))
+    }
+
+    pub fn is_replaying() -> bool {
+        matches!(REPLAY.lock().unwrap().mode, Mode::Replaying { .. })
+    }
+
+    // Frames recorded or replayed so far, and the total when replaying
+    pub fn progress() -> (usize, usize) {
+        match &REPLAY.lock().unwrap().mode {
+            Mode::Live => (0, 0),
+            Mode::Recording(recording) => (recording.frames.len(), recording.frames.len()),
+            Mode::Replaying { recording, frame } => (*frame, recording.frames.len()),
+        }
+    }
+
+    pub fn input() -> FrameInput {
+        REPLAY.lock().unwrap().input
+    }
+}
+
+// Stands in for the engine's pointer so recorded input can be fed back in
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Pointer {
+    x: i32,
+    y: i32,
+    state: Button,
+    scroll: (i32, i32),
+}
+
+impl Pointer {
+    pub fn intersects(&self, x: i32, y: i32, w: u32, h: u32) -> bool {
+        Bounds::new(x, y, w, h).intersects_xy(self.xy())
+    }
+
+    pub fn intersects_fixed(&self, x: i32, y: i32, w: u32, h: u32) -> bool {
+        Bounds::new(x, y, w, h).intersects_xy(self.xy_fixed())
+    }
+
+    // Position in the world, through the camera
+    pub fn xy(&self) -> (i32, i32) {
+        let (x, y, z) = camera::xyz();
+        let (w, h) = resolution();
+        let (cx, cy) = (w as f32 / 2.0, h as f32 / 2.0);
+        (((self.x as f32 - cx) / z + x).round() as i32, ((self.y as f32 - cy) / z + y).round() as i32)
+    }
+
+    pub fn xy_fixed(&self) -> (i32, i32) {
+        (self.x, self.y)
+    }
+
+    pub fn scroll_delta(&self) -> (i32, i32) {
+        self.scroll
+    }
+
+    pub fn pressed(&self) -> bool {
+        self.state.pressed()
+    }
+
+    pub fn just_pressed(&self) -> bool {
+        self.state.just_pressed()
+    }
+
+    pub fn released(&self) -> bool {
+        self.state.released()
+    }
+
+    pub fn just_released(&self) -> bool {
+        self.state.just_released()
+    }
+}
+
+// This frame's pointer, live or replayed
+pub fn pointer() -> Pointer {
+    let input = Replay::input();
+    Pointer {
+        x: input.pointer.0,
+        y: input.pointer.1,
+        // Only the low bits, a hand edited recording must not panic the conversion
+        state: Button::from(input.pointer_state & 3),
+        scroll: input.scroll,
+    }
+}
+
+// This frame's gamepad, live or replayed
+pub fn gamepad(_player: u32) -> Gamepad<Button> {
+    let [up, down, left, right, a, b, x, y, start, select] = Replay::input().gamepad.map(|button| Button::from(button & 3));
+    Gamepad { up, down, left, right, a, b, x, y, start, select }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn recording() -> Recording {
+        let frame = |x| FrameInput { pointer: (x, 7), pointer_state: 1, scroll: (0, -1), gamepad: [0, 0, 0, 0, 2, 0, 0, 0, 0, 0] };
+        Recording { start: vec![1, 2, 3], frames: vec![frame(5), frame(6)] }
+    }
+
+    #[test]
+    fn recording_round_trips() {
+        let recording = recording();
+        assert_eq!(Recording::decode(&recording.encode()), Ok(recording.clone()));
+        assert_eq!(Recording::import(&recording.export()), Ok(recording));
+    }
+
+    #[test]
+    fn decode_rejects_foreign_and_stale_recordings() {
+        let data = recording().encode();
+
+        let mut magic = data.clone();
+        magic[..4].copy_from_slice(&SAVE_MAGIC);
+        assert!(matches!(Recording::decode(&magic), Err(SaveError::Corrupt(_))));
+
+        let mut older = data.clone();
+        older[4..6].copy_from_slice(&(SAVE_VERSION - 1).to_le_bytes());
+        assert!(matches!(Recording::decode(&older), Err(SaveError::Corrupt(reason)) if reason.contains("save version")));
+        let mut newer = data.clone();
+        newer[4..6].copy_from_slice(&(SAVE_VERSION + 1).to_le_bytes());
+        assert_eq!(Recording::decode(&newer), Err(SaveError::TooNew(SAVE_VERSION + 1)));
+
+        let mut tampered = data.clone();
+        tampered[HEADER_LEN] ^= 0xff;
+        assert!(matches!(Recording::decode(&tampered), Err(SaveError::Checksum { .. })));
+        assert_eq!(Recording::decode(&data[..HEADER_LEN - 1]), Err(SaveError::Truncated));
+    }
+
+    // The last recorded frame still plays, the one after it is live again
+    #[test]
+    fn replay_hands_back_to_live_input() {
+        let recording = recording();
+        Replay::play(recording.clone());
+        for frame in recording.frames.iter() {
+            assert!(!Replay::begin_frame());
+            assert_eq!(Replay::input(), *frame);
+            assert!(Replay::is_replaying());
+        }
+        assert!(Replay::begin_frame());
+        assert!(!Replay::is_replaying());
+        assert_eq!(Replay::input(), FrameInput::live());
+        assert!(!Replay::begin_frame());
+    }
+}
@@ -0,0 +1,261 @@
+use super::*;
+
+// Projects a station can have waiting, the one underway included
+pub const QUEUE_LIMIT: usize = 4;
+
+// An upgrade paid for and waiting to apply, stations work through theirs in order
+#[derive(Debug, Clone, PartialEq, BorshDeserialize, BorshSerialize)]
+pub struct Project {
+    pub station: StationId,
+    // Index into the station's upgrade list
+    pub id: usize,
+    // Ticks put in so far and needed in total, research complex drones each put in one a tick
+    pub work: u64,
+    pub required: u64,
+    // What was paid when it was queued, given back if it can no longer apply
+    pub cost: Vec<(Resources, BigNum)>,
+}
+
+impl Project {
+    fn upgrade(&self) -> Option<&'static Upgrade> {
+        station_upgrades(self.station).get(self.id)
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.upgrade().map_or("", |upgrade| upgrade.name.as_str())
+    }
+
+    pub fn progress(&self) -> f32 {
+        self.work as f32 / self.required.max(1) as f32
+    }
+
+    // Work put in per tick, techs need drones at the complex
+    fn rate(&self, drones: usize) -> u64 {
+        match self.upgrade().and_then(Research::duration) {
+            Some((_, true)) => drones as u64,
+            _ => 1,
+        }
+    }
+}
+
+// Why a purchase was turned down
+#[derive(Debug, Clone, PartialEq, BorshDeserialize, BorshSerialize)]
+pub enum Rejection {
+    Shortfall(Shortfall),
+    // The project is already in the station's queue
+    Queued(StationId, String),
+    // The station has QUEUE_LIMIT projects waiting
+    QueueFull(StationId),
+}
+
+impl From<Shortfall> for Rejection {
+    fn from(shortfall: Shortfall) -> Self {
+        Rejection::Shortfall(shortfall)
+    }
+}
+
+impl std::fmt::Display for Rejection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Rejection::Shortfall(shortfall) => write!(f, "{}", shortfall),
+            Rejection::Queued(station, name) => write!(f, "{} already queued at {}", name, station.label()),
+            Rejection::QueueFull(station) => write!(f, "{} queue is full", station.label()),
+        }
+    }
+}
+
+// Projects of a run, started fresh after every jump
+#[derive(Debug, Clone, Default, PartialEq, BorshDeserialize, BorshSerialize)]
+pub struct Research {
+    // Finished techs, by index into COMPLEX_UPGRADES
+    pub completed: Vec<usize>,
+    // Every station's projects, each station's in the order they were bought
+    pub queue: Vec<Project>,
+}
+
+impl Research {
+    pub fn new() -> Self {
+        Research::default()
+    }
+
+    // Seconds the upgrade takes and whether drones at the complex do the work, None for upgrades that apply at once
+    pub fn duration(upgrade: &Upgrade) -> Option<(u32, bool)> {
+        upgrade.effects.iter().find_map(|effect| match effect {
+            UpgradeEffect::Research(secs) => Some((*secs, true)),
+            UpgradeEffect::Project(secs) => Some((*secs, false)),
+            _ => None,
+        })
+    }
+
+    // A project can be bought once at a time, into a queue with room
+    pub fn check(&self, station: StationId, upgrade: &Upgrade) -> Result<(), Rejection> {
+        if Research::duration(upgrade).is_none() {
+            return Ok(());
+        }
+        let queued = self.queue.iter().filter(|project| project.station == station);
+        if queued.clone().any(|project| project.id == upgrade.id) {
+            return Err(Rejection::Queued(station, upgrade.name.clone()));
+        }
+        if queued.count() >= QUEUE_LIMIT {
+            return Err(Rejection::QueueFull(station));
+        }
+        Ok(())
+    }
+
+    pub fn accepts(&self, station: StationId, upgrade: &Upgrade) -> bool {
+        self.check(station, upgrade).is_ok()
+    }
+
+    pub fn enqueue(&mut self, station: StationId, upgrade: &Upgrade) {
+        let Some((secs, _)) = Research::duration(upgrade) else {
+            return;
+        };
+        self.queue.push(Project { station, id: upgrade.id, work: 0, required: secs as u64 * 60, cost: upgrade.cost.clone() });
+    }
+
+    pub fn projects(&self, station: StationId) -> Vec<Project> {
+        self.queue.iter().filter(|project| project.station == station).cloned().collect()
+    }
+
+    // Work each station's first project for the given ticks, leftover time going to the next,
+    // returns the projects finished in the order ticking along would have finished them
+    pub fn advance(&mut self, drones: usize, ticks: u64) -> Vec<Project> {
+        let total = ticks;
+        let mut finished = vec![];
+        for station in StationId::ALL {
+            let mut ticks = ticks;
+            while let Some(index) = self.queue.iter().position(|project| project.station == station) {
+                let project = &mut self.queue[index];
+                let rate = project.rate(drones);
+                if rate == 0 {
+                    break;
+                }
+                let needed = (project.required - project.work.min(project.required)).div_ceil(rate);
+                if ticks < needed {
+                    project.work += ticks * rate;
+                    break;
+                }
+                ticks -= needed;
+                let mut project = self.queue.remove(index);
+                project.work = project.required;
+                if project.station == StationId::ResearchComplex {
+                    self.completed.push(project.id);
+                }
+                finished.push((total - ticks, project));
+            }
+        }
+        // Stable, so projects finishing on the same tick keep the StationId::ALL order
+        finished.sort_by_key(|(at, _)| *at);
+        finished.into_iter().map(|(_, project)| project).collect()
+    }
+
+    // Keep the buy buttons of projects that cannot be queued greyed out and drop their clicks
+    pub fn gate(&self, station: StationId, upgrades: &mut [Upgrade], order: Option<Order>) -> Option<Order> {
+        for upgrade in upgrades.iter_mut() {
+            upgrade.buy_button.interactable &= self.accepts(station, upgrade);
+        }
+        order.filter(|order| !matches!(order, Order::Upgrade(i) if !upgrades.get(*i).is_some_and(|upgrade| self.accepts(station, upgrade))))
+    }
+
+    // Added to the production multiplier of a resource by finished techs
+    pub fn boost(&self, resource: &Resources) -> f64 {
+        self.completed.iter().filter_map(|id| COMPLEX_UPGRADES.get(*id)).flat_map(|upgrade| upgrade.effects.iter()).map(|effect| match effect {
+            UpgradeEffect::Boost(boosted, amount) if boosted == resource => *amount as f64,
+            _ => 0.,
+        }).sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn complex(id: usize) -> &'static Upgrade {
+        &station_upgrades(StationId::ResearchComplex)[id]
+    }
+
+    // CONSTRUCT and the four techs are projects, DEPLOY RESEARCH DRONE applies at once
+    #[test]
+    fn accepts_each_project_once_into_a_queue_with_room() {
+        let mut research = Research::new();
+        let station = StationId::ResearchComplex;
+        for _ in 0..QUEUE_LIMIT + 1 {
+            research.enqueue(station, complex(1));
+        }
+        assert!(research.queue.is_empty());
+        assert_eq!(research.check(station, complex(1)), Ok(()));
+
+        research.enqueue(station, complex(0));
+        assert_eq!(research.queue[0].required, 60 * 60);
+        assert_eq!(research.queue[0].cost, complex(0).cost);
+        assert_eq!(research.check(station, complex(0)), Err(Rejection::Queued(station, "CONSTRUCT".to_string())));
+        for id in 2..QUEUE_LIMIT + 1 {
+            assert!(research.accepts(station, complex(id)));
+            research.enqueue(station, complex(id));
+        }
+        assert_eq!(research.check(station, complex(QUEUE_LIMIT + 1)), Err(Rejection::QueueFull(station)));
+        // Other stations keep their own queues
+        assert!(research.accepts(StationId::Jumpgate, &station_upgrades(StationId::Jumpgate)[0]));
+        assert!(research.accepts(station, complex(1)));
+    }
+
+    // Stations work through their queues side by side, each one project at a time
+    #[test]
+    fn advances_in_order() {
+        let mut research = Research::new();
+        research.enqueue(StationId::ResearchComplex, complex(0));
+        research.enqueue(StationId::ResearchComplex, complex(2));
+        research.enqueue(StationId::Jumpgate, &station_upgrades(StationId::Jumpgate)[0]);
+
+        assert!(research.advance(2, 3_000).is_empty());
+        let work: Vec<u64> = research.queue.iter().map(|project| project.work).collect();
+        assert_eq!(work, vec![3_000, 0, 3_000]);
+
+        // CONSTRUCT needs 600 more ticks, the rest goes to the tech at two drones' pace
+        let finished = research.advance(2, 1_000);
+        assert_eq!(finished.iter().map(|project| (project.station, project.id)).collect::<Vec<_>>(), vec![(StationId::ResearchComplex, 0)]);
+        assert_eq!(research.projects(StationId::ResearchComplex)[0].work, 800);
+        assert_eq!(research.projects(StationId::Jumpgate)[0].work, 4_000);
+
+        // Techs stall without drones, projects do not
+        research.advance(0, 1_000);
+        assert_eq!(research.projects(StationId::ResearchComplex)[0].work, 800);
+        assert_eq!(research.projects(StationId::Jumpgate)[0].work, 5_000);
+        assert!(!research.completed.contains(&2));
+    }
+
+    // Catching up in one call ends where ticking along would have
+    #[test]
+    fn offline_advance_matches_ticking() {
+        let fill = || {
+            let mut research = Research::new();
+            for id in [0, 2, 3, 4] {
+                research.enqueue(StationId::ResearchComplex, complex(id));
+            }
+            research.enqueue(StationId::Jumpgate, &station_upgrades(StationId::Jumpgate)[0]);
+            research
+        };
+        // An hour, through CONSTRUCT, the gate and two techs and into the third
+        let ticks = 60 * 60 * 60;
+        let (mut ticking, mut away) = (fill(), fill());
+        let mut finished = vec![];
+        for _ in 0..ticks {
+            finished.extend(ticking.advance(3, 1));
+        }
+        assert_eq!(away.advance(3, ticks), finished);
+        assert_eq!(away, ticking);
+        let order: Vec<(StationId, usize)> = finished.iter().map(|project| (project.station, project.id)).collect();
+        assert_eq!(order, vec![(StationId::ResearchComplex, 0), (StationId::Jumpgate, 0), (StationId::ResearchComplex, 2), (StationId::ResearchComplex, 3)]);
+        assert!(away.completed.contains(&2));
+        assert_eq!(away.projects(StationId::ResearchComplex).len(), 1);
+
+        // And the simulation applies what finished while away
+        let mut sim = Simulation::new(0, Sector::home(), Player::default());
+        sim.handle_event(&Event::ComplexUnlockable);
+        sim.purchase(StationId::ResearchComplex, 0).unwrap();
+        let mut report = AwayReport::new(120, 120);
+        sim.idle(120., &mut report);
+        assert_eq!(report.completed, vec!["CONSTRUCT".to_string()]);
+        assert!(sim.research_complex.unlocked);
+    }
+}
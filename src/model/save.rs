@@ -0,0 +1,835 @@
+use super::*;
+use once_cell::sync::Lazy;
+use std::sync::Mutex;
+use turbo::os::encoding;
+
+// Every save written since versioning starts with this
+pub const SAVE_MAGIC: [u8; 4] = *b"TINC";
+// Layout version of the save file, bump it and add a migration when a SaveData field changes
+pub const SAVE_VERSION: u16 = 1;
+// Magic, version and checksum
+const HEADER_LEN: usize = 4 + 2 + 4;
+// Start of an exported save string, followed by the encoded save in base64
+pub const EXPORT_PREFIX: &str = "TINC-";
+
+// MIGRATIONS[n] upgrades a version n state to version n + 1
+type Migration = fn(Vec<u8>) -> Result<Vec<u8>, String>;
+const MIGRATIONS: [Migration; SAVE_VERSION as usize] = [migrate_v0];
+
+// Saves from before the envelope are bare GameState bytes in the first release's layout, with u64
+// amounts, drone levels on each station and no offline settings
+fn migrate_v0(state: Vec<u8>) -> Result<Vec<u8>, String> {
+    let game = GameStateV0::try_from_slice(&state).map_err(|err| err.to_string())?;
+    // The run had no seed, take one from its own bytes
+    let rng = GameRng::new(crc32(&state) as u64);
+    game.into_save(rng).try_to_vec().map_err(|err| err.to_string())
+}
+
+// The layouts below are frozen as they were written, old saves must keep decoding whatever happens to
+// the live types. Most fields are only read past to reach the progression. Bounds and Tween belong to
+// turbo, BigNum never changed
+
+// Unchanged since the first release
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, BorshDeserialize, BorshSerialize)]
+enum ResourcesV0 {
+    Research,
+    Drones,
+    Metals,
+    Power,
+    Prestige,
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+enum EventV0 {
+    StartGame,
+    SaveGame,
+    ResetGame,
+    DroneDepotUnlockable,
+    UnlockDroneDepot,
+    MinesUnlockable,
+    PowerPlantUnlockable,
+    UnlockPowerPlant,
+    LateGame,
+    Prestige,
+    EndGame,
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+enum CostFormulaV0 {
+    None,
+    Double,
+    Exponential,
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+enum DroneModeV0 {
+    Survey,
+    Mining,
+    Shipping,
+    Conduit,
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+enum BtnStateV0 {
+    Disabled,
+    Normal,
+    Hovered,
+    Pressed,
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+struct BtnV0 {
+    bounds: Bounds,
+    state: BtnStateV0,
+    string: String,
+    text: bool,
+    interactable: bool,
+    clickable: bool,
+    colors_index: u32,
+    fixed: bool,
+    font: String,
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+struct WrapBoxV0 {
+    bounds: Bounds,
+    lines: Vec<String>,
+    colors_index: u32,
+    fixed: bool,
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+struct CameraCtrlV0 {
+    zoom_tick: usize,
+    dragging: bool,
+    last_pointer_pos: (f32, f32),
+    pos: (f32, f32),
+    velocity: (f32, f32),
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+struct ScanV0 {
+    origin: (f32, f32),
+    pos: (f32, f32),
+    radius: f32,
+    lifetime: f32,
+    key: String,
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+struct CircleV0 {
+    pos: (f32, f32),
+    size: u32,
+    color: u32,
+    angle: f32,
+    speed: f32,
+    wobble_phase: f32,
+    wobble_amplitude: f32,
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+struct CloudV0 {
+    center: (i32, i32),
+    radius: u32,
+    size_range: (u32, u32),
+    rings: Vec<Vec<CircleV0>>,
+    fade_ranges: Vec<(f32, f32)>,
+    fade: bool,
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+struct VignetteV0 {
+    fade: bool,
+    fade_prog: f32,
+    stage: u32,
+    depot: Vec<CloudV0>,
+    mines: Vec<CloudV0>,
+    clouds: Vec<CloudV0>,
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+struct DebrisV0 {
+    pos: (f32, f32),
+    angle: f32,
+    speed: f32,
+    size: f32,
+    lifetime: u32,
+    timer: u32,
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+struct AsteroidV0 {
+    pos: (f32, f32),
+    angle: f32,
+    speed: f32,
+    radius: f32,
+    size: f32,
+    id: u32,
+    drilling: bool,
+    debris: Vec<DebrisV0>,
+    sprite: u32,
+    rot: u32,
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+struct AsteroidFieldV0 {
+    asteroids: Vec<Vec<AsteroidV0>>,
+    limit: usize,
+    spawn_interval: u32,
+    timer: u32,
+    belt_index: usize,
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+struct SegmentV0 {
+    start: (f32, f32),
+    end: (f32, f32),
+    thickness: f32,
+    direction: (f32, f32),
+    color: u32,
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+struct BoltV0 {
+    segments: Vec<SegmentV0>,
+    age: f32,
+    lifespan: f32,
+    dir: f32,
+    center: (f32, f32),
+    angle_speed: f32,
+    draw: bool,
+    draw_segments: Vec<SegmentV0>,
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+struct NebulousV0 {
+    center: (f32, f32),
+    counter: u8,
+    k: i32,
+    flow_array: Vec<(f32, f32, u32)>,
+    start_col: f32,
+    rez1: f32,
+    rez2: f32,
+    gap: f32,
+    len: f32,
+    start_vary: f32,
+    radius_max: f32,
+    radius_min: f32,
+    segments: Vec<SegmentV0>,
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+struct NebulaStormV0 {
+    bolts: Vec<BoltV0>,
+    spawn_timer: f32,
+    field: NebulousV0,
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+struct DialogueBoxV0 {
+    panel: Bounds,
+    typed_message: String,
+    message: String,
+    tween: (Option<Tween<i32>>, Option<Tween<i32>>),
+    prompt: bool,
+    confirm: BtnV0,
+    cancel: BtnV0,
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+struct DialogueV0 {
+    messages: Vec<String>,
+    camera_pos: Vec<((i32, i32), i32)>,
+    event_broadcast: i32,
+    d_box: DialogueBoxV0,
+    prompt: bool,
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+struct EventManagerV0 {
+    events: Vec<EventV0>,
+    dialogue: Option<DialogueV0>,
+    over: bool,
+}
+
+// The first release's layouts of what version 1 changed
+#[allow(dead_code)]
+#[derive(BorshDeserialize)]
+struct CollectionV0 {
+    is_active: bool,
+    created_at: usize,
+    pos: (f32, f32),
+    value: (ResourcesV0, u64),
+    positive: bool,
+}
+
+#[allow(dead_code)]
+#[derive(BorshDeserialize)]
+struct DroneV0 {
+    pos: (f32, f32),
+    target_pos: (f32, f32),
+    front: bool,
+    interval: f32,
+    mode: DroneModeV0,
+    timer: f32,
+    phase: f32,
+    angle: f32,
+    level: u32,
+    speed: u32,
+    scan: Option<ScanV0>,
+    asteroid_id: u32,
+    cargo: Vec<(ResourcesV0, u64)>,
+    on_site: bool,
+    wander_progress: f32,
+    wander_forward: bool,
+}
+
+#[allow(dead_code)]
+#[derive(BorshDeserialize)]
+struct PopUpV0 {
+    hitbox: Bounds,
+    panel: Bounds,
+    drone_hitbox: Bounds,
+    drone_panel: Bounds,
+    hovered: bool,
+    drone_inspect: BtnV0,
+    unassaign: BtnV0,
+    inspecting: bool,
+    title: String,
+    drones: u32,
+    drone_resource: ResourcesV0,
+    fab: bool,
+}
+
+#[allow(dead_code)]
+#[derive(BorshDeserialize)]
+struct UpgradeV0 {
+    name: String,
+    description: String,
+    cost: Vec<(ResourcesV0, u64)>,
+    unlocks: Vec<usize>,
+    level: u32,
+    max_level: u32,
+    entry: BtnV0,
+    buy_button: BtnV0,
+    tooltip: WrapBoxV0,
+    hovered: bool,
+    display_lvl: bool,
+    base_cost: Vec<(ResourcesV0, u64)>,
+    cost_formula: CostFormulaV0,
+}
+
+#[allow(dead_code)]
+#[derive(BorshDeserialize)]
+struct StationV0 {
+    drone_base: f32,
+    drone_eff: f32,
+    drone_speed: f32,
+}
+
+#[allow(dead_code)]
+#[derive(BorshDeserialize)]
+struct PlayerV0 {
+    resources: Vec<(ResourcesV0, u64)>,
+    hitbox: Bounds,
+    target_pos: (f32, f32),
+    dir: f32,
+    camera: CameraCtrlV0,
+    scans: Vec<ScanV0>,
+    prestiged: bool,
+    jumping: bool,
+    jump_timer: u32,
+    gate_aligned: bool,
+    prestige_prog: u64,
+    prestige_index: u32,
+    prestige_limit: u64,
+    prestige_earned: u64,
+    pop_up: PopUpV0,
+    hovered: bool,
+    hovered_else: bool,
+    avail_upgrades: Vec<UpgradeV0>,
+}
+
+#[allow(dead_code)]
+#[derive(BorshDeserialize)]
+struct ExoplanetV0 {
+    drones: Vec<DroneV0>,
+    scanner_level: u32,
+    collecting: bool,
+    station: StationV0,
+    hitbox: Bounds,
+    pop_up: PopUpV0,
+    hovered: bool,
+    clicked_at: usize,
+    collections: Vec<CollectionV0>,
+    scans: Vec<ScanV0>,
+    collect_interval: usize,
+    assigned: bool,
+    avail_upgrades: Vec<UpgradeV0>,
+}
+
+#[allow(dead_code)]
+#[derive(BorshDeserialize)]
+struct DroneDepotV0 {
+    drones: Vec<DroneV0>,
+    station: StationV0,
+    drone_level: u32,
+    drone_speed: u32,
+    unlockable: bool,
+    unlocked: bool,
+    hitbox: Bounds,
+    pop_up: PopUpV0,
+    hovered: bool,
+    fabricator_unlocked: bool,
+    power_plant_unlocked: bool,
+    fabricator: PopUpV0,
+    fab_prog: u64,
+    fab_level: u32,
+    fab_limit: u64,
+    clicked_at: usize,
+    collections: Vec<CollectionV0>,
+    collect_interval: usize,
+    avail_upgrades: Vec<UpgradeV0>,
+    fab_upgrades: Vec<UpgradeV0>,
+}
+
+#[allow(dead_code)]
+#[derive(BorshDeserialize)]
+struct AsteroidMinesV0 {
+    drones: Vec<DroneV0>,
+    station: StationV0,
+    drone_level: u32,
+    drone_speed: u32,
+    unlockable: bool,
+    unlocked: bool,
+    hitbox: Bounds,
+    pop_up: PopUpV0,
+    hovered: bool,
+    clicked_at: usize,
+    collections: Vec<CollectionV0>,
+    collect_interval: usize,
+    avail_upgrades: Vec<UpgradeV0>,
+}
+
+#[allow(dead_code)]
+#[derive(BorshDeserialize)]
+struct PowerPlantV0 {
+    drones: Vec<DroneV0>,
+    station: StationV0,
+    drone_level: u32,
+    drone_speed: u32,
+    unlockable: bool,
+    unlocked: bool,
+    hitbox: Bounds,
+    pop_up: PopUpV0,
+    hovered: bool,
+    clicked_at: usize,
+    collections: Vec<CollectionV0>,
+    collect_interval: usize,
+    avail_upgrades: Vec<UpgradeV0>,
+}
+
+#[allow(dead_code)]
+#[derive(BorshDeserialize)]
+struct JumpgateV0 {
+    drones: Vec<DroneV0>,
+    station: StationV0,
+    drone_level: u32,
+    drone_speed: u32,
+    hitbox: Bounds,
+    pop_up: PopUpV0,
+    hovered: bool,
+    clicked_at: usize,
+    collections: Vec<CollectionV0>,
+    collect_interval: usize,
+    earn: u64,
+    limit: u64,
+    prog: u64,
+    unlockable: bool,
+    unlocked: bool,
+    avail_upgrades: Vec<UpgradeV0>,
+}
+
+#[allow(dead_code)]
+#[derive(BorshDeserialize)]
+struct ResearchComplexV0 {
+    drones: Vec<DroneV0>,
+    station: StationV0,
+    drone_level: u32,
+    drone_speed: u32,
+    hitbox: Bounds,
+    pop_up: PopUpV0,
+    hovered: bool,
+    clicked_at: usize,
+    collections: Vec<CollectionV0>,
+    collect_interval: usize,
+    unlockable: bool,
+    unlocked: bool,
+    avail_upgrades: Vec<UpgradeV0>,
+}
+
+// GameState as the first release saved it
+#[allow(dead_code)]
+#[derive(BorshDeserialize)]
+struct GameStateV0 {
+    player: PlayerV0,
+    vignette: VignetteV0,
+    event_manager: EventManagerV0,
+    exoplanet: ExoplanetV0,
+    drone_depot: DroneDepotV0,
+    asteroid_field: AsteroidFieldV0,
+    asteroid_mines: AsteroidMinesV0,
+    nebula_storm: NebulaStormV0,
+    power_plant: PowerPlantV0,
+    jumpgate: JumpgateV0,
+    research_complex: ResearchComplexV0,
+}
+
+impl From<ResourcesV0> for Resources {
+    fn from(resource: ResourcesV0) -> Self {
+        match resource {
+            ResourcesV0::Research => Resources::Research,
+            ResourcesV0::Drones => Resources::Drones,
+            ResourcesV0::Metals => Resources::Metals,
+            ResourcesV0::Power => Resources::Power,
+            ResourcesV0::Prestige => Resources::Prestige,
+        }
+    }
+}
+
+impl From<EventV0> for Event {
+    fn from(event: EventV0) -> Self {
+        match event {
+            EventV0::StartGame => Event::StartGame,
+            EventV0::SaveGame => Event::SaveGame,
+            EventV0::ResetGame => Event::ResetGame,
+            EventV0::DroneDepotUnlockable => Event::DroneDepotUnlockable,
+            EventV0::UnlockDroneDepot => Event::UnlockDroneDepot,
+            EventV0::MinesUnlockable => Event::MinesUnlockable,
+            EventV0::PowerPlantUnlockable => Event::PowerPlantUnlockable,
+            EventV0::UnlockPowerPlant => Event::UnlockPowerPlant,
+            EventV0::LateGame => Event::LateGame,
+            EventV0::Prestige => Event::Prestige,
+            EventV0::EndGame => Event::EndGame,
+        }
+    }
+}
+
+// Upgrades by their index in the station's list, ones no longer in the list are dropped
+fn upgrade_saves(upgrades: &[UpgradeV0], station: StationId) -> Vec<UpgradeSave> {
+    let list = station_upgrades(station);
+    upgrades.iter().filter_map(|upgrade| {
+        let id = list.iter().position(|listed| listed.name == upgrade.name)?;
+        Some(UpgradeSave { id, level: upgrade.level })
+    }).collect()
+}
+
+impl StationV0 {
+    // Drone levels moved from the stations into Station
+    fn with_levels(self, level: u32, speed: u32) -> Station {
+        Station { drone_base: self.drone_base, drone_eff: self.drone_eff, drone_speed: self.drone_speed, level, speed }
+    }
+}
+
+// Drones are kept as a count and respawned on restore
+fn station_save(station: Station, drones: &[DroneV0], unlockable: bool, unlocked: bool, upgrades: &[UpgradeV0], id: StationId) -> StationSave {
+    StationSave { station, drones: drones.len() as u32, unlockable, unlocked, upgrades: upgrade_saves(upgrades, id), detail: StationDetail::None }
+}
+
+impl GameStateV0 {
+    // The progression of a first release run, everything added since starts out fresh
+    fn into_save(self, rng: GameRng) -> SaveData {
+        let player = self.player;
+        // Amounts were u64, what was held counts as earned
+        let resources: Vec<(Resources, BigNum)> = player.resources.into_iter()
+            .map(|(resource, amount)| (Resources::from(resource), BigNum::from(amount)))
+            .collect();
+        let exoplanet = self.exoplanet;
+        let depot = self.drone_depot;
+        let mines = self.asteroid_mines;
+        let power = self.power_plant;
+        let gate = self.jumpgate;
+        let complex = self.research_complex;
+        SaveData {
+            // Saved before offline progress existed, catch-up starts from the next save
+            saved_at: 0,
+            offline: OfflineProgress::new(),
+            tick: 0,
+            events: self.event_manager.events.into_iter().map(Event::from).collect(),
+            player: PlayerSave {
+                resources: Wallet::from(resources).persistent(),
+                prestiged: player.prestiged,
+                prestige_prog: BigNum::from(player.prestige_prog),
+                prestige_index: player.prestige_index,
+                prestige_earned: BigNum::from(player.prestige_earned),
+                upgrades: upgrade_saves(&player.avail_upgrades, StationId::Probe),
+            },
+            stations: vec![
+                (StationId::Exoplanet, StationSave {
+                    detail: StationDetail::Exoplanet { scanner_level: exoplanet.scanner_level, assigned: exoplanet.assigned },
+                    ..station_save(exoplanet.station.with_levels(0, 0), &exoplanet.drones, true, true, &exoplanet.avail_upgrades, StationId::Exoplanet)
+                }),
+                (StationId::DroneDepot, StationSave {
+                    detail: StationDetail::Depot {
+                        fabricator_unlocked: depot.fabricator_unlocked,
+                        power_plant_unlocked: depot.power_plant_unlocked,
+                        fab_level: depot.fab_level,
+                        fab_prog: BigNum::from(depot.fab_prog),
+                        fab_upgrades: upgrade_saves(&depot.fab_upgrades, StationId::Fabricator),
+                    },
+                    ..station_save(depot.station.with_levels(depot.drone_level, depot.drone_speed), &depot.drones, depot.unlockable, depot.unlocked, &depot.avail_upgrades, StationId::DroneDepot)
+                }),
+                (StationId::AsteroidMines, station_save(mines.station.with_levels(mines.drone_level, mines.drone_speed), &mines.drones, mines.unlockable, mines.unlocked, &mines.avail_upgrades, StationId::AsteroidMines)),
+                (StationId::PowerPlant, station_save(power.station.with_levels(power.drone_level, power.drone_speed), &power.drones, power.unlockable, power.unlocked, &power.avail_upgrades, StationId::PowerPlant)),
+                (StationId::Jumpgate, station_save(gate.station.with_levels(gate.drone_level, gate.drone_speed), &gate.drones, gate.unlockable, gate.unlocked, &gate.avail_upgrades, StationId::Jumpgate)),
+                (StationId::ResearchComplex, station_save(complex.station.with_levels(complex.drone_level, complex.drone_speed), &complex.drones, complex.unlockable, complex.unlocked, &complex.avail_upgrades, StationId::ResearchComplex)),
+            ],
+            rng,
+            auto_buyer: AutoBuyer::new(),
+            achievements: Achievements::new(),
+            stats: Stats::new(),
+            research: Research::new(),
+            packs: vec![],
+            sector: Sector::home(),
+        }
+    }
+}
+
+// The last save that could not be read, written back with every save until replaced
+static BACKUP: Lazy<Mutex<Option<Vec<u8>>>> = Lazy::new(|| Mutex::new(None));
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SaveError {
+    Empty,
+    Truncated,
+    Checksum { expected: u32, found: u32 },
+    // Written by a newer build
+    TooNew(u16),
+    Migration { from: u16, reason: String },
+    Corrupt(String),
+    // Text that is not an exported save
+    NotExport,
+    EmptySlot(usize),
+    SlotsFull,
+    NoAutosave(usize),
+}
+
+impl std::fmt::Display for SaveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SaveError::Empty => write!(f, "no save"),
+            SaveError::Truncated => write!(f, "save is truncated"),
+            SaveError::Checksum { expected, found } => write!(f, "checksum mismatch, expected {:08x} found {:08x}", expected, found),
+            SaveError::TooNew(version) => write!(f, "save version {} is newer than {}", version, SAVE_VERSION),
+            SaveError::Migration { from, reason } => write!(f, "migrating from version {}: {}", from, reason),
+            SaveError::Corrupt(reason) => write!(f, "corrupt save: {}", reason),
+            SaveError::NotExport => write!(f, "not a save string"),
+            SaveError::EmptySlot(index) => write!(f, "slot {} is empty", SLOT_NAMES.get(*index).unwrap_or(&"?")),
+            SaveError::SlotsFull => write!(f, "no empty slot"),
+            SaveError::NoAutosave(index) => write!(f, "no autosave {}", index + 1),
+        }
+    }
+}
+
+// What goes into local storage, magic, version and checksum are written ahead of it
+#[derive(Debug, Clone, Default, PartialEq, BorshDeserialize, BorshSerialize)]
+pub struct SaveFile {
+    // Borsh SaveData at SAVE_VERSION
+    pub state: Vec<u8>,
+    pub backup: Option<Vec<u8>>,
+    pub slots: SaveSlots,
+    // Newest first
+    pub autosaves: Vec<SaveSlot>,
+}
+
+impl SaveFile {
+    pub fn new(state: Vec<u8>) -> Self {
+        SaveFile {
+            state,
+            backup: BACKUP.lock().unwrap().clone(),
+            slots: SaveSlots::current(),
+            autosaves: Autosaves::list(),
+        }
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        let body = self.try_to_vec().unwrap_or_default();
+        let mut data = Vec::with_capacity(HEADER_LEN + body.len());
+        data.extend_from_slice(&SAVE_MAGIC);
+        data.extend_from_slice(&SAVE_VERSION.to_le_bytes());
+        data.extend_from_slice(&crc32(&body).to_le_bytes());
+        data.extend_from_slice(&body);
+        data
+    }
+
+    // Verify and unpack a save, migrating its state up to SAVE_VERSION
+    pub fn decode(data: &[u8]) -> Result<SaveFile, SaveError> {
+        if data.is_empty() {
+            return Err(SaveError::Empty);
+        }
+        if data.starts_with(EXPORT_PREFIX.as_bytes()) {
+            return SaveFile::import(&String::from_utf8_lossy(data));
+        }
+        if !data.starts_with(&SAVE_MAGIC) {
+            return SaveFile::migrate(0, SaveFile { state: data.to_vec(), ..SaveFile::default() });
+        }
+        if data.len() < HEADER_LEN {
+            return Err(SaveError::Truncated);
+        }
+        let version = u16::from_le_bytes([data[4], data[5]]);
+        let expected = u32::from_le_bytes([data[6], data[7], data[8], data[9]]);
+        let body = &data[HEADER_LEN..];
+        let found = crc32(body);
+        if found != expected {
+            return Err(SaveError::Checksum { expected, found });
+        }
+        let file = SaveFile::try_from_slice(body).map_err(|err| SaveError::Corrupt(err.to_string()))?;
+        SaveFile::migrate(version, file)
+    }
+
+    fn migrate(version: u16, mut file: SaveFile) -> Result<SaveFile, SaveError> {
+        if version > SAVE_VERSION {
+            return Err(SaveError::TooNew(version));
+        }
+        for from in version..SAVE_VERSION {
+            let migrate = MIGRATIONS[from as usize];
+            file.state = migrate(file.state).map_err(|reason| SaveError::Migration { from, reason })?;
+            for slot in file.slots.slots.iter_mut().flatten().chain(file.autosaves.iter_mut()) {
+                slot.state = migrate(std::mem::take(&mut slot.state)).map_err(|reason| SaveError::Migration { from, reason })?;
+            }
+        }
+        Ok(file)
+    }
+
+    // Copyable text form of the running game, the checksum travels inside the base64
+    pub fn export(&self) -> String {
+        let file = SaveFile { state: self.state.clone(), ..SaveFile::default() };
+        format!("{}{}", EXPORT_PREFIX, encoding::encode_base64(file.encode()))
+    }
+
+    // Read an exported save string, whitespace from copying and pasting is ignored
+    pub fn import(text: &str) -> Result<SaveFile, SaveError> {
+        let text: String = text.split_whitespace().collect();
+        let body = text.strip_prefix(EXPORT_PREFIX).ok_or(SaveError::NotExport)?;
+        let data = encoding::decode_base64(body).map_err(|err| SaveError::Corrupt(err.to_string()))?;
+        // Exports always carry the header, never fall back to reading bare legacy bytes
+        if !data.starts_with(&SAVE_MAGIC) {
+            return Err(SaveError::Corrupt("missing header".to_string()));
+        }
+        SaveFile::decode(&data)
+    }
+
+    // Hold on to an unreadable save so the next write keeps it
+    pub fn keep_backup(data: Vec<u8>) {
+        *BACKUP.lock().unwrap() = Some(data);
+    }
+
+    pub fn backup() -> Option<Vec<u8>> {
+        BACKUP.lock().unwrap().clone()
+    }
+}
+
+// CRC-32 (IEEE), enough to catch truncated or mangled storage
+pub fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xffff_ffffu32;
+    for byte in data {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xedb8_8320 & mask);
+        }
+    }
+    !crc
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Written by the first release, bare GameState bytes in base64. Kept apart from .local-storage,
+    // which the game overwrites whenever it runs
+    const BASELINE_SAVE: &str = include_str!("../../tests/fixtures/baseline_save.txt");
+
+    #[test]
+    fn baseline_save_loads() {
+        let bytes = encoding::decode_base64(BASELINE_SAVE.trim()).unwrap();
+        let file = SaveFile::decode(&bytes).unwrap();
+        let data = SaveData::read(&file).unwrap();
+        let wallet = &data.player.resources;
+        assert_eq!(wallet.amount(&Resources::Research), BigNum::from(3999999275u64));
+        assert_eq!(wallet.amount(&Resources::Metals), BigNum::from(4000000060u64));
+        assert_eq!(wallet.amount(&Resources::Prestige), BigNum::from(1u64));
+        let station = |id: StationId| &data.stations.iter().find(|(listed, _)| *listed == id).unwrap().1;
+        let levels: Vec<(usize, u32)> = station(StationId::Exoplanet).upgrades.iter().map(|upgrade| (upgrade.id, upgrade.level)).collect();
+        assert_eq!(levels, vec![(0, 2), (1, 1), (2, 0)]);
+        assert_eq!(station(StationId::Exoplanet).drones, 1);
+        assert!(station(StationId::DroneDepot).unlocked);
+        assert!(station(StationId::AsteroidMines).unlocked);
+        assert_eq!(station(StationId::AsteroidMines).drones, 1);
+        assert_eq!(data.saved_at, 0);
+        assert!(data.offline.enabled);
+        assert_eq!(data.sector.index, 0);
+        data.restore();
+    }
+    // Every built-in station comes back from the keyed list as it was captured
+    #[test]
+    fn stations_survive_restore() {
+        let mut sim = Simulation::new(7, Sector::home(), Player::default());
+        let mut buyer = AutoBuyer { unlocked: true, policy: BuyPolicy::Cheapest, enabled: vec![true; StationId::ALL.len()], ..AutoBuyer::new() };
+        for _ in 0..5_000 {
+            sim.event_manager.skip_dialogue();
+            let mut input = SimInput { scanning: true, commands: vec![] };
+            buyer.update(&sim, &mut input);
+            sim.step(&input);
+        }
+        let data = SaveData::capture(&sim, &OfflineProgress::new(), 0, &buyer);
+        assert_eq!(data.stations.len(), 6);
+        let restored = data.restore();
+        assert_eq!(SaveData::capture(&restored, &OfflineProgress::new(), 0, &buyer).stations, data.stations);
+        // Pop up layout is left out, it depends on where the panels were last drawn
+        let offered = |sim: &Simulation, station| -> Vec<(usize, u32, String)> {
+            sim.offers(station).iter().map(|upgrade| (upgrade.id, upgrade.level, format!("{:?}", upgrade.cost))).collect()
+        };
+        for station in StationId::ALL {
+            assert_eq!(offered(&restored, station), offered(&sim, station));
+        }
+    }
+
+    fn exported() -> (SaveData, String) {
+        let sim = Simulation::new(3, Sector::home(), Player::default());
+        let data = SaveData::capture(&sim, &OfflineProgress::new(), 0, &AutoBuyer::new());
+        let file = SaveFile { state: data.try_to_vec().unwrap(), ..SaveFile::default() };
+        (data, file.export())
+    }
+
+    #[test]
+    fn export_round_trips() {
+        let (data, text) = exported();
+        assert!(text.starts_with(EXPORT_PREFIX));
+        // Line breaks picked up when pasting are ignored
+        let pasted = format!("{}\n{}\n", &text[..20], &text[20..]);
+        let file = SaveFile::import(&pasted).unwrap();
+        assert_eq!(SaveData::read(&file).unwrap(), data);
+        assert_eq!(file.slots, SaveSlots::default());
+    }
+
+    #[test]
+    fn import_rejects_damaged_text() {
+        let (_, text) = exported();
+        assert_eq!(SaveFile::import(&text[EXPORT_PREFIX.len()..]), Err(SaveError::NotExport));
+        assert_eq!(SaveFile::import("TINC"), Err(SaveError::NotExport));
+
+        // Cut mid quad the base64 no longer decodes, cut on a quad the body comes up short
+        assert!(matches!(SaveFile::import(&text[..text.len() - 3]), Err(SaveError::Corrupt(_))));
+        let cut = EXPORT_PREFIX.len() + (text.len() - EXPORT_PREFIX.len()) / 2 / 4 * 4;
+        assert!(matches!(SaveFile::import(&text[..cut]), Err(SaveError::Checksum { .. })));
+        assert_eq!(SaveFile::import(&text[..EXPORT_PREFIX.len() + 8]), Err(SaveError::Truncated));
+
+        let mut data = encoding::decode_base64(&text[EXPORT_PREFIX.len()..]).unwrap();
+        let found = u32::from_le_bytes([data[6], data[7], data[8], data[9]]);
+        data[6] ^= 0x01;
+        let flipped = format!("{}{}", EXPORT_PREFIX, encoding::encode_base64(&data));
+        assert_eq!(SaveFile::import(&flipped), Err(SaveError::Checksum { expected: found ^ 0x01, found }));
+    }
+}
@@ -0,0 +1,111 @@
+use super::*;
+
+// An upgrade on offer, by its index in the upgrade list it came from
+#[derive(Debug, Clone, PartialEq, BorshDeserialize, BorshSerialize)]
+pub struct UpgradeSave {
+    pub id: usize,
+    pub level: u32,
+}
+
+// Progression shared by every station, drones are stored as a count and respawned
+#[derive(Debug, Clone, PartialEq, BorshDeserialize, BorshSerialize)]
+pub struct StationSave {
+    pub station: Station,
+    pub drones: u32,
+    pub unlockable: bool,
+    pub unlocked: bool,
+    pub upgrades: Vec<UpgradeSave>,
+    pub detail: StationDetail,
+}
+
+// What a station keeps besides the shared progression
+#[derive(Debug, Clone, PartialEq, BorshDeserialize, BorshSerialize)]
+pub enum StationDetail {
+    None,
+    Exoplanet {
+        scanner_level: u32,
+        assigned: bool,
+    },
+    Depot {
+        fabricator_unlocked: bool,
+        power_plant_unlocked: bool,
+        fab_level: u32,
+        fab_prog: BigNum,
+        fab_upgrades: Vec<UpgradeSave>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, BorshDeserialize, BorshSerialize)]
+pub struct PlayerSave {
+    pub resources: Wallet,
+    pub prestiged: bool,
+    pub prestige_prog: BigNum,
+    pub prestige_index: u32,
+    pub prestige_earned: BigNum,
+    pub upgrades: Vec<UpgradeSave>,
+}
+
+// Everything a run needs to resume, the rest of GameState is rebuilt on load
+#[derive(Debug, Clone, PartialEq, BorshDeserialize, BorshSerialize)]
+pub struct SaveData {
+    pub saved_at: u64,
+    pub offline: OfflineProgress,
+    pub tick: usize,
+    // Events still waiting on their cutscene
+    pub events: Vec<Event>,
+    pub player: PlayerSave,
+    // Built-in stations by the first StationId they go by, in registry order
+    pub stations: Vec<(StationId, StationSave)>,
+    pub rng: GameRng,
+    pub auto_buyer: AutoBuyer,
+    pub achievements: Achievements,
+    pub stats: Stats,
+    pub research: Research,
+    // Content pack stations by name
+    pub packs: Vec<(String, StationSave)>,
+    pub sector: Sector,
+}
+
+impl SaveData {
+    pub fn capture(sim: &Simulation, offline: &OfflineProgress, saved_at: u64, auto_buyer: &AutoBuyer) -> Self {
+        SaveData {
+            saved_at,
+            offline: offline.clone(),
+            tick: sim.tick,
+            events: sim.event_manager.pending(),
+            player: sim.player.save(),
+            stations: sim.registry().into_iter().filter_map(|station| Some((station.ids()[0], station.save()?))).collect(),
+            rng: sim.rng,
+            auto_buyer: auto_buyer.clone(),
+            achievements: sim.achievements.clone(),
+            stats: sim.stats.clone(),
+            research: sim.research.clone(),
+            packs: sim.packs.save(),
+            sector: sim.sector.clone(),
+        }
+    }
+
+    // The progression held by a decoded save file
+    pub fn read(file: &SaveFile) -> Result<SaveData, SaveError> {
+        SaveData::try_from_slice(&file.state).map_err(|err| SaveError::Corrupt(err.to_string()))
+    }
+
+    pub fn restore(&self) -> Simulation {
+        let mut sim = Simulation::new(0, self.sector.clone(), Player::restore(&self.player));
+        sim.tick = self.tick;
+        sim.event_manager = EventManager::restore(self.events.clone());
+        // Respawned drones take their phase from the saved stream, where the saved run left off
+        let mut rng = self.rng;
+        for (id, save) in self.stations.iter() {
+            if let Some(station) = sim.station_mut(*id) {
+                station.restore(save, &mut rng.gameplay, &self.sector);
+            }
+        }
+        sim.packs = Packs::restore(&self.packs, &mut rng.gameplay, &self.sector);
+        sim.rng = rng;
+        sim.research = self.research.clone();
+        sim.achievements = Achievements::restore(&self.achievements);
+        sim.stats = self.stats.clone();
+        sim
+    }
+}
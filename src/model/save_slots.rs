@@ -0,0 +1,165 @@
+use super::*;
+use once_cell::sync::Lazy;
+use std::sync::Mutex;
+
+// Slots a run can be kept in next to the others, in picker order
+pub const SLOT_NAMES: [&str; 3] = ["MAIN", "BRANCH A", "BRANCH B"];
+
+// Autosaves kept to roll back to
+pub const AUTOSAVE_COUNT: usize = 5;
+
+// The stored slots, written out with every save
+static SLOTS: Lazy<Mutex<SaveSlots>> = Lazy::new(|| Mutex::new(SaveSlots::new()));
+// The latest autosaves, newest first
+static AUTOSAVES: Lazy<Mutex<Vec<SaveSlot>>> = Lazy::new(|| Mutex::new(vec![]));
+
+// What the slot picker shows without restoring the run
+#[derive(Debug, Clone, PartialEq, BorshDeserialize, BorshSerialize)]
+pub struct SlotInfo {
+    pub prestige: u32,
+    // Everything held apart from prestige
+    pub resources: BigNum,
+    pub saved_at: u64,
+}
+
+impl SlotInfo {
+    pub fn new(save: &SaveData) -> Self {
+        let resources = save.player.resources.iter()
+            .filter(|(resource, _)| **resource != Resources::Prestige)
+            .fold(BigNum::ZERO, |total, (_, balance)| total + balance.amount);
+        SlotInfo {
+            prestige: save.player.prestige_index,
+            resources,
+            saved_at: save.saved_at,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, BorshDeserialize, BorshSerialize)]
+pub struct SaveSlot {
+    pub info: SlotInfo,
+    // Borsh SaveData at SAVE_VERSION
+    pub state: Vec<u8>,
+}
+
+#[derive(Debug, Clone, PartialEq, BorshDeserialize, BorshSerialize)]
+pub struct SaveSlots {
+    pub slots: Vec<Option<SaveSlot>>,
+    // The slot the running game was loaded from or saved to, kept up to date by every save
+    pub active: Option<usize>,
+}
+
+impl SaveSlots {
+    pub fn new() -> Self {
+        SaveSlots {
+            slots: vec![None; SLOT_NAMES.len()],
+            active: None,
+        }
+    }
+
+    // The slots to write with the next save
+    pub fn current() -> SaveSlots {
+        SLOTS.lock().unwrap().clone()
+    }
+
+    // Take over the slots of a loaded save
+    pub fn keep(mut slots: SaveSlots) {
+        slots.slots.resize(SLOT_NAMES.len(), None);
+        if slots.active.is_some_and(|index| index >= SLOT_NAMES.len()) {
+            slots.active = None;
+        }
+        *SLOTS.lock().unwrap() = slots;
+    }
+
+    // Summaries for the slot picker, without copying the runs
+    pub fn infos() -> Vec<Option<SlotInfo>> {
+        SLOTS.lock().unwrap().slots.iter().map(|slot| slot.as_ref().map(|slot| slot.info.clone())).collect()
+    }
+
+    pub fn get(index: usize) -> Option<SaveSlot> {
+        SLOTS.lock().unwrap().slots.get(index).cloned().flatten()
+    }
+
+    pub fn active() -> Option<usize> {
+        SLOTS.lock().unwrap().active
+    }
+
+    // Keep saving the running game into this slot
+    pub fn activate(index: usize) {
+        if index < SLOT_NAMES.len() {
+            SLOTS.lock().unwrap().active = Some(index);
+        }
+    }
+
+    // Write the running game into the active slot, if it has one
+    pub fn refresh(save: &SaveData, state: &[u8]) {
+        let mut slots = SLOTS.lock().unwrap();
+        if let Some(index) = slots.active {
+            slots.slots[index] = Some(SaveSlot { info: SlotInfo::new(save), state: state.to_vec() });
+        }
+    }
+
+    // Progression stored in a slot, which becomes the active one
+    pub fn load(index: usize) -> Result<SaveData, SaveError> {
+        let slot = SaveSlots::get(index).ok_or(SaveError::EmptySlot(index))?;
+        let save = SaveData::try_from_slice(&slot.state).map_err(|err| SaveError::Corrupt(err.to_string()))?;
+        SaveSlots::activate(index);
+        Ok(save)
+    }
+
+    // Copy a slot into the first empty one, returns where it went
+    pub fn duplicate(index: usize) -> Result<usize, SaveError> {
+        let mut slots = SLOTS.lock().unwrap();
+        let slot = slots.slots.get(index).cloned().flatten().ok_or(SaveError::EmptySlot(index))?;
+        let free = slots.slots.iter().position(|slot| slot.is_none()).ok_or(SaveError::SlotsFull)?;
+        slots.slots[free] = Some(slot);
+        Ok(free)
+    }
+
+    pub fn delete(index: usize) {
+        let mut slots = SLOTS.lock().unwrap();
+        if let Some(slot) = slots.slots.get_mut(index) {
+            *slot = None;
+        }
+        if slots.active == Some(index) {
+            slots.active = None;
+        }
+    }
+}
+
+impl Default for SaveSlots {
+    fn default() -> Self {
+        SaveSlots::new()
+    }
+}
+
+// Ring of the last AUTOSAVE_COUNT autosaves, so a bad state that got saved can be rolled back
+pub struct Autosaves;
+
+impl Autosaves {
+    pub fn list() -> Vec<SaveSlot> {
+        AUTOSAVES.lock().unwrap().clone()
+    }
+
+    pub fn infos() -> Vec<SlotInfo> {
+        AUTOSAVES.lock().unwrap().iter().map(|autosave| autosave.info.clone()).collect()
+    }
+
+    // Take over the autosaves of a loaded save
+    pub fn keep(mut autosaves: Vec<SaveSlot>) {
+        autosaves.truncate(AUTOSAVE_COUNT);
+        *AUTOSAVES.lock().unwrap() = autosaves;
+    }
+
+    // Add an autosave, dropping the oldest once the ring is full
+    pub fn push(save: &SaveData, state: &[u8]) {
+        let mut autosaves = AUTOSAVES.lock().unwrap();
+        autosaves.insert(0, SaveSlot { info: SlotInfo::new(save), state: state.to_vec() });
+        autosaves.truncate(AUTOSAVE_COUNT);
+    }
+
+    pub fn load(index: usize) -> Result<SaveData, SaveError> {
+        let autosave = AUTOSAVES.lock().unwrap().get(index).cloned().ok_or(SaveError::NoAutosave(index))?;
+        SaveData::try_from_slice(&autosave.state).map_err(|err| SaveError::Corrupt(err.to_string()))
+    }
+}
@@ -0,0 +1,259 @@
+use super::*;
+
+// Mixed into the run seed so the sector gets its own sequence
+const SECTOR_STREAM: u64 = 0x7365_6374;
+// Furthest a station drifts from its place on the home map, rerolled while it would crowd another
+const DRIFT: i32 = 64;
+const DRIFT_TRIES: usize = 8;
+// Space kept clear around every station
+const STATION_GAP: i32 = 16;
+// Where the belt circles and the storm gathers on the home map, and how far they drift in other sectors
+const HOME_BELT: (f32, f32) = (1344., 1344.);
+const BELT_DRIFT: u32 = 96;
+const HOME_STORM: (f32, f32) = (640. + 240. + 64., -240. - 64.);
+const STORM_DRIFT: u32 = 128;
+// Percent chance a field is missing from the sector, from the second jump on. Never both at once
+const FIELD_MISSING_CHANCE: u32 = 20;
+const FIELD_MISSING_FROM: u32 = 2;
+// Multiplier on the yield of a station with no field to draw from, its drones scrape what they can
+const MISSING_FIELD_YIELD: f32 = 0.5;
+// Percent chance of a modifier in each group, growing deeper in
+const MODIFIER_CHANCE: u32 = 35;
+const MODIFIER_CHANCE_STEP: u32 = 10;
+const MODIFIER_CHANCE_MAX: u32 = 80;
+
+#[derive(Debug, Clone, Copy, PartialEq, BorshDeserialize, BorshSerialize)]
+pub enum Modifier {
+    MetalRich,
+    SparseBelt,
+    StormHeavy,
+    CalmStorm,
+    DataRich,
+}
+
+// At most one modifier of each group per sector
+const MODIFIER_GROUPS: [&[Modifier]; 3] = [
+    &[Modifier::MetalRich, Modifier::SparseBelt],
+    &[Modifier::StormHeavy, Modifier::CalmStorm],
+    &[Modifier::DataRich],
+];
+
+impl Modifier {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Modifier::MetalRich => "METAL-RICH",
+            Modifier::SparseBelt => "SPARSE BELT",
+            Modifier::StormHeavy => "STORM-HEAVY",
+            Modifier::CalmStorm => "CALM STORM",
+            Modifier::DataRich => "DATA-RICH",
+        }
+    }
+
+    // Multiplier on the base yield of a resource
+    fn yield_of(&self, resource: &Resources) -> f32 {
+        match (self, resource) {
+            (Modifier::MetalRich, Resources::Metals) => 1.5,
+            (Modifier::SparseBelt, Resources::Metals) => 0.75,
+            (Modifier::StormHeavy, Resources::Power) => 1.5,
+            (Modifier::CalmStorm, Resources::Power) => 0.75,
+            (Modifier::DataRich, Resources::Research) => 1.25,
+            _ => 1.,
+        }
+    }
+
+    // Multipliers on the asteroids in the belt and the bolts in the storm
+    fn fields(&self) -> (f32, f32) {
+        match self {
+            Modifier::MetalRich => (1.5, 1.),
+            Modifier::SparseBelt => (0.4, 1.),
+            Modifier::StormHeavy => (1., 2.),
+            Modifier::CalmStorm => (1., 0.4),
+            Modifier::DataRich => (1., 1.),
+        }
+    }
+}
+
+// Layout and modifiers of the sector a run plays in, rolled on every jump
+#[derive(Debug, Clone, PartialEq, BorshDeserialize, BorshSerialize)]
+pub struct Sector {
+    pub seed: u64,
+    pub index: u32,
+    pub modifiers: Vec<Modifier>,
+    // Moved from the home map, by StationId::ALL
+    pub offsets: Vec<(i32, i32)>,
+    // Centres of the asteroid belt and the nebula storm, None when the sector has no such field
+    pub belt: Option<(f32, f32)>,
+    pub storm: Option<(f32, f32)>,
+}
+
+impl Sector {
+    // The hand made map of the first run
+    pub fn home() -> Self {
+        Sector {
+            seed: 0,
+            index: 0,
+            modifiers: vec![],
+            offsets: vec![(0, 0); StationId::ALL.len()],
+            belt: Some(HOME_BELT),
+            storm: Some(HOME_STORM),
+        }
+    }
+
+    // Roll the sector for a run's prestige index and seed, the first run always plays the home map
+    pub fn generate(index: u32, seed: u64) -> Self {
+        if index == 0 {
+            return Sector::home();
+        }
+        let mut rng = Rng::new(Rng::new(seed ^ SECTOR_STREAM ^ index as u64).next_u64());
+        let chance = (MODIFIER_CHANCE + MODIFIER_CHANCE_STEP * index).min(MODIFIER_CHANCE_MAX);
+        let mut modifiers = vec![];
+        for group in MODIFIER_GROUPS.iter() {
+            if rng.next_u32() % 100 < chance {
+                modifiers.push(group[rng.next_u32() as usize % group.len()]);
+            }
+        }
+        // The exoplanet stays put, the sector is laid out around it
+        let mut offsets = vec![(0, 0); StationId::ALL.len()];
+        let mut placed = vec![(PLANET_BOX, (0, 0))];
+        for station in StationId::ALL.iter().filter(|station| **station != StationId::Exoplanet) {
+            let Some(home) = home_box(*station) else {
+                continue;
+            };
+            // Out of tries the station keeps its home place, which never crowds the home map
+            let offset = (0..DRIFT_TRIES)
+                .map(|_| (drift(&mut rng, DRIFT as u32) as i32, drift(&mut rng, DRIFT as u32) as i32))
+                .find(|offset| placed.iter().all(|(other, at)| !crowds(home, *offset, *other, *at)))
+                .unwrap_or((0, 0));
+            offsets[station.index()] = offset;
+            placed.push((home, offset));
+        }
+        let mut field = |home: (f32, f32), range: u32| {
+            let missing = index >= FIELD_MISSING_FROM && rng.next_u32() % 100 < FIELD_MISSING_CHANCE;
+            let centre = (home.0 + drift(&mut rng, range), home.1 + drift(&mut rng, range));
+            (!missing).then_some(centre)
+        };
+        let belt = field(HOME_BELT, BELT_DRIFT);
+        let mut storm = field(HOME_STORM, STORM_DRIFT);
+        if belt.is_none() && storm.is_none() {
+            storm = Some(HOME_STORM);
+        }
+        Sector { seed, index, modifiers, offsets, belt, storm }
+    }
+
+    // Where a station sits in this sector, given its place on the home map. Stations added after the
+    // sector was saved stay put
+    pub fn place(&self, station: StationId, home: (i32, i32, i32, i32)) -> (i32, i32, i32, i32) {
+        let (x, y) = self.offsets.get(station.index()).copied().unwrap_or((0, 0));
+        (home.0 + x, home.1 + y, home.2, home.3)
+    }
+
+    // Multiplier on a station's base yield of the resource
+    pub fn yield_of(&self, resource: &Resources) -> f32 {
+        let missing = match resource {
+            Resources::Metals if self.belt.is_none() => MISSING_FIELD_YIELD,
+            Resources::Power if self.storm.is_none() => MISSING_FIELD_YIELD,
+            _ => 1.,
+        };
+        missing * self.modifiers.iter().map(|modifier| modifier.yield_of(resource)).product::<f32>()
+    }
+
+    // Where the storm gathers, the conduit drones still circle its place when the sector has none
+    pub fn storm_centre(&self) -> (f32, f32) {
+        self.storm.unwrap_or(HOME_STORM)
+    }
+
+    // Multipliers on the asteroids in the belt and the bolts in the storm
+    pub fn fields(&self) -> (f32, f32) {
+        self.modifiers.iter().fold((1., 1.), |(belt, storm), modifier| {
+            let fields = modifier.fields();
+            (belt * fields.0, storm * fields.1)
+        })
+    }
+
+    // Told when the run starts, None on the home map
+    pub fn briefing(&self) -> Option<String> {
+        if self.index == 0 {
+            return None;
+        }
+        let name = format!("SECTOR {}-{:03}", (b'A' + (self.seed % 26) as u8) as char, self.seed % 1000);
+        let mut labels: Vec<&str> = self.modifiers.iter().map(|modifier| modifier.label()).collect();
+        if self.belt.is_none() {
+            labels.push("NO BELT");
+        }
+        if self.storm.is_none() {
+            labels.push("NO STORM");
+        }
+        if labels.is_empty() {
+            return Some(format!("Arrived in {}. Readings are nominal.", name));
+        }
+        Some(format!("Arrived in {}. Readings: {}.", name, labels.join(", ")))
+    }
+}
+
+// Evenly rolled in -range..=range
+fn drift(rng: &mut Rng, range: u32) -> f32 {
+    (rng.next_u32() % (range * 2 + 1)) as f32 - range as f32
+}
+
+// Place of a station on the home map, None for those drawn as part of another
+fn home_box(station: StationId) -> Option<(i32, i32, i32, i32)> {
+    match station {
+        StationId::Exoplanet => Some(PLANET_BOX),
+        StationId::DroneDepot => Some(DEPOT_BOX),
+        StationId::AsteroidMines => Some(MINES_BOX),
+        StationId::PowerPlant => Some(PLANT_BOX),
+        StationId::Jumpgate => Some(GATE_BOX),
+        StationId::ResearchComplex => Some(COMPLEX_BOX),
+        StationId::Probe | StationId::Fabricator => None,
+    }
+}
+
+// Whether two station boxes moved by their offsets would sit closer than the gap
+fn crowds(a: (i32, i32, i32, i32), offset: (i32, i32), b: (i32, i32, i32, i32), at: (i32, i32)) -> bool {
+    let (ax, ay) = (a.0 + offset.0, a.1 + offset.1);
+    let (bx, by) = (b.0 + at.0, b.1 + at.1);
+    ax < bx + b.2 + STATION_GAP && bx < ax + a.2 + STATION_GAP && ay < by + b.3 + STATION_GAP && by < ay + a.3 + STATION_GAP
+}
+
+impl Default for Sector {
+    fn default() -> Self {
+        Sector::home()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generate_is_deterministic() {
+        assert_eq!(Sector::generate(0, 42), Sector::home());
+        for index in 1..20 {
+            assert_eq!(Sector::generate(index, 42), Sector::generate(index, 42));
+        }
+    }
+
+    #[test]
+    fn sectors_vary_fields_and_layout() {
+        let sectors: Vec<Sector> = (1..200u64).map(|seed| Sector::generate(FIELD_MISSING_FROM, seed)).collect();
+        assert!(sectors.iter().any(|sector| sector.belt.is_none()));
+        assert!(sectors.iter().any(|sector| sector.storm.is_none()));
+        assert!(sectors.iter().all(|sector| sector.belt.is_some() || sector.storm.is_some()));
+        assert!(sectors.iter().any(|sector| sector.belt.is_some_and(|belt| belt != HOME_BELT)));
+        assert!(sectors.iter().any(|sector| sector.storm.is_some_and(|storm| storm != HOME_STORM)));
+        // Fields only go missing deeper in
+        assert!((1..200u64).all(|seed| {
+            let sector = Sector::generate(1, seed);
+            sector.belt.is_some() && sector.storm.is_some()
+        }));
+
+        for sector in sectors.iter() {
+            let boxes: Vec<_> = StationId::ALL.iter()
+                .filter_map(|station| home_box(*station).map(|home| (home, sector.offsets[station.index()])))
+                .collect();
+            for (i, (a, offset)) in boxes.iter().enumerate() {
+                assert!(boxes[i + 1..].iter().all(|(b, at)| !crowds(*a, *offset, *b, *at)));
+            }
+        }
+    }
+}
@@ -11,6 +11,9 @@ pub struct Global {
     pub options: bool,
     pub info_button: Btn,
     pub info: bool,
+    pub stats_button: Btn,
+    pub stats: bool,
+    pub stats_bounds: Bounds,
     pub menu_bounds: Bounds,
     pub music_toggle: Btn,
     pub sfx_toggle: Btn,
@@ -18,6 +21,58 @@ pub struct Global {
     pub save_button: Btn,
     pub autosave: bool,
     pub autosave_toggle: Btn,
+    pub notation_button: Btn,
+    pub export_button: Btn,
+    pub import_button: Btn,
+    pub slots_button: Btn,
+    pub slots: bool,
+    pub slot_bounds: Bounds,
+    // LOAD, SAVE, COPY and DEL for every slot
+    pub slot_buttons: Vec<Vec<Btn>>,
+    pub backups_button: Btn,
+    pub backups: bool,
+    pub backup_bounds: Bounds,
+    // One RESTORE per autosave, newest first
+    pub backup_buttons: Vec<Btn>,
+    pub record_button: Btn,
+    pub replay_button: Btn,
+    // Save and replay strings beside the menu, going out or coming in
+    pub transfer: Option<Transfer>,
+    pub transfer_bounds: Bounds,
+    pub copy_button: Btn,
+    pub load_button: Btn,
+    pub auto_button: Btn,
+    pub auto: bool,
+    pub auto_bounds: Bounds,
+    pub policy_button: Btn,
+    pub reserve_button: Btn,
+    // One per station, by StationId::ALL
+    pub auto_toggles: Vec<Btn>,
+    // Short feedback shown under the menu buttons
+    pub message: String,
+    pub message_ticks: usize,
+    // Achievements waiting for their toast, the first one is showing
+    pub toasts: Vec<usize>,
+    pub toast_ticks: usize,
+}
+
+// Frames an achievement toast stays up
+const TOAST_TICKS: usize = 240;
+// Characters per line and lines of a string shown in the transfer panel
+const TRANSFER_COLUMNS: usize = 36;
+const TRANSFER_LINES: usize = 8;
+
+// What the transfer panel is showing
+#[derive(Debug, Clone, PartialEq, BorshDeserialize, BorshSerialize)]
+pub enum Transfer {
+    // An exported save string, already copied to the clipboard
+    ExportSave(String),
+    // Waiting for a save string pasted into the page
+    ImportSave,
+    // A finished recording, already copied to the clipboard
+    ExportReplay(String),
+    // Waiting for a recording pasted into the page
+    ImportReplay,
 }
 
 // The singleton instance
@@ -25,8 +80,23 @@ pub static GLOBAL: Lazy<Mutex<Global>> = Lazy::new(|| Mutex::new(Global::new()))
 impl Global {
     // Private constructor
     fn new() -> Self {
-        let menu_bounds = Bounds::new(0, 26, 96, 96);
+        let menu_bounds = Bounds::new(0, 26, 96, 264);
         let spacing = 24;
+        let slot_bounds = Bounds::new(menu_bounds.right() + 2, menu_bounds.y(), 172, 8 + SLOT_NAMES.len() as u32 * 40);
+        let slot_buttons = (0..SLOT_NAMES.len()).map(|i| {
+            ["LOAD", "SAVE", "COPY", "DEL"].iter().enumerate().map(|(j, label)| {
+                Btn::new(label.to_string(), Bounds::new(slot_bounds.x() + 6 + j as i32 * 40, slot_bounds.y() + 26 + i as i32 * 40, 36, 14), true, 1)
+            }).collect()
+        }).collect();
+        let backup_bounds = Bounds::new(menu_bounds.right() + 2, menu_bounds.y(), 172, 8 + AUTOSAVE_COUNT as u32 * 20);
+        let backup_buttons = (0..AUTOSAVE_COUNT).map(|i| {
+            Btn::new("RESTORE".to_string(), Bounds::new(backup_bounds.right() - 52, backup_bounds.y() + 4 + i as i32 * 20, 46, 14), true, 1)
+        }).collect();
+        let transfer_bounds = Bounds::new(menu_bounds.right() + 2, menu_bounds.y(), 196, 40 + TRANSFER_LINES as u32 * 10 + 24);
+        let auto_bounds = Bounds::new(0, 26, 120, 60 + StationId::ALL.len() as u32 * 20);
+        let auto_toggles = (0..StationId::ALL.len()).map(|i| {
+            Btn::new("toggle".to_string(), Bounds::new(auto_bounds.x() + 8, auto_bounds.y() + 56 + i as i32 * 20, 16, 16), false, 1)
+        }).collect();
         Global {
             sfx: true,
             music: true,
@@ -34,6 +104,9 @@ impl Global {
             options: false,
             info_button: Btn::new("i".to_string(), Bounds::new(26,0,24,24), false, 1),
             info: false,
+            stats_button: Btn::new("S".to_string(), Bounds::new(52,0,24,24), true, 1),
+            stats: false,
+            stats_bounds: Bounds::new(menu_bounds.x(), menu_bounds.y(), 176, 162),
             menu_bounds,
             music_toggle: Btn::new("toggle".to_string(), Bounds::new(menu_bounds.x()+16, menu_bounds.y()+spacing/3,16,16), false, 1),
             sfx_toggle: Btn::new("toggle".to_string(), Bounds::new(menu_bounds.x()+16, menu_bounds.y()+spacing/3+spacing,16,16), false, 1),
@@ -41,6 +114,33 @@ impl Global {
             reset_button: Btn::new("RESET SAVE".to_string(), Bounds::new(menu_bounds.x()+12, menu_bounds.y()+2+spacing * 3,72,16), true, 1),
             autosave: true,
             autosave_toggle: Btn::new("toggle".to_string(), Bounds::new(menu_bounds.x()+16, menu_bounds.y()+spacing/3+spacing,16,16), false, 1),
+            notation_button: Btn::new(Numbers::notation().label().to_string(), Bounds::new(menu_bounds.x()+12, menu_bounds.y()+2+spacing * 4,72,16), true, 1),
+            export_button: Btn::new("EXPORT".to_string(), Bounds::new(menu_bounds.x()+12, menu_bounds.y()+2+spacing * 5,72,16), true, 1),
+            import_button: Btn::new("IMPORT".to_string(), Bounds::new(menu_bounds.x()+12, menu_bounds.y()+2+spacing * 6,72,16), true, 1),
+            slots_button: Btn::new("SLOTS".to_string(), Bounds::new(menu_bounds.x()+12, menu_bounds.y()+2+spacing * 7,72,16), true, 1),
+            slots: false,
+            slot_bounds,
+            slot_buttons,
+            backups_button: Btn::new("BACKUPS".to_string(), Bounds::new(menu_bounds.x()+12, menu_bounds.y()+2+spacing * 8,72,16), true, 1),
+            backups: false,
+            backup_bounds,
+            backup_buttons,
+            record_button: Btn::new("RECORD".to_string(), Bounds::new(menu_bounds.x()+12, menu_bounds.y()+2+spacing * 9,72,16), true, 1),
+            replay_button: Btn::new("REPLAY".to_string(), Bounds::new(menu_bounds.x()+12, menu_bounds.y()+2+spacing * 10,72,16), true, 1),
+            transfer: None,
+            transfer_bounds,
+            copy_button: Btn::new("COPY".to_string(), Bounds::new(transfer_bounds.right() - 54, transfer_bounds.bottom() - 22, 48, 16), true, 1),
+            load_button: Btn::new("LOAD".to_string(), Bounds::new(transfer_bounds.right() - 54, transfer_bounds.bottom() - 22, 48, 16), true, 1),
+            auto_button: Btn::new("A".to_string(), Bounds::new(78,0,24,24), true, 1),
+            auto: false,
+            auto_bounds,
+            policy_button: Btn::new(BuyPolicy::Cheapest.label().to_string(), Bounds::new(auto_bounds.x()+8, auto_bounds.y()+6, auto_bounds.w()-16, 16), true, 1),
+            reserve_button: Btn::new("".to_string(), Bounds::new(auto_bounds.x()+8, auto_bounds.y()+30, auto_bounds.w()-16, 16), true, 1),
+            auto_toggles,
+            message: String::new(),
+            message_ticks: 0,
+            toasts: vec![],
+            toast_ticks: 0,
         }
     }
 
@@ -49,13 +149,46 @@ impl Global {
         let mut instance = GLOBAL.lock().unwrap();
     }
 
-    pub fn update(&mut self, event_manager: &mut EventManager) {
+    // Show a message for a few seconds
+    pub fn notify(&mut self, message: String) {
+        self.message = message;
+        self.message_ticks = 300;
+    }
+
+    // Open the transfer panel beside the menu
+    pub fn show_transfer(&mut self, transfer: Transfer) {
+        self.options = true;
+        self.slots = false;
+        self.backups = false;
+        self.load_button.string = if transfer == Transfer::ImportReplay { "PLAY" } else { "LOAD" }.to_string();
+        self.transfer = Some(transfer);
+    }
+
+    // Open the panel, or close it when it already shows this
+    fn toggle_transfer(&mut self, transfer: Transfer) {
+        if self.transfer.as_ref() == Some(&transfer) {
+            self.transfer = None;
+        } else {
+            self.show_transfer(transfer);
+        }
+    }
+
+    pub fn update(&mut self, event_manager: &mut EventManager, unlocked: Vec<usize>) {
         let p = pointer();
         self.options_button.update();
         if self.options_button.on_click() {
             self.options = !self.options
         } else if p.just_pressed() && !p.intersects_fixed(self.menu_bounds.x(), self.menu_bounds.y(), self.menu_bounds.w(), self.menu_bounds.h()) && self.options {
-            self.options = false;
+            // The slot and backup pickers and the transfer panel sit beside the menu and keep it open
+            let in_slots = self.slots && p.intersects_fixed(self.slot_bounds.x(), self.slot_bounds.y(), self.slot_bounds.w(), self.slot_bounds.h());
+            let in_backups = self.backups && p.intersects_fixed(self.backup_bounds.x(), self.backup_bounds.y(), self.backup_bounds.w(), self.backup_bounds.h());
+            let in_transfer = self.transfer.is_some() && p.intersects_fixed(self.transfer_bounds.x(), self.transfer_bounds.y(), self.transfer_bounds.w(), self.transfer_bounds.h());
+            if !in_slots && !in_backups && !in_transfer {
+                self.options = false;
+                self.slots = false;
+                self.backups = false;
+                self.transfer = None;
+            }
         }
         self.info_button.update();
         if self.info_button.on_click() {
@@ -63,6 +196,14 @@ impl Global {
         } else if p.just_pressed() && !p.intersects_fixed(self.menu_bounds.x(), self.menu_bounds.y(), self.menu_bounds.w(), self.menu_bounds.h()) && self.info {
             self.info = false;
         }
+        self.stats_button.update();
+        if self.stats_button.on_click() {
+            self.stats = !self.stats;
+            self.options = false;
+            self.info = false;
+        } else if p.just_pressed() && !p.intersects_fixed(self.stats_bounds.x(), self.stats_bounds.y(), self.stats_bounds.w(), self.stats_bounds.h()) && self.stats {
+            self.stats = false;
+        }
         if self.options {
             self.music_toggle.update();
             if self.music_toggle.on_click() {
@@ -89,16 +230,113 @@ impl Global {
                 self.autosave = !self.autosave;
                 self.autosave_toggle.string = if self.autosave { "toggle".to_string() } else { "".to_string() };
             }
+            // Cycle how large numbers are written
+            self.notation_button.update();
+            if self.notation_button.on_click() {
+                let notation = Numbers::notation().next();
+                Numbers::set_notation(notation);
+                self.notation_button.string = notation.label().to_string();
+            }
+            self.export_button.update();
+            if self.export_button.on_click() {
+                event_manager.trigger(Event::ExportSave);
+            }
+            self.import_button.update();
+            if self.import_button.on_click() {
+                self.toggle_transfer(Transfer::ImportSave);
+            }
+            self.slots_button.update();
+            if self.slots_button.on_click() {
+                self.slots = !self.slots;
+                self.backups = false;
+                self.transfer = None;
+            }
+            self.backups_button.update();
+            if self.backups_button.on_click() {
+                self.backups = !self.backups;
+                self.slots = false;
+                self.transfer = None;
+            }
+            self.record_button.update();
+            if self.record_button.on_click() {
+                event_manager.trigger(Event::ToggleRecording);
+            }
+            self.replay_button.update();
+            if self.replay_button.on_click() {
+                self.toggle_transfer(Transfer::ImportReplay);
+            }
+            match &self.transfer {
+                Some(Transfer::ExportSave(text) | Transfer::ExportReplay(text)) => {
+                    self.copy_button.update();
+                    if self.copy_button.on_click() {
+                        Clipboard::copy(text);
+                        self.notify("COPIED".to_string());
+                    }
+                }
+                Some(Transfer::ImportSave) => {
+                    self.load_button.update();
+                    if self.load_button.on_click() {
+                        event_manager.trigger(Event::ImportSave);
+                    }
+                }
+                Some(Transfer::ImportReplay) => {
+                    self.load_button.update();
+                    if self.load_button.on_click() {
+                        event_manager.trigger(Event::PlayReplay);
+                    }
+                }
+                None => {}
+            }
+            if self.backups {
+                let count = Autosaves::infos().len();
+                for (i, button) in self.backup_buttons.iter_mut().enumerate().take(count) {
+                    button.update();
+                    if button.on_click() {
+                        event_manager.trigger(Event::RestoreAutosave(i));
+                    }
+                }
+            }
+            if self.slots {
+                for (i, buttons) in self.slot_buttons.iter_mut().enumerate() {
+                    for (j, button) in buttons.iter_mut().enumerate() {
+                        button.update();
+                        if button.on_click() {
+                            event_manager.trigger(match j {
+                                0 => Event::LoadSlot(i),
+                                1 => Event::StoreSlot(i),
+                                2 => Event::CopySlot(i),
+                                _ => Event::DeleteSlot(i),
+                            });
+                        }
+                    }
+                }
+            }
+        }
+        if self.message_ticks > 0 {
+            self.message_ticks -= 1;
         }
+        self.toasts.extend(unlocked);
+        if !self.toasts.is_empty() {
+            self.toast_ticks += 1;
+            if self.toast_ticks >= TOAST_TICKS {
+                self.toasts.remove(0);
+                self.toast_ticks = 0;
+            }
+        }
+        self.record_button.string = if Replay::is_recording() { "STOP REC" } else { "RECORD" }.to_string();
 
         if self.music && !audio::is_playing("loop") {
             audio::play("loop");
         }
     }
 
-    pub fn draw(&self) {
+    pub fn draw(&self, sim: &Simulation) {
         self.options_button.draw();
         self.info_button.draw();
+        self.stats_button.draw();
+        if self.stats {
+            self.draw_stats(&sim.stats);
+        }
         if self.options {
             rect!( 
                 fixed = true,
@@ -129,6 +367,34 @@ impl Global {
             );
             self.save_button.draw();
             self.reset_button.draw();
+            self.notation_button.draw();
+            self.export_button.draw();
+            self.import_button.draw();
+            self.slots_button.draw();
+            self.backups_button.draw();
+            self.record_button.draw();
+            self.replay_button.draw();
+            if self.slots {
+                self.draw_slots();
+            }
+            if self.backups {
+                self.draw_backups();
+            }
+            if let Some(transfer) = &self.transfer {
+                self.draw_transfer(transfer);
+            }
+        }
+        if self.message_ticks > 0 {
+            text!(&self.message, fixed = true, xy = (self.message_x(), 8));
+        }
+        if let Some(index) = self.toasts.first() {
+            self.draw_toast(&ACHIEVEMENTS[*index]);
+        }
+        let (frame, frames) = Replay::progress();
+        if Replay::is_replaying() {
+            text!("REPLAY {}/{}", frame, frames; fixed = true, xy = (self.message_x(), 18), color = 0xf8c53aff);
+        } else if Replay::is_recording() {
+            text!("REC {}", Numbers::format_duration(frame as u64 / 60); fixed = true, xy = (self.message_x(), 18), color = 0xe43b44ff);
         }
         if self.info {
             rect!( 
@@ -160,6 +426,255 @@ impl Global {
             textbox.set_size(88, 96);
             textbox.set_position(self.menu_bounds.x() + 4, self.menu_bounds.y() + 74);
             textbox.draw();
+            // For bug reports
+            let seed = format!("seed {:016x}", sim.rng.seed);
+            let mut textbox = TextBox::new(&seed);
+            textbox.set_fixed(true);
+            textbox.set_size(88, 96);
+            textbox.set_position(self.menu_bounds.x() + 4, self.menu_bounds.y() + 110);
+            textbox.draw();
+        }
+    }
+
+    fn draw_stats(&self, stats: &Stats) {
+        rect!( 
+            fixed = true,
+            xy = self.stats_bounds.xy(),
+            wh = self.stats_bounds.wh(),
+            border_size = 1,
+            border_radius = 2,
+            color = 0x1f122bff,
+            border_color = 0xffffffff,
+        );
+        let (x, y) = (self.stats_bounds.x() + 6, self.stats_bounds.y() + 6);
+        let (run_x, total_x) = (x + 84, x + 130);
+        text!("RUN", fixed = true, xy = (run_x, y), color = 0x847e87ff);
+        text!("TOTAL", fixed = true, xy = (total_x, y), color = 0x847e87ff);
+        let time = |ticks: u64| Numbers::format_duration(ticks / 60);
+        let rows = [
+            ("TIME", time(stats.run.ticks), time(stats.lifetime.ticks)),
+            ("SCANS", stats.run.scans.to_string(), stats.lifetime.scans.to_string()),
+            ("DRONES", stats.run.drones_deployed.to_string(), stats.lifetime.drones_deployed.to_string()),
+            ("ASTEROIDS", stats.run.asteroids_mined.to_string(), stats.lifetime.asteroids_mined.to_string()),
+            ("BOLTS", stats.run.bolts_harvested.to_string(), stats.lifetime.bolts_harvested.to_string()),
+            ("UPGRADES", stats.run.upgrades_bought.to_string(), stats.lifetime.upgrades_bought.to_string()),
+        ];
+        for (i, (label, run, total)) in rows.iter().enumerate() {
+            let row_y = y + 14 + i as i32 * 12;
+            text!(label, fixed = true, xy = (x, row_y));
+            text!(run, fixed = true, xy = (run_x, row_y));
+            text!(total, fixed = true, xy = (total_x, row_y));
+        }
+        let y = y + 14 + rows.len() as i32 * 12 + 6;
+        rect!(fixed = true, xy = (x - 2, y - 4), wh = (self.stats_bounds.w() - 8, 1), color = 0x847e87ff);
+        text!("PRESTIGES  {}", stats.prestiges; fixed = true, xy = (x, y));
+        let fastest = stats.fastest_run.map_or("-".to_string(), time);
+        text!("FASTEST RUN  {}", fastest; fixed = true, xy = (x, y + 12));
+        let last = stats.runs.last().map_or("-".to_string(), |ticks| time(*ticks));
+        text!("LAST RUN  {}", last; fixed = true, xy = (x, y + 24));
+        // Newest first, one bar per finished run against the slowest
+        let slowest = stats.runs.iter().copied().max().unwrap_or(1).max(1);
+        for (i, ticks) in stats.runs.iter().rev().enumerate() {
+            let w = ((*ticks as f64 / slowest as f64) * 60.) as u32;
+            rect!(fixed = true, xy = (x + 100, y + 2 + i as i32 * 4), wh = (w.max(1), 2), color = 0xf8c53aff);
+        }
+    }
+
+    // Slides in at the top right, out of the way of the menus and station pop ups
+    fn draw_toast(&self, achievement: &Achievement) {
+        let (w, h) = (168, 34);
+        let slide = (self.toast_ticks.min(TOAST_TICKS - self.toast_ticks) as i32 * 8).min(w + 4);
+        let bounds = Bounds::new(640 - slide, 4, w as u32, h as u32);
+        rect!( 
+            fixed = true,
+            xy = bounds.xy(),
+            wh = bounds.wh(),
+            border_size = 1,
+            border_radius = 2,
+            color = 0x1f122bff,
+            border_color = 0xf8c53aff,
+        );
+        text!("ACHIEVEMENT", fixed = true, xy = (bounds.x() + 4, bounds.y() + 3), font = "small", color = 0xf8c53aff);
+        text!(achievement.name, fixed = true, xy = (bounds.x() + 4, bounds.y() + 11));
+        let detail = if achievement.offline_bonus > 0. {
+            format!("{}  +{}% OFFLINE", achievement.description, (achievement.offline_bonus * 100.).round())
+        } else {
+            achievement.description.to_string()
+        };
+        text!(&detail, fixed = true, xy = (bounds.x() + 4, bounds.y() + 23), font = "small", color = 0x847e87ff);
+    }
+
+    // Messages go right of the top buttons, the auto-buyer's included once it shows
+    fn message_x(&self) -> i32 {
+        self.auto_button.bounds.right() + 6
+    }
+
+    // The auto-buyer button and its settings, once the probe upgrade is bought
+    pub fn update_auto_buyer(&mut self, buyer: &AutoBuyer, event_manager: &mut EventManager) {
+        if !buyer.unlocked {
+            self.auto = false;
+            return;
+        }
+        let p = pointer();
+        self.auto_button.update();
+        if self.auto_button.on_click() {
+            self.auto = !self.auto;
+            self.options = false;
+            self.info = false;
+            self.stats = false;
+        } else if p.just_pressed() && !p.intersects_fixed(self.auto_bounds.x(), self.auto_bounds.y(), self.auto_bounds.w(), self.auto_bounds.h()) && self.auto {
+            self.auto = false;
+        }
+        if !self.auto {
+            return;
+        }
+        self.policy_button.update();
+        if self.policy_button.on_click() {
+            event_manager.trigger(Event::CycleBuyPolicy);
+        }
+        self.reserve_button.interactable = buyer.policy == BuyPolicy::Reserve;
+        self.reserve_button.update();
+        if self.reserve_button.on_click() {
+            event_manager.trigger(Event::CycleBuyReserve);
+        }
+        for (station, toggle) in StationId::ALL.iter().zip(self.auto_toggles.iter_mut()) {
+            toggle.update();
+            if toggle.on_click() {
+                event_manager.trigger(Event::AutoBuyStation(*station));
+            }
+        }
+        self.policy_button.string = buyer.policy.label().to_string();
+        self.reserve_button.string = format!("KEEP {}%", buyer.reserve);
+        for (station, toggle) in StationId::ALL.iter().zip(self.auto_toggles.iter_mut()) {
+            toggle.string = if buyer.is_enabled(*station) { "toggle".to_string() } else { "".to_string() };
+        }
+    }
+
+    pub fn draw_auto_buyer(&self, buyer: &AutoBuyer) {
+        if !buyer.unlocked {
+            return;
+        }
+        self.auto_button.draw();
+        if !self.auto {
+            return;
+        }
+        rect!( 
+            fixed = true,
+            xy = self.auto_bounds.xy(),
+            wh = self.auto_bounds.wh(),
+            border_size = 1,
+            border_radius = 2,
+            color = 0x1f122bff,
+            border_color = 0xffffffff,
+        );
+        self.policy_button.draw();
+        self.reserve_button.draw();
+        for (station, toggle) in StationId::ALL.iter().zip(self.auto_toggles.iter()) {
+            toggle.draw();
+            text!(
+                station.label(),
+                fixed = true,
+                xy = (toggle.bounds.right() + 6, toggle.bounds.center_y() - 4),
+            );
+        }
+    }
+
+    fn draw_slots(&self) {
+        rect!( 
+            fixed = true,
+            xy = self.slot_bounds.xy(),
+            wh = self.slot_bounds.wh(),
+            border_size = 1,
+            border_radius = 2,
+            color = 0x1f122bff,
+            border_color = 0xffffffff,
+        );
+        let infos = SaveSlots::infos();
+        let active = SaveSlots::active();
+        let now = now_millis();
+        for (i, name) in SLOT_NAMES.iter().enumerate() {
+            let x = self.slot_bounds.x() + 6;
+            let y = self.slot_bounds.y() + 4 + i as i32 * 40;
+            let marker = if active == Some(i) { " *" } else { "" };
+            text!("{}{}", name, marker; fixed = true, xy = (x, y));
+            let info = match &infos[i] {
+                Some(info) => format!("P{}  {}  {} ago", info.prestige, info.resources, Numbers::format_duration(now.saturating_sub(info.saved_at) / 1000)),
+                None => "EMPTY".to_string(),
+            };
+            text!(&info, fixed = true, xy = (x, y + 10), color = 0x847e87ff);
+            for button in self.slot_buttons[i].iter() {
+                button.draw();
+            }
+        }
+    }
+
+    // There is no text box to select from or type into, the string is shown for checking and moves
+    // through the clipboard
+    fn draw_transfer(&self, transfer: &Transfer) {
+        rect!( 
+            fixed = true,
+            xy = self.transfer_bounds.xy(),
+            wh = self.transfer_bounds.wh(),
+            border_size = 1,
+            border_radius = 2,
+            color = 0x1f122bff,
+            border_color = 0xffffffff,
+        );
+        let (x, y) = (self.transfer_bounds.x() + 6, self.transfer_bounds.y() + 6);
+        let (title, hint, text) = match transfer {
+            Transfer::ExportSave(text) => ("SAVE STRING", "COPIED TO THE CLIPBOARD", text.clone()),
+            Transfer::ImportSave => ("IMPORT SAVE", "PRESS CTRL+V TO PASTE, THEN LOAD", Clipboard::paste()),
+            Transfer::ExportReplay(text) => ("RECORDING", "COPIED TO THE CLIPBOARD", text.clone()),
+            Transfer::ImportReplay => ("PLAY REPLAY", "PRESS CTRL+V TO PASTE, THEN PLAY", Clipboard::paste()),
+        };
+        text!(title, fixed = true, xy = (x, y));
+        text!(hint, fixed = true, xy = (x, y + 12), color = 0x847e87ff);
+        let lines = Global::preview(&text);
+        if lines.is_empty() {
+            text!("NOTHING PASTED YET", fixed = true, xy = (x, y + 30), font = "small", color = 0x847e87ff);
+        }
+        for (i, line) in lines.iter().enumerate() {
+            text!(line, fixed = true, xy = (x, y + 30 + i as i32 * 10), font = "small");
+        }
+        text!("{} CHARACTERS", text.trim().chars().count(); fixed = true, xy = (x, self.transfer_bounds.bottom() - 18), color = 0x847e87ff);
+        match transfer {
+            Transfer::ExportSave(_) | Transfer::ExportReplay(_) => self.copy_button.draw(),
+            Transfer::ImportSave | Transfer::ImportReplay => self.load_button.draw(),
+        }
+    }
+
+    // The start of a string cut into lines for the transfer panel, the last one ends in ... when the
+    // string goes on
+    fn preview(text: &str) -> Vec<String> {
+        let chars: Vec<char> = text.trim().chars().filter(|c| !c.is_control()).collect();
+        let mut lines: Vec<String> = chars.chunks(TRANSFER_COLUMNS).take(TRANSFER_LINES).map(|line| line.iter().collect()).collect();
+        if let Some(last) = lines.last_mut().filter(|_| chars.len() > TRANSFER_COLUMNS * TRANSFER_LINES) {
+            last.truncate(TRANSFER_COLUMNS - 3);
+            last.push_str("...");
+        }
+        lines
+    }
+
+    fn draw_backups(&self) {
+        rect!( 
+            fixed = true,
+            xy = self.backup_bounds.xy(),
+            wh = self.backup_bounds.wh(),
+            border_size = 1,
+            border_radius = 2,
+            color = 0x1f122bff,
+            border_color = 0xffffffff,
+        );
+        let autosaves = Autosaves::infos();
+        if autosaves.is_empty() {
+            text!("NO AUTOSAVES YET", fixed = true, xy = (self.backup_bounds.x() + 6, self.backup_bounds.y() + 8));
+        }
+        let now = now_millis();
+        for (i, autosave) in autosaves.iter().enumerate() {
+            let y = self.backup_bounds.y() + 8 + i as i32 * 20;
+            let info = format!("{} ago  P{}  {}", Numbers::format_duration(now.saturating_sub(autosave.saved_at) / 1000), autosave.prestige, autosave.resources);
+            text!(&info, fixed = true, xy = (self.backup_bounds.x() + 6, y));
+            self.backup_buttons[i].draw();
         }
     }
 }
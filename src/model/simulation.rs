@@ -0,0 +1,586 @@
+use super::*;
+use serde::Deserialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, BorshDeserialize, BorshSerialize, Deserialize)]
+pub enum StationId {
+    Probe,
+    Exoplanet,
+    DroneDepot,
+    Fabricator,
+    AsteroidMines,
+    PowerPlant,
+    Jumpgate,
+    ResearchComplex,
+}
+
+impl StationId {
+    pub const ALL: [StationId; 8] = [
+        StationId::Probe,
+        StationId::Exoplanet,
+        StationId::DroneDepot,
+        StationId::Fabricator,
+        StationId::AsteroidMines,
+        StationId::PowerPlant,
+        StationId::Jumpgate,
+        StationId::ResearchComplex,
+    ];
+
+    // Position in ALL
+    pub fn index(&self) -> usize {
+        *self as usize
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            StationId::Probe => "PROBE",
+            StationId::Exoplanet => "EXOPLANET",
+            StationId::DroneDepot => "DRONE DEPOT",
+            StationId::Fabricator => "FABRICATOR",
+            StationId::AsteroidMines => "MINES",
+            StationId::PowerPlant => "POWER PLANT",
+            StationId::Jumpgate => "JUMPGATE",
+            StationId::ResearchComplex => "RESEARCH",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, BorshDeserialize, BorshSerialize)]
+pub enum Order {
+    // Index into the station's available upgrades
+    Upgrade(usize),
+    Unassign,
+    // Pack stations have no StationId, so the order names the station by index into CONTENT.stations
+    // along with the upgrade
+    PackUpgrade(usize, usize),
+}
+
+#[derive(Debug, Clone, PartialEq, BorshDeserialize, BorshSerialize)]
+pub struct Command {
+    pub station: StationId,
+    pub order: Order,
+}
+
+// Everything the player asked for during one tick
+#[derive(Debug, Clone, Default, PartialEq, BorshDeserialize, BorshSerialize)]
+pub struct SimInput {
+    pub scanning: bool,
+    pub commands: Vec<Command>,
+}
+
+impl SimInput {
+    pub fn push(&mut self, station: StationId, order: Option<Order>) {
+        if let Some(order) = order {
+            self.commands.push(Command { station, order });
+        }
+    }
+
+    // Orders from a pack station's pop up, the command's station is left at the probe
+    pub fn push_pack(&mut self, station: usize, order: Option<Order>) {
+        let order = order.and_then(|order| match order {
+            Order::Upgrade(index) => Some(Order::PackUpgrade(station, index)),
+            _ => None,
+        });
+        self.push(StationId::Probe, order);
+    }
+}
+
+// The economy of a run, advanced one tick at a time without pointer, clock or drawing
+#[derive(Debug, Clone, PartialEq, BorshDeserialize, BorshSerialize)]
+pub struct Simulation {
+    pub tick: usize,
+    pub player: Player,
+    pub event_manager: EventManager,
+    pub exoplanet: Exoplanet,
+    pub drone_depot: DroneDepot,
+    pub asteroid_mines: AsteroidMines,
+    pub power_plant: PowerPlant,
+    pub jumpgate: Jumpgate,
+    pub research_complex: ResearchComplex,
+    pub packs: Packs,
+    pub rng: GameRng,
+    pub sector: Sector,
+    pub research: Research,
+    pub achievements: Achievements,
+    pub stats: Stats,
+    // Achievements unlocked since the toasts last looked, by index into ACHIEVEMENTS
+    pub unlocked: Vec<usize>,
+    // Purchases turned down during the last step, for the UI to show
+    pub rejected: Vec<Rejection>,
+}
+
+// A Simulation borrowed apart, so the stations can update next to what they read
+pub struct Split<'a> {
+    pub player: &'a mut Player,
+    pub event_manager: &'a mut EventManager,
+    pub research: &'a Research,
+    pub sector: &'a Sector,
+    // The cosmetic stream, updating and drawing must not move the simulation's
+    pub rng: &'a mut Rng,
+    pub stations: Vec<&'a mut dyn POI>,
+}
+
+// The built-in stations borrowed apart, with the gameplay stream
+pub struct Registry<'a> {
+    pub event_manager: &'a mut EventManager,
+    pub rng: &'a mut Rng,
+    pub sector: &'a Sector,
+    pub stations: Vec<&'a mut dyn POI>,
+}
+
+impl Simulation {
+    // A run from its seed, with the stations placed in the sector
+    pub fn new(seed: u64, sector: Sector, player: Player) -> Self {
+        Simulation {
+            tick: 0,
+            player,
+            event_manager: EventManager::new(&sector),
+            exoplanet: Exoplanet::load(&sector),
+            drone_depot: DroneDepot::load(&sector),
+            asteroid_mines: AsteroidMines::load(&sector),
+            power_plant: PowerPlant::load(&sector),
+            jumpgate: Jumpgate::load(&sector),
+            research_complex: ResearchComplex::load(&sector),
+            packs: Packs::new(),
+            rng: GameRng::new(seed),
+            sector,
+            research: Research::new(),
+            achievements: Achievements::new(),
+            stats: Stats::new(),
+            unlocked: vec![],
+            rejected: vec![],
+        }
+    }
+
+    // The run after a jump, in a sector rolled from its seed. PRESTIGE, the prestige tree, achievements
+    // and stats carry over, kept levels and head starts are granted
+    pub fn next_run(&self, seed: u64) -> Simulation {
+        let player = &self.player;
+        let leftover = player.resources.amount(&Resources::Prestige);
+        let player = Player::load(true, leftover + player.prestige_earned, player.prestige_prog, player.prestige_index, player.avail_upgrades.clone());
+        let mut sim = Simulation::new(seed, Sector::generate(player.prestige_index, seed), player);
+        sim.achievements = self.achievements.clone();
+        sim.stats = self.stats.clone();
+        sim.head_start(&self.kept_levels());
+        sim
+    }
+
+    // Advance the economy by one tick, returns the events handled this tick
+    pub fn step(&mut self, input: &SimInput) -> Vec<Event> {
+        self.tick += 1;
+        self.stats.tick();
+
+        self.rejected.clear();
+        for command in input.commands.iter() {
+            if let Err(rejection) = self.apply(command) {
+                self.rejected.push(rejection);
+            }
+        }
+
+        let scanning = input.scanning || self.player.auto_scan();
+        let researched = self.exoplanet.simulate(self.tick, scanning, &mut self.stats);
+        if let Some(scanned) = self.exoplanet.scanned {
+            self.collect_from((Resources::Research, scanned), Source::Manual);
+        }
+        self.collect((Resources::Research, researched));
+        if self.asteroid_mines.unlockable {
+            let mined = self.asteroid_mines.simulate(&mut self.stats);
+            if let Some(metals) = mined {
+                self.collect((Resources::Metals, metals));
+            }
+        }
+        if self.drone_depot.unlockable {
+            let fabricated = self.drone_depot.simulate(&mut self.player);
+            if let Some(drones) = fabricated {
+                self.collect((Resources::Drones, drones));
+            }
+        }
+        if self.power_plant.unlockable {
+            let harvested = self.power_plant.simulate(&mut self.stats);
+            if let Some(power) = harvested {
+                self.collect((Resources::Power, power));
+            }
+        }
+        if self.jumpgate.unlockable {
+            self.jumpgate.simulate(&self.player);
+        }
+        for resource in self.packs.simulate() {
+            self.collect(resource);
+        }
+        self.player.simulate();
+
+        // Projects finished this tick apply and tell everyone at once, without waiting on cutscenes
+        let mut events = vec![];
+        for project in self.research.advance(self.research_complex.drones.len(), 1) {
+            if let Some(event) = self.complete(&project) {
+                self.handle_event(&event);
+                events.push(event);
+            }
+        }
+        if let Some(event) = self.event_manager.poll(&self.sector) {
+            self.handle_event(&event);
+            events.push(event);
+        }
+        self.unlocked.extend(self.achievements.unlock());
+        events
+    }
+
+    // Production from drones and stations
+    fn collect(&mut self, resource: (Resources, BigNum)) {
+        self.collect_from(resource, Source::Automated);
+    }
+
+    pub fn collect_from(&mut self, mut resource: (Resources, BigNum), source: Source) {
+        if source == Source::Automated {
+            resource.1 = self.boosted(resource.1, &resource.0);
+        }
+        self.achievements.collected(&resource, source);
+        self.player.deposit(resource, source);
+    }
+
+    // Multiplier on automated production of a resource, from the prestige tree and finished techs
+    pub fn boost(&self, resource: &Resources) -> f64 {
+        self.player.boost(resource) + self.research.boost(resource)
+    }
+
+    pub fn boosted(&self, amount: BigNum, resource: &Resources) -> BigNum {
+        let boost = self.boost(resource);
+        if boost == 1. { amount } else { amount.mul_f64(boost).floor() }
+    }
+
+    // Achievements unlocked since the last call, for the toasts
+    pub fn take_unlocked(&mut self) -> Vec<usize> {
+        std::mem::take(&mut self.unlocked)
+    }
+
+    // Carry out a command, returns why a purchase was turned down
+    pub fn apply(&mut self, command: &Command) -> Result<(), Rejection> {
+        match command.order {
+            Order::Upgrade(index) => self.purchase(command.station, index)?,
+            Order::PackUpgrade(station, index) => {
+                let bought = self.packs.purchase(station, index, &mut self.player, &mut self.event_manager, &mut self.rng.gameplay, &self.sector).transpose()?;
+                if let Some(upgrade) = bought {
+                    self.reward(&upgrade);
+                    self.stats.bought(&upgrade);
+                }
+            }
+            Order::Unassign => {
+                let Registry { event_manager, rng, sector, mut stations } = self.registry_mut();
+                if let Some(station) = stations.iter_mut().find(|station| station.ids().contains(&command.station)) {
+                    station.upgrade(&UNASSGIN, event_manager, rng, sector);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // Buy at once, or pay now and queue the upgrade when it is a project. Orders for upgrades no longer
+    // on offer are dropped
+    pub fn purchase(&mut self, station: StationId, index: usize) -> Result<(), Rejection> {
+        let Some(upgrade) = self.offers(station).get(index).filter(|upgrade| upgrade.level < upgrade.max_level) else {
+            return Ok(());
+        };
+        if Research::duration(upgrade).is_none() {
+            return Ok(self.buy(station, index)?);
+        }
+        self.research.check(station, upgrade)?;
+        let upgrade = upgrade.clone();
+        self.player.spend(&upgrade.cost)?;
+        self.research.enqueue(station, &upgrade);
+        Ok(())
+    }
+
+    fn buy(&mut self, station: StationId, index: usize) -> Result<(), Shortfall> {
+        let cost = self.offers(station)[index].cost.clone();
+        self.player.spend(&cost)?;
+        let Registry { event_manager, rng, sector, mut stations } = self.registry_mut();
+        let Some(upgrade) = stations.iter_mut()
+            .find(|listed| listed.ids().contains(&station))
+            .and_then(|listed| listed.advance(station, index, event_manager, rng, sector)) else {
+            return Ok(());
+        };
+        self.reward(&upgrade);
+        self.achievements.bought(station, &upgrade);
+        self.stats.bought(&upgrade);
+        Ok(())
+    }
+
+    // Resources an upgrade just bought hands out
+    fn reward(&mut self, upgrade: &Upgrade) {
+        for effect in upgrade.effects.iter() {
+            if let UpgradeEffect::Grant(resource, amount) = effect {
+                self.collect_from((resource.clone(), BigNum::from(*amount)), Source::Manual);
+            }
+        }
+    }
+
+    // Buy the upgrade with this id at a station for free, projects included, returns false when the
+    // station does not offer it
+    pub fn grant(&mut self, station: StationId, id: usize) -> bool {
+        let Some(index) = self.offers(station).iter().position(|upgrade| upgrade.id == id && upgrade.level < upgrade.max_level) else {
+            return false;
+        };
+        self.waive(station, index);
+        self.buy(station, index).is_ok()
+    }
+
+    // Clear the cost of the upgrade at index on a station's list so buying it leaves the wallet alone,
+    // next_level prices the level after it again
+    fn waive(&mut self, station: StationId, index: usize) {
+        if let Some(upgrades) = self.station_mut(station).and_then(|listed| listed.offers_mut(station)) {
+            upgrades[index].cost = vec![];
+        }
+    }
+
+    // Apply a finished project, it was paid for when queued. When the station no longer offers it the
+    // cost is given back and there is no event
+    fn complete(&mut self, project: &Project) -> Option<Event> {
+        if !self.grant(project.station, project.id) {
+            self.player.refund(&project.cost);
+            return None;
+        }
+        Some(Event::ProjectComplete(project.station, project.id))
+    }
+
+    // Levels bought this run of the upgrades the prestige tree keeps, read before a jump
+    pub fn kept_levels(&self) -> Vec<(StationId, usize, u32)> {
+        self.player.kept().into_iter().map(|(station, id)| {
+            let level = self.offers(station).iter().find(|upgrade| upgrade.id == id).map_or(0, |upgrade| upgrade.level);
+            (station, id, level)
+        }).collect()
+    }
+
+    // Free levels from the prestige tree at the start of a run, granted in passes since one grant can
+    // put the next on offer, their events are handled on the spot without cutscenes and none of it
+    // counts for achievements or stats
+    pub fn head_start(&mut self, kept: &[(StationId, usize, u32)]) {
+        let mut grants = self.player.head_starts();
+        grants.extend_from_slice(kept);
+        let (achievements, stats) = (self.achievements.clone(), self.stats.clone());
+        let mut granted = true;
+        while granted {
+            granted = false;
+            for (station, id, levels) in grants.iter_mut() {
+                while *levels > 0 && self.grant(*station, *id) {
+                    *levels -= 1;
+                    granted = true;
+                    for event in self.event_manager.drain() {
+                        self.handle_event(&event);
+                    }
+                }
+            }
+        }
+        self.achievements = achievements;
+        self.stats = stats;
+    }
+
+    // Upgrades on offer at a station, empty where the UI would not show any
+    pub fn offers(&self, station: StationId) -> &[Upgrade] {
+        self.station(station).filter(|listed| listed.unlockable()).map_or(&[], |listed| listed.offers(station))
+    }
+
+    // The built-in stations, the probe's upgrades included, each listed once and found by the StationIds
+    // in its ids. A new station only needs adding here and to registry_mut to be bought from and saved
+    pub fn registry(&self) -> Vec<&dyn POI> {
+        vec![
+            &self.player,
+            &self.exoplanet,
+            &self.drone_depot,
+            &self.asteroid_mines,
+            &self.power_plant,
+            &self.jumpgate,
+            &self.research_complex,
+        ]
+    }
+
+    // The registry next to what buying from it moves
+    pub fn registry_mut(&mut self) -> Registry<'_> {
+        Registry {
+            event_manager: &mut self.event_manager,
+            rng: &mut self.rng.gameplay,
+            sector: &self.sector,
+            stations: vec![
+                &mut self.player,
+                &mut self.exoplanet,
+                &mut self.drone_depot,
+                &mut self.asteroid_mines,
+                &mut self.power_plant,
+                &mut self.jumpgate,
+                &mut self.research_complex,
+            ],
+        }
+    }
+
+    pub fn station(&self, station: StationId) -> Option<&dyn POI> {
+        self.registry().into_iter().find(|listed| listed.ids().contains(&station))
+    }
+
+    pub fn station_mut(&mut self, station: StationId) -> Option<&mut dyn POI> {
+        self.registry_mut().stations.into_iter().find(|listed| listed.ids().contains(&station))
+    }
+
+    pub fn handle_event(&mut self, event: &Event) {
+        self.achievements.handle_event(event);
+        self.stats.handle_event(event);
+        self.player.handle_event(event);
+        for station in self.packs.unlocked_by(event) {
+            self.event_manager.trigger(Event::PackUnlockable(station));
+        }
+        for station in self.stations_mut() {
+            station.handle_event(event);
+        }
+    }
+
+    // Every station in drawing order, next to what they read while updating, a new station only
+    // needs adding here to be updated, drawn and told about events. Pack stations come last
+    pub fn split_stations(&mut self) -> Split<'_> {
+        let mut stations: Vec<&mut dyn POI> = vec![
+            &mut self.exoplanet,
+            &mut self.asteroid_mines,
+            &mut self.drone_depot,
+            &mut self.power_plant,
+            &mut self.jumpgate,
+            &mut self.research_complex,
+        ];
+        stations.extend(self.packs.stations.iter_mut().map(|station| station as &mut dyn POI));
+        Split {
+            player: &mut self.player,
+            event_manager: &mut self.event_manager,
+            research: &self.research,
+            sector: &self.sector,
+            rng: &mut self.rng.cosmetic,
+            stations,
+        }
+    }
+
+    pub fn stations_mut(&mut self) -> Vec<&mut dyn POI> {
+        self.split_stations().stations
+    }
+
+    // Credit secs of unattended production, recording the totals in report
+    pub fn idle(&mut self, secs: f32, report: &mut AwayReport) {
+        let produced = vec![
+            (Resources::Research, BigNum::from_f64((self.exoplanet.idle_rate() * secs) as f64).floor()),
+            (Resources::Metals, BigNum::from_f64((self.asteroid_mines.idle_rate() * secs) as f64).floor()),
+            (Resources::Power, BigNum::from_f64((self.power_plant.idle_rate() * secs) as f64).floor()),
+        ];
+        for mut resource in produced.into_iter().chain(self.packs.idle(secs)) {
+            resource.1 = self.boosted(resource.1, &resource.0);
+            self.collect_from(resource.clone(), Source::Offline);
+            report.gain(resource);
+        }
+
+        // Shipping drones can only take the METALS on hand, so the spend always goes through
+        let shipped = BigNum::from_f64((self.drone_depot.idle_rate() * secs) as f64).floor().min(self.player.amount(&Resources::Metals));
+        let _ = self.player.spend(&[(Resources::Metals, shipped)]);
+        report.spend((Resources::Metals, shipped));
+        let fabricated = self.drone_depot.fabricate(shipped);
+        let fabricated = self.boosted(fabricated, &Resources::Drones);
+        self.collect_from((Resources::Drones, fabricated), Source::Offline);
+        report.gain((Resources::Drones, fabricated));
+
+        // Queued projects kept going too
+        for project in self.research.advance(self.research_complex.drones.len(), (secs * 60.) as u64) {
+            if let Some(event) = self.complete(&project) {
+                self.handle_event(&event);
+                report.complete(project.name());
+            }
+        }
+        self.unlocked.extend(self.achievements.unlock());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Everything a run does follows from its seed and input, the auto buyer's trial purchases included
+    #[test]
+    fn step_is_deterministic() {
+        let run = || {
+            let mut sim = Simulation::new(42, Sector::generate(1, 42), Player::default());
+            let mut buyer = AutoBuyer { unlocked: true, policy: BuyPolicy::BestRoi, enabled: vec![true; StationId::ALL.len()], ..AutoBuyer::new() };
+            for _ in 0..20_000 {
+                sim.event_manager.skip_dialogue();
+                let mut input = SimInput { scanning: true, commands: vec![] };
+                buyer.update(&sim, &mut input);
+                sim.step(&input);
+            }
+            sim
+        };
+        let sim = run();
+        assert!(!sim.drone_depot.drones.is_empty());
+        assert_eq!(sim, run());
+    }
+
+    // Granted levels are free, they must not show up in the wallet's totals
+    #[test]
+    fn grant_leaves_the_wallet_alone() {
+        let mut sim = Simulation::new(0, Sector::home(), Player::default());
+        let wallet = sim.player.resources.clone();
+        // FIELD SCANNER, 15 RESEARCH doubling each level
+        let scanner = sim.offers(StationId::Exoplanet)[0].id;
+        assert!(sim.grant(StationId::Exoplanet, scanner));
+        assert_eq!(sim.player.resources, wallet);
+        assert_eq!(sim.offers(StationId::Exoplanet)[0].cost, vec![(Resources::Research, BigNum::from(30u64))]);
+    }
+
+    // Free levels at the start of a run are not the player's doing
+    #[test]
+    fn head_start_is_not_counted() {
+        let mut sim = Simulation::new(0, Sector::home(), Player::default());
+        let (achievements, stats) = (sim.achievements.clone(), sim.stats.clone());
+        let scanner = sim.offers(StationId::Exoplanet)[0].id;
+        sim.head_start(&[(StationId::Exoplanet, scanner, 3)]);
+        assert_eq!(sim.offers(StationId::Exoplanet)[0].level, 3);
+        assert_eq!(sim.achievements, achievements);
+        assert_eq!(sim.stats, stats);
+    }
+
+    // Projects are paid for once and given back when they can no longer apply
+    #[test]
+    fn projects_reject_and_refund() {
+        let mut sim = Simulation::new(0, Sector::home(), Player::default());
+        sim.handle_event(&Event::ComplexUnlockable);
+        // CONSTRUCT, a 60 second project
+        let wallet = sim.player.resources.clone();
+        assert_eq!(sim.purchase(StationId::ResearchComplex, 0), Ok(()));
+        assert_ne!(sim.player.resources, wallet);
+        assert_eq!(sim.purchase(StationId::ResearchComplex, 0), Err(Rejection::Queued(StationId::ResearchComplex, "CONSTRUCT".to_string())));
+
+        if let Some(upgrades) = sim.station_mut(StationId::ResearchComplex).and_then(|listed| listed.offers_mut(StationId::ResearchComplex)) {
+            upgrades.clear();
+        }
+        let finished = sim.research.advance(0, 60 * 60);
+        assert_eq!(finished.len(), 1);
+        assert_eq!(sim.complete(&finished[0]), None);
+        for (resource, balance) in wallet.iter() {
+            let refunded = sim.player.resources.balance(resource);
+            assert_eq!((refunded.amount, refunded.spent), (balance.amount, balance.spent));
+        }
+    }
+
+    // Catching up on an absence credits what the same time left running would have
+    #[test]
+    fn idle_matches_live_stepping() {
+        let mut sim = Simulation::new(42, Sector::home(), Player::default());
+        let mut buyer = AutoBuyer { unlocked: true, policy: BuyPolicy::BestRoi, enabled: vec![true; StationId::ALL.len()], ..AutoBuyer::new() };
+        for _ in 0..15_000 {
+            sim.event_manager.skip_dialogue();
+            let mut input = SimInput { scanning: false, commands: vec![] };
+            buyer.update(&sim, &mut input);
+            sim.step(&input);
+        }
+        let (mut live, mut away) = (sim.clone(), sim.clone());
+        for _ in 0..60 * 600 {
+            live.event_manager.skip_dialogue();
+            live.step(&SimInput { scanning: false, commands: vec![] });
+        }
+        away.idle(600., &mut AwayReport::new(600, 600));
+        // Live production comes in whole cycles, the estimate is spread evenly over the time
+        for resource in [Resources::Research, Resources::Metals, Resources::Power, Resources::Drones] {
+            let gained = |after: &Simulation| (after.player.resources.balance(&resource).earned - sim.player.resources.balance(&resource).earned).to_f64();
+            let (live, away) = (gained(&live), gained(&away));
+            assert!(live > 0. && (away - live).abs() / live < 0.01, "{:?} live {} away {}", resource, live, away);
+        }
+    }
+}
@@ -0,0 +1,127 @@
+use super::*;
+
+// Finished runs kept for the stats panel
+pub const RUN_HISTORY: usize = 10;
+
+#[derive(Debug, Clone, Default, PartialEq, BorshDeserialize, BorshSerialize)]
+pub struct Counters {
+    // Simulation ticks played
+    pub ticks: u64,
+    pub scans: u64,
+    pub drones_deployed: u64,
+    pub asteroids_mined: u64,
+    pub bolts_harvested: u64,
+    pub upgrades_bought: u64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, BorshDeserialize, BorshSerialize)]
+pub struct Stats {
+    // Since the last jump
+    pub run: Counters,
+    // Every run together, kept through prestiges
+    pub lifetime: Counters,
+    pub prestiges: u32,
+    // Ticks each finished run took, newest last
+    pub runs: Vec<u64>,
+    pub fastest_run: Option<u64>,
+}
+
+impl Stats {
+    pub fn new() -> Self {
+        Stats::default()
+    }
+
+    // Add to the run and lifetime counters
+    fn count(&mut self, f: impl Fn(&mut Counters)) {
+        f(&mut self.run);
+        f(&mut self.lifetime);
+    }
+
+    pub fn tick(&mut self) {
+        self.count(|counters| counters.ticks += 1);
+    }
+
+    pub fn scanned(&mut self) {
+        self.count(|counters| counters.scans += 1);
+    }
+
+    pub fn mined(&mut self, asteroids: u64) {
+        self.count(|counters| counters.asteroids_mined += asteroids);
+    }
+
+    pub fn harvested(&mut self, bolts: u64) {
+        self.count(|counters| counters.bolts_harvested += bolts);
+    }
+
+    // Fed by Simulation::purchase with every upgrade bought
+    pub fn bought(&mut self, upgrade: &Upgrade) {
+        let deployed = upgrade.effects.iter().filter(|effect| **effect == UpgradeEffect::AddDrone).count() as u64;
+        self.count(|counters| {
+            counters.upgrades_bought += 1;
+            counters.drones_deployed += deployed;
+        });
+    }
+
+    // A jump closes the run
+    pub fn handle_event(&mut self, event: &Event) {
+        if *event != Event::Prestige {
+            return;
+        }
+        let ticks = self.run.ticks;
+        self.prestiges += 1;
+        self.runs.push(ticks);
+        if self.runs.len() > RUN_HISTORY {
+            self.runs.remove(0);
+        }
+        self.fastest_run = Some(self.fastest_run.map_or(ticks, |fastest| fastest.min(ticks)));
+        self.run = Counters::default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prestige_resets_the_run_only() {
+        let mut stats = Stats::new();
+        for _ in 0..100 {
+            stats.tick();
+        }
+        stats.scanned();
+        stats.mined(3);
+        stats.handle_event(&Event::Prestige);
+        assert_eq!(stats.run, Counters::default());
+        assert_eq!(stats.lifetime, Counters { ticks: 100, scans: 1, asteroids_mined: 3, ..Counters::default() });
+        assert_eq!((stats.prestiges, stats.runs.clone(), stats.fastest_run), (1, vec![100], Some(100)));
+
+        for _ in 0..40 {
+            stats.tick();
+        }
+        stats.handle_event(&Event::Prestige);
+        assert_eq!(stats.lifetime.ticks, 140);
+        assert_eq!((stats.prestiges, stats.runs.clone(), stats.fastest_run), (2, vec![100, 40], Some(40)));
+        for _ in 0..RUN_HISTORY {
+            stats.handle_event(&Event::Prestige);
+        }
+        assert_eq!(stats.runs.len(), RUN_HISTORY);
+    }
+
+    // The jump itself hands the stats to the next run's simulation
+    #[test]
+    fn lifetime_survives_the_jump() {
+        let mut sim = Simulation::new(0, Sector::home(), Player::default());
+        for _ in 0..600 {
+            sim.step(&SimInput { scanning: true, commands: vec![] });
+        }
+        let lifetime = sim.stats.lifetime.clone();
+        assert!(lifetime.scans > 0);
+        sim.handle_event(&Event::Prestige);
+        let mut next = sim.next_run(1);
+        assert_eq!(next.stats.run, Counters::default());
+        assert_eq!(next.stats.lifetime, lifetime);
+        next.step(&SimInput { scanning: false, commands: vec![] });
+        assert_eq!(next.stats.run.ticks, 1);
+        assert_eq!(next.stats.lifetime.ticks, lifetime.ticks + 1);
+    }
+}
@@ -190,6 +190,51 @@ class GamepadManager {
   }
 }
 
+/**************************************************/
+/* CLIPBOARD                                      */
+/**************************************************/
+
+// The game has no text input, save and replay strings go through the clipboard.
+// Copies use the Clipboard API, pastes are caught from the page's paste events.
+const clipboard = {
+  // The game's memory, set once its module is instantiated
+  memory: null,
+  // Text of the last paste into the page
+  pasted: "",
+  imports: {
+    // Copy len bytes of UTF-8 text at ptr to the clipboard
+    clipboard_write(ptr, len) {
+      const bytes = new Uint8Array(clipboard.memory.buffer, ptr, len);
+      const text = new TextDecoder().decode(bytes);
+      navigator.clipboard
+        ?.writeText(text)
+        .catch((err) => console.warn("Could not copy to the clipboard", err));
+    },
+    // Copy the last pasted text to ptr if it fits in cap bytes, returns its length either way
+    clipboard_read(ptr, cap) {
+      const bytes = new TextEncoder().encode(clipboard.pasted);
+      if (bytes.length <= cap) {
+        new Uint8Array(clipboard.memory.buffer, ptr, bytes.length).set(bytes);
+      }
+      return bytes.length;
+    },
+  },
+};
+
+window.addEventListener("paste", (event) => {
+  clipboard.pasted = event.clipboardData?.getData("text") ?? "";
+});
+
+// The runtime instantiates the game itself, keep the memory of the module given the clipboard
+const instantiate = WebAssembly.instantiate;
+WebAssembly.instantiate = async (source, imports) => {
+  const result = await instantiate(source, imports);
+  if (imports?.clipboard === clipboard.imports) {
+    clipboard.memory = (result.instance ?? result).exports.memory;
+  }
+  return result;
+};
+
 /**************************************************/
 /* WASM IMPORT PROXY                              */
 /**************************************************/
@@ -198,6 +243,7 @@ class GamepadManager {
 // prettier-ignore
 window.createWasmImportsProxy = (target = {}) => {
   console.log(target);
+  target.clipboard = target.clipboard ?? clipboard.imports;
   return new Proxy(target, {
     get: (target, namespace) => {
       // Stub each undefined namespace with a Proxy